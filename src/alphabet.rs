@@ -0,0 +1,232 @@
+//! Alphabets of symbols that sequences are packed from.
+//!
+//! [`Sequence`](crate::Sequence) and [`growable::Kmer`](crate::growable::Kmer)
+//! store each symbol in a fixed number of bits, given by an [`Alphabet`].
+//! Both default to [`Dna`], whose symbols are [`Base`]s, and most of their
+//! methods, such as iterating over k-mers, are only for DNA. Other alphabets
+//! can be stored and accessed symbol by symbol.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::alphabet::Protein;
+//! use helicase::translate::AminoAcid;
+//! use helicase::Sequence;
+//!
+//! let mut protein = Sequence::<u64, Protein>::parse_ascii(b"MKV*").unwrap();
+//! assert_eq!(protein.get(1), Some(AminoAcid::Lys));
+//! protein.push(AminoAcid::Trp);
+//! assert_eq!(protein.to_ascii(), b"MKV*W");
+//! ```
+
+use core::fmt::{self, Debug, Display};
+
+use crate::Base;
+use crate::translate::AminoAcid;
+
+/// An alphabet of symbols, each encoded in a fixed number of bits.
+///
+/// # Examples
+///
+/// A reduced alphabet, that only distinguishes purines from pyrimidines:
+///
+/// ```
+/// use helicase::alphabet::Alphabet;
+/// use helicase::Sequence;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Purine {
+///     R,
+///     Y,
+/// }
+///
+/// struct Ry;
+///
+/// impl Alphabet for Ry {
+///     type Symbol = Purine;
+///     const BITS: usize = 1;
+///
+///     fn encode(symbol: Purine) -> u8 {
+///         symbol as u8
+///     }
+///
+///     fn decode(code: u8) -> Purine {
+///         if code == 0 { Purine::R } else { Purine::Y }
+///     }
+///
+///     fn from_ascii(byte: u8) -> Option<Purine> {
+///         match byte.to_ascii_uppercase() {
+///             b'A' | b'G' | b'R' => Some(Purine::R),
+///             b'C' | b'T' | b'Y' => Some(Purine::Y),
+///             _ => None,
+///         }
+///     }
+///
+///     fn to_ascii(symbol: Purine) -> u8 {
+///         match symbol {
+///             Purine::R => b'R',
+///             Purine::Y => b'Y',
+///         }
+///     }
+/// }
+///
+/// let seq = Sequence::<u8, Ry>::parse_ascii(b"GATTACA").unwrap();
+/// assert_eq!(seq.to_ascii(), b"RRYYRYR");
+/// assert_eq!(seq.as_raw_slice(), [0b0010_1100]);
+/// ```
+pub trait Alphabet {
+    /// A symbol of the alphabet.
+    type Symbol: Copy + Eq + Debug;
+
+    /// The number of bits that each symbol is encoded in, at most 8.
+    const BITS: usize;
+
+    /// Returns the code of a symbol, which fits in [`BITS`](Self::BITS)
+    /// bits.
+    fn encode(symbol: Self::Symbol) -> u8;
+
+    /// Returns the symbol with a code returned by [`encode`](Self::encode).
+    ///
+    /// May panic, or return any symbol, for other codes.
+    fn decode(code: u8) -> Self::Symbol;
+
+    /// Parses a symbol from an ASCII character, or returns `None` if the
+    /// character is not a symbol.
+    fn from_ascii(byte: u8) -> Option<Self::Symbol>;
+
+    /// Returns the ASCII character of a symbol.
+    fn to_ascii(symbol: Self::Symbol) -> u8;
+
+    /// Returns the complement of a symbol, or `None` if the alphabet has no
+    /// complement.
+    fn complement(symbol: Self::Symbol) -> Option<Self::Symbol> {
+        let _ = symbol;
+        None
+    }
+}
+
+/// The alphabet of DNA bases, encoded in 2 bits as `C = 0`, `A = 1`, `T = 2`
+/// and `G = 3`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Dna;
+
+impl Alphabet for Dna {
+    type Symbol = Base;
+    const BITS: usize = 2;
+
+    fn encode(symbol: Base) -> u8 {
+        symbol as u8
+    }
+
+    fn decode(code: u8) -> Base {
+        // SAFETY: masked to 2 bits, and every 2 bit code is a base.
+        unsafe { Base::from_u8_unchecked(code & 0b11) }
+    }
+
+    fn from_ascii(byte: u8) -> Option<Base> {
+        Base::from_ascii(byte)
+    }
+
+    fn to_ascii(symbol: Base) -> u8 {
+        symbol.to_ascii()
+    }
+
+    fn complement(symbol: Base) -> Option<Base> {
+        Some(symbol.complement())
+    }
+}
+
+/// The alphabet of the 20 standard amino acids and the stop symbol, encoded
+/// in 5 bits in the order of [`AminoAcid`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Protein;
+
+impl Protein {
+    /// Every amino acid, indexed by code.
+    const SYMBOLS: [AminoAcid; 21] = {
+        use AminoAcid::*;
+        [
+            Ala, Arg, Asn, Asp, Cys, Gln, Glu, Gly, His, Ile, Leu, Lys, Met, Phe, Pro, Ser, Thr,
+            Trp, Tyr, Val, Stop,
+        ]
+    };
+}
+
+impl Alphabet for Protein {
+    type Symbol = AminoAcid;
+    const BITS: usize = 5;
+
+    fn encode(symbol: AminoAcid) -> u8 {
+        symbol as u8
+    }
+
+    fn decode(code: u8) -> AminoAcid {
+        Self::SYMBOLS[code as usize]
+    }
+
+    fn from_ascii(byte: u8) -> Option<AminoAcid> {
+        AminoAcid::from_ascii(byte)
+    }
+
+    fn to_ascii(symbol: AminoAcid) -> u8 {
+        symbol.to_ascii()
+    }
+}
+
+/// An error returned when parsing the symbols of an [`Alphabet`] from ASCII
+/// text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseSymbolError {
+    position: usize,
+    byte: u8,
+}
+
+impl ParseSymbolError {
+    #[cfg(feature = "bitvec")]
+    pub(crate) const fn new(position: usize, byte: u8) -> Self {
+        Self { position, byte }
+    }
+
+    /// Returns the byte offset of the invalid character in the input.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the invalid character.
+    pub const fn byte(&self) -> u8 {
+        self.byte
+    }
+}
+
+impl Display for ParseSymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid symbol '{}' at byte {}",
+            self.byte.escape_ascii(),
+            self.position
+        )
+    }
+}
+
+impl core::error::Error for ParseSymbolError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for (code, &amino) in Protein::SYMBOLS.iter().enumerate() {
+            assert_eq!(Protein::encode(amino), code as u8);
+            assert_eq!(Protein::decode(code as u8), amino);
+            assert_eq!(Protein::from_ascii(Protein::to_ascii(amino)), Some(amino));
+            assert_eq!(Protein::complement(amino), None);
+        }
+        for base in [Base::C, Base::A, Base::T, Base::G] {
+            assert_eq!(Dna::decode(Dna::encode(base)), base);
+            assert_eq!(Dna::complement(base), Some(base.complement()));
+        }
+        assert_eq!(Dna::from_ascii(b'N'), None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 
@@ -15,16 +15,84 @@ pub enum Base {
 }
 
 impl Display for Base {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Base::C => write!(f, "C"),
-            Base::A => write!(f, "A"),
-            Base::T => write!(f, "T"),
-            Base::G => write!(f, "G"),
-        }
+    /// Formats the base as an uppercase letter, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_bases(f, core::iter::once(*self))
     }
 }
 
+/// The strand a k-mer was read from, relative to its canonical form.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Strand {
+    /// The k-mer is its own canonical form.
+    Forward,
+    /// The canonical form is the reverse complement of the k-mer.
+    Reverse,
+}
+
+/// Writes bases as ASCII, in uppercase unless the alternate flag (`{:#}`) is
+/// set, in which case they are written in lowercase.
+pub(crate) fn write_bases(
+    f: &mut core::fmt::Formatter<'_>,
+    bases: impl IntoIterator<Item = Base>,
+) -> core::fmt::Result {
+    let lowercase = f.alternate();
+    for base in bases {
+        let c = if lowercase {
+            base.to_ascii().to_ascii_lowercase()
+        } else {
+            base.to_ascii()
+        };
+        core::fmt::Write::write_char(f, c as char)?;
+    }
+    Ok(())
+}
+
+/// An error returned when parsing bases from ASCII text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBaseError {
+    position: usize,
+    byte: u8,
+}
+
+impl ParseBaseError {
+    pub(crate) const fn new(position: usize, byte: u8) -> Self {
+        Self { position, byte }
+    }
+
+    /// Returns the byte offset of the invalid character in the input.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the invalid character.
+    pub const fn byte(&self) -> u8 {
+        self.byte
+    }
+}
+
+impl Display for ParseBaseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "invalid base '{}' at byte {}",
+            self.byte.escape_ascii(),
+            self.position
+        )
+    }
+}
+
+impl core::error::Error for ParseBaseError {}
+
+/// Parses ASCII characters into bases, reporting the position of any invalid
+/// character.
+pub(crate) fn parse_ascii(bytes: &[u8]) -> impl Iterator<Item = Result<Base, ParseBaseError>> + '_ {
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(position, &byte)| Base::from_ascii(byte).ok_or(ParseBaseError::new(position, byte)))
+}
+
 impl TryFrom<u8> for Base {
     type Error = ();
 
@@ -50,7 +118,7 @@ impl Base {
             1 => Base::A,
             2 => Base::T,
             3 => Base::G,
-            _ => unsafe { std::hint::unreachable_unchecked() },
+            _ => unsafe { core::hint::unreachable_unchecked() },
         }
     }
 
@@ -66,6 +134,155 @@ impl Base {
             _ => None,
         }
     }
+
+    /// Returns the Watson-Crick complement of the base.
+    pub const fn complement(self) -> Self {
+        // SAFETY: the result is always in the range `0..4`.
+        unsafe { Base::from_u8_unchecked(self as u8 ^ 0b11) }
+    }
+
+    /// Converts the base to an uppercase ASCII character.
+    pub const fn to_ascii(self) -> u8 {
+        match self {
+            Base::C => b'C',
+            Base::A => b'A',
+            Base::T => b'T',
+            Base::G => b'G',
+        }
+    }
+
+    /// Converts the base to an uppercase ASCII character, using `U` (uracil)
+    /// in place of `T`.
+    pub const fn to_rna_ascii(self) -> u8 {
+        match self {
+            Base::T => b'U',
+            b => b.to_ascii(),
+        }
+    }
+}
+
+/// An IUPAC nucleotide code: a set of bases that may occur at a position.
+///
+/// Codes are stored as a 4-bit mask with `A`, `C`, `G` and `T` in bits 0 to
+/// 3, so `N` is `0b1111` and the gap `-` is `0b0000`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Iupac(u8);
+
+impl Display for Iupac {
+    /// Formats the code as an uppercase letter, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let c = if f.alternate() {
+            self.to_ascii().to_ascii_lowercase()
+        } else {
+            self.to_ascii()
+        };
+        core::fmt::Write::write_char(f, c as char)
+    }
+}
+
+impl From<Base> for Iupac {
+    fn from(base: Base) -> Self {
+        Self::from_base(base)
+    }
+}
+
+impl Iupac {
+    /// Any base (`N`).
+    pub const N: Self = Self(0b1111);
+
+    /// No base (`-`).
+    pub const GAP: Self = Self(0);
+
+    /// Codes by mask, from the gap to `N`.
+    const ASCII: [u8; 16] = *b"-ACMGRSVTWYHKDBN";
+
+    /// Creates a code from a 4-bit mask of `A`, `C`, `G` and `T`.
+    ///
+    /// Returns `None` if the mask has more than 4 bits.
+    pub const fn from_mask(mask: u8) -> Option<Self> {
+        if mask < 16 { Some(Self(mask)) } else { None }
+    }
+
+    /// Returns the code for a single base.
+    pub const fn from_base(base: Base) -> Self {
+        Self(match base {
+            Base::A => 0b0001,
+            Base::C => 0b0010,
+            Base::G => 0b0100,
+            Base::T => 0b1000,
+        })
+    }
+
+    /// Converts an IUPAC character (in either case) to a code, reading `U`
+    /// as `T` and `.` as a gap.
+    ///
+    /// Returns `None` if the character is not an IUPAC code.
+    pub const fn from_ascii(value: u8) -> Option<Self> {
+        let mask = match value.to_ascii_uppercase() {
+            b'-' | b'.' => 0,
+            b'U' => 0b1000,
+            upper => {
+                let mut mask = 0;
+                while mask < 16 {
+                    if Self::ASCII[mask] == upper {
+                        break;
+                    }
+                    mask += 1;
+                }
+                if mask == 16 {
+                    return None;
+                }
+                mask as u8
+            }
+        };
+        Some(Self(mask))
+    }
+
+    /// Converts the code to an uppercase ASCII character.
+    pub const fn to_ascii(self) -> u8 {
+        Self::ASCII[self.0 as usize]
+    }
+
+    /// Returns the 4-bit mask of the bases the code allows.
+    pub const fn mask(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if the code allows `base`.
+    pub const fn contains(self, base: Base) -> bool {
+        self.0 & Self::from_base(base).0 != 0
+    }
+
+    /// Returns the single base the code allows, or `None` if it is
+    /// ambiguous or a gap.
+    pub const fn to_base(self) -> Option<Base> {
+        match self.0 {
+            0b0001 => Some(Base::A),
+            0b0010 => Some(Base::C),
+            0b0100 => Some(Base::G),
+            0b1000 => Some(Base::T),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the code allows more than one base.
+    pub const fn is_ambiguous(self) -> bool {
+        self.0.count_ones() > 1
+    }
+
+    /// Returns the code of the complementary bases.
+    pub const fn complement(self) -> Self {
+        // `A`/`T` and `C`/`G` are at mirrored bits
+        Self(self.0.reverse_bits() >> 4)
+    }
+
+    /// Returns an iterator over the bases the code allows, in the order
+    /// `A`, `C`, `G`, `T`.
+    pub fn bases(self) -> impl Iterator<Item = Base> {
+        [Base::A, Base::C, Base::G, Base::T]
+            .into_iter()
+            .filter(move |&base| self.contains(base))
+    }
 }
 
 #[cfg(test)]
@@ -92,4 +309,64 @@ mod tests {
         assert_eq!(Base::from_ascii(b'g'), Some(Base::G));
         assert_eq!(Base::from_ascii(b'X'), None);
     }
+
+    #[test]
+    fn complement() {
+        assert_eq!(Base::C.complement(), Base::G);
+        assert_eq!(Base::A.complement(), Base::T);
+        assert_eq!(Base::T.complement(), Base::A);
+        assert_eq!(Base::G.complement(), Base::C);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn parse_ascii() {
+        let bases: Result<Vec<Base>, _> = super::parse_ascii(b"GaTc").collect();
+        assert_eq!(bases, Ok(vec![Base::G, Base::A, Base::T, Base::C]));
+
+        let err = super::parse_ascii(b"ACNT")
+            .collect::<Result<Vec<Base>, _>>()
+            .unwrap_err();
+        assert_eq!(err.position(), 2);
+        assert_eq!(err.byte(), b'N');
+        assert_eq!(err.to_string(), "invalid base 'N' at byte 2");
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Base::T.to_string(), "T");
+        assert_eq!(format!("{:#}", Base::G), "g");
+        assert_eq!(Base::T.to_rna_ascii(), b'U');
+        assert_eq!(Base::A.to_rna_ascii(), b'A');
+    }
+
+    #[test]
+    fn iupac() {
+        for c in b"-ACMGRSVTWYHKDBN" {
+            let code = Iupac::from_ascii(*c).unwrap();
+            assert_eq!(code.to_ascii(), *c);
+            assert_eq!(Iupac::from_ascii(c.to_ascii_lowercase()), Some(code));
+        }
+        assert_eq!(Iupac::from_ascii(b'u'), Some(Iupac::from(Base::T)));
+        assert_eq!(Iupac::from_ascii(b'X'), None);
+        assert_eq!(Iupac::from_mask(16), None);
+
+        let r = Iupac::from_ascii(b'R').unwrap();
+        assert!(r.is_ambiguous() && r.contains(Base::A) && r.contains(Base::G));
+        assert!(r.bases().eq([Base::A, Base::G]));
+        assert_eq!(r.complement().to_string(), "Y");
+        assert_eq!(
+            Iupac::from_ascii(b'B').unwrap().complement().to_ascii(),
+            b'V'
+        );
+        assert_eq!(Iupac::N.complement(), Iupac::N);
+        assert_eq!(Iupac::from(Base::C).to_base(), Some(Base::C));
+        assert_eq!(format!("{:#}", Iupac::N), "n");
+        for base in [Base::A, Base::C, Base::G, Base::T] {
+            assert_eq!(
+                Iupac::from(base).complement(),
+                Iupac::from(base.complement())
+            );
+        }
+    }
 }
@@ -0,0 +1,108 @@
+//! Helpers shared by the wasm and Python bindings, which take the k-mer
+//! length as an argument checked at runtime.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::count;
+use crate::small::Kmer;
+
+/// An error for a k-mer length that is not between 1 and 32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InvalidK(pub(crate) usize);
+
+impl fmt::Display for InvalidK {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "k must be between 1 and 32, not {}", self.0)
+    }
+}
+
+impl core::error::Error for InvalidK {}
+
+/// Calls `$body` with the const `$K` set to the runtime value `$k`, or
+/// returns [`InvalidK`] if `$k` is not between 1 and 32.
+macro_rules! with_k {
+    ($k:expr, $K:ident => $body:expr) => {
+        $crate::bindings::with_k!(@arms $k, $K => $body;
+            1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+            17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32)
+    };
+    (@arms $k:expr, $K:ident => $body:expr; $($n:literal)*) => {
+        match $k {
+            $($n => {
+                const $K: usize = $n;
+                Ok::<_, $crate::bindings::InvalidK>($body)
+            })*
+            k => Err($crate::bindings::InvalidK(k)),
+        }
+    };
+}
+
+pub(crate) use with_k;
+
+/// Returns `k` if it is between 1 and 32, or [`InvalidK`] otherwise.
+#[cfg(feature = "python")]
+pub(crate) fn check_k(k: usize) -> Result<usize, InvalidK> {
+    if (1..=32).contains(&k) {
+        Ok(k)
+    } else {
+        Err(InvalidK(k))
+    }
+}
+
+/// A k-mer counter whose `k` is chosen at runtime.
+pub(crate) trait DynCounter: Send + Sync {
+    fn count_sequence(&mut self, seq: &crate::Sequence);
+    /// Returns the count of a k-mer given as ASCII, or `None` if it is not
+    /// a valid k-mer.
+    fn get(&self, kmer: &[u8]) -> Option<u64>;
+    fn len(&self) -> usize;
+    fn total(&self) -> u64;
+    #[cfg(feature = "python")]
+    fn sorted_by_kmer(&self) -> Vec<(u64, u64)>;
+    fn sorted_by_count(&self) -> Vec<(u64, u64)>;
+}
+
+impl<const K: usize> DynCounter for count::KmerCounter<K> {
+    fn count_sequence(&mut self, seq: &crate::Sequence) {
+        self.count_sequence(seq);
+    }
+
+    fn get(&self, kmer: &[u8]) -> Option<u64> {
+        Some(self.get(Kmer::from_ascii(kmer).ok()?))
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn total(&self) -> u64 {
+        self.total()
+    }
+
+    #[cfg(feature = "python")]
+    fn sorted_by_kmer(&self) -> Vec<(u64, u64)> {
+        self.sorted_by_kmer()
+            .into_iter()
+            .map(|(kmer, count)| (kmer.as_masked(), count))
+            .collect()
+    }
+
+    fn sorted_by_count(&self) -> Vec<(u64, u64)> {
+        self.sorted_by_count()
+            .into_iter()
+            .map(|(kmer, count)| (kmer.as_masked(), count))
+            .collect()
+    }
+}
+
+/// Creates an empty counter of `k`-mers, counting each k-mer together with
+/// its reverse complement if `canonical` is `true`.
+pub(crate) fn new_counter(k: usize, canonical: bool) -> Result<Box<dyn DynCounter>, InvalidK> {
+    with_k!(k, K => if canonical {
+        Box::new(count::KmerCounter::<K>::canonical()) as Box<dyn DynCounter>
+    } else {
+        Box::new(count::KmerCounter::<K>::new())
+    })
+}
@@ -0,0 +1,363 @@
+//! Correcting sequencing errors with trusted k-mers.
+//!
+//! K-mers that occur often in a set of reads are likely to be in the genome,
+//! while those containing a sequencing error are rare. Given a set of
+//! trusted k-mers, a [`Corrector`] substitutes the bases of a read so that
+//! every one of its k-mers is trusted.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::correct::Corrector;
+//! use helicase::set::KmerSet;
+//! use helicase::{Base, Sequence};
+//!
+//! let genome = Sequence::<usize>::try_from("GATTACACTGGCATTCAGCCAT").unwrap();
+//! let trusted = KmerSet::<7>::canonical_from_sequence(&genome);
+//!
+//! let mut read = Sequence::<usize>::try_from("ACACTGGAATTCAGC").unwrap();
+//! let corrections = Corrector::new(&trusted).correct(&mut read).unwrap();
+//! assert_eq!(read.to_string(), "ACACTGGCATTCAGC");
+//! assert_eq!(corrections[0].position, 7);
+//! assert_eq!((corrections[0].from, corrections[0].to), (Base::A, Base::C));
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use bitvec::store::BitStore;
+
+use crate::set::KmerSet;
+use crate::small::Kmer;
+use crate::{Base, Sequence};
+
+/// A source of trusted k-mers.
+///
+/// Implemented for [`KmerSet`], and for closures, such as one that trusts
+/// the k-mers counted at least a few times by a
+/// [`KmerCounter`](crate::count::KmerCounter).
+pub trait Trusted<const K: usize> {
+    /// Returns `true` if `kmer` is trusted.
+    fn is_trusted(&self, kmer: Kmer<K>) -> bool;
+}
+
+impl<const K: usize> Trusted<K> for KmerSet<K> {
+    fn is_trusted(&self, kmer: Kmer<K>) -> bool {
+        self.contains(kmer)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const K: usize> Trusted<K> for crate::set::ArchivedKmerSet<K> {
+    fn is_trusted(&self, kmer: Kmer<K>) -> bool {
+        self.contains(kmer)
+    }
+}
+
+impl<const K: usize, F: Fn(Kmer<K>) -> bool> Trusted<K> for F {
+    fn is_trusted(&self, kmer: Kmer<K>) -> bool {
+        self(kmer)
+    }
+}
+
+impl<const K: usize> Trusted<K> for &KmerSet<K> {
+    fn is_trusted(&self, kmer: Kmer<K>) -> bool {
+        self.contains(kmer)
+    }
+}
+
+/// A base substituted by a [`Corrector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Correction {
+    /// The index of the base in the sequence.
+    pub position: usize,
+    /// The base before correction.
+    pub from: Base,
+    /// The base after correction.
+    pub to: Base,
+}
+
+/// An error returned by [`Corrector::correct`] for a sequence that could
+/// not be corrected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrectError {
+    /// None of the k-mers in the sequence are trusted, so there is no
+    /// context to correct from.
+    NoTrustedKmers,
+    /// No single substitution of the base at `position` makes the next
+    /// k-mer trusted, or more than one does.
+    Uncorrectable {
+        /// The index of the base.
+        position: usize,
+    },
+    /// More substitutions were needed than the corrector allows.
+    TooManyCorrections,
+}
+
+impl fmt::Display for CorrectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorrectError::NoTrustedKmers => write!(f, "no k-mers are trusted"),
+            CorrectError::Uncorrectable { position } => {
+                write!(f, "no unique correction for base {position}")
+            }
+            CorrectError::TooManyCorrections => write!(f, "too many corrections needed"),
+        }
+    }
+}
+
+impl core::error::Error for CorrectError {}
+
+/// Corrects substitution errors in sequences, so that all of their k-mers
+/// are trusted.
+///
+/// A sequence is corrected in one greedy pass. Each untrusted k-mer that
+/// follows a trusted one must differ from the genome in its last base, so
+/// that base is replaced by the alternative that makes the most following
+/// k-mers trusted. Untrusted k-mers at the start of the sequence are
+/// corrected the same way, backwards from the first trusted k-mer.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::correct::Corrector;
+/// use helicase::count::KmerCounter;
+/// use helicase::Sequence;
+///
+/// let reads = ["GATTACAGGCA", "GATTACAGGCA", "GATTCCAGGCA"]
+///     .map(|s| Sequence::<usize>::try_from(s).unwrap());
+/// let mut counter = KmerCounter::<5>::canonical();
+/// for read in &reads {
+///     counter.count_sequence(read);
+/// }
+///
+/// let corrector = Corrector::new(|kmer| counter.get(kmer) >= 2);
+/// let mut read = reads[2].clone();
+/// assert_eq!(corrector.correct(&mut read).unwrap().len(), 1);
+/// assert_eq!(read, reads[0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Corrector<const K: usize, T> {
+    trusted: T,
+    max_corrections: usize,
+}
+
+impl<const K: usize, T: Trusted<K>> Corrector<K, T> {
+    /// Creates a corrector that trusts the k-mers in `trusted`, and makes
+    /// any number of corrections.
+    pub fn new(trusted: T) -> Self {
+        Self {
+            trusted,
+            max_corrections: usize::MAX,
+        }
+    }
+
+    /// Limits the number of bases corrected in each sequence.
+    pub fn max_corrections(mut self, max: usize) -> Self {
+        self.max_corrections = max;
+        self
+    }
+
+    /// Corrects `seq` in place so that all of its k-mers are trusted, and
+    /// returns the substitutions made, in the order they were made.
+    ///
+    /// Sequences shorter than `K` have no k-mers, and are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sequence cannot be corrected, in which case
+    /// it is left unchanged.
+    pub fn correct<B: BitStore>(
+        &self,
+        seq: &mut Sequence<B>,
+    ) -> Result<Vec<Correction>, CorrectError> {
+        let mut kmers: Vec<Kmer<K>> = seq.kmers().collect();
+        let mut corrections = Vec::new();
+        let result = self.correct_kmers(seq, &mut kmers, &mut corrections);
+        if result.is_err() {
+            for correction in corrections.iter().rev() {
+                seq.set(correction.position, correction.from);
+            }
+        }
+        result.map(|()| corrections)
+    }
+
+    fn correct_kmers<B: BitStore>(
+        &self,
+        seq: &mut Sequence<B>,
+        kmers: &mut [Kmer<K>],
+        corrections: &mut Vec<Correction>,
+    ) -> Result<(), CorrectError> {
+        let first = kmers.iter().position(|&kmer| self.trusted.is_trusted(kmer));
+        let mut i = match first {
+            Some(i) => i,
+            None if kmers.is_empty() => return Ok(()),
+            None => return Err(CorrectError::NoTrustedKmers),
+        };
+
+        // correct the untrusted k-mers before the first trusted one,
+        // backwards from the base just before it
+        while i > 0 {
+            let position = i - 1;
+            let windows = (position.saturating_sub(K - 1)..=position).rev();
+            i -= self.substitute(seq, kmers, position, windows, corrections)?;
+        }
+
+        // then the rest, forwards from the base just after each trusted k-mer
+        while i < kmers.len() {
+            if self.trusted.is_trusted(kmers[i]) {
+                i += 1;
+                continue;
+            }
+            let position = i + K - 1;
+            let windows = i..=position.min(kmers.len() - 1);
+            i += self.substitute(seq, kmers, position, windows, corrections)?;
+        }
+        Ok(())
+    }
+
+    /// Substitutes the base at `position` with the alternative that makes
+    /// the most consecutive `windows` trusted, and returns that number.
+    fn substitute<B: BitStore>(
+        &self,
+        seq: &mut Sequence<B>,
+        kmers: &mut [Kmer<K>],
+        position: usize,
+        windows: impl Iterator<Item = usize> + Clone,
+        corrections: &mut Vec<Correction>,
+    ) -> Result<usize, CorrectError> {
+        if corrections.len() >= self.max_corrections {
+            return Err(CorrectError::TooManyCorrections);
+        }
+
+        let from = seq.get(position).expect("position is in bounds");
+        let trusted_with = |base: Base| {
+            windows
+                .clone()
+                .take_while(|&w| {
+                    let kmer = kmers[w].with_base_at(position - w, base);
+                    self.trusted.is_trusted(kmer)
+                })
+                .count()
+        };
+        let mut best = None;
+        let mut tied = false;
+        for to in [Base::C, Base::A, Base::T, Base::G] {
+            if to == from {
+                continue;
+            }
+            let trusted = trusted_with(to);
+            match best {
+                Some((_, most)) if trusted < most => {}
+                Some((_, most)) if trusted == most => tied = true,
+                _ => {
+                    best = Some((to, trusted));
+                    tied = false;
+                }
+            }
+        }
+        let (to, trusted) = best.expect("there are three alternatives");
+        if trusted == 0 || tied {
+            return Err(CorrectError::Uncorrectable { position });
+        }
+
+        seq.set(position, to);
+        for w in position.saturating_sub(K - 1)..=position.min(kmers.len() - 1) {
+            kmers[w] = kmers[w].with_base_at(position - w, to);
+        }
+        corrections.push(Correction { position, from, to });
+        Ok(trusted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::*;
+
+    fn genome() -> String {
+        let mut state = 1u64;
+        (0..400)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                ["A", "C", "G", "T"][(state >> 62) as usize]
+            })
+            .collect()
+    }
+
+    fn corrector() -> Corrector<15, KmerSet<15>> {
+        let genome = Sequence::<usize>::try_from(genome().as_str()).unwrap();
+        Corrector::new(KmerSet::canonical_from_sequence(&genome))
+    }
+
+    fn with_errors(read: &str, errors: &[usize]) -> Sequence<usize> {
+        let mut seq = Sequence::try_from(read).unwrap();
+        for &i in errors {
+            seq.set(i, seq.get(i).unwrap().complement());
+        }
+        seq
+    }
+
+    #[test]
+    fn correct() {
+        let corrector = corrector();
+        let genome = genome();
+        let read = &genome[100..200];
+        let expected = Sequence::<usize>::try_from(read).unwrap();
+
+        for errors in [
+            &[][..],
+            &[50],
+            &[0],
+            &[99],
+            &[3, 60],
+            &[20, 27],
+            &[5, 9, 80],
+        ] {
+            let mut seq = with_errors(read, errors);
+            let corrections = corrector.correct(&mut seq).unwrap();
+            assert_eq!(seq, expected, "errors at {errors:?}");
+            let mut positions: Vec<usize> = corrections.iter().map(|c| c.position).collect();
+            positions.sort_unstable();
+            assert_eq!(positions, errors);
+        }
+
+        // the reverse complement of a read is corrected too
+        let mut seq = with_errors(read, &[40]).to_reverse_complement();
+        corrector.correct(&mut seq).unwrap();
+        assert_eq!(seq, expected.to_reverse_complement());
+    }
+
+    #[test]
+    fn uncorrectable() {
+        let corrector = corrector();
+        let read = &genome()[100..200];
+
+        let mut seq = with_errors(read, &[10, 50]);
+        let original = seq.clone();
+        let err = corrector.clone().max_corrections(1).correct(&mut seq);
+        assert_eq!(err, Err(CorrectError::TooManyCorrections));
+        assert_eq!(seq, original);
+
+        let mut seq = Sequence::<usize>::try_from("A".repeat(50).as_str()).unwrap();
+        assert_eq!(
+            corrector.correct(&mut seq),
+            Err(CorrectError::NoTrustedKmers)
+        );
+
+        let mut short = Sequence::<usize>::try_from("GATTACA").unwrap();
+        assert_eq!(corrector.correct(&mut short), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn closure() {
+        let genome = Sequence::<usize>::try_from(genome().as_str()).unwrap();
+        let set = KmerSet::<11>::from_sequence(&genome);
+        let corrector = Corrector::new(|kmer| set.contains(kmer));
+        let mut seq = with_errors(&genome.to_string()[10..60], &[30]);
+        assert_eq!(corrector.correct(&mut seq).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,521 @@
+//! Counting k-mers.
+//!
+//! A [`KmerCounter`] counts how many times each k-mer occurs, optionally
+//! counting each k-mer together with its reverse complement. When there are
+//! too many distinct k-mers to count exactly, a [`CountingBloomFilter`]
+//! estimates the counts of their hashes in a fixed amount of memory. A
+//! [`Spectrum`] summarizes how many distinct k-mers have each count.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::count::KmerCounter;
+//! use helicase::small::Kmer;
+//! use helicase::Base;
+//!
+//! let gat = Kmer::from_bases([Base::G, Base::A, Base::T]);
+//! let mut counter = KmerCounter::canonical();
+//! counter.insert(gat);
+//! counter.insert(gat.reverse_complement());
+//! assert_eq!(counter.get(gat), 2);
+//! ```
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::collections::hash_map::Entry;
+
+#[cfg(feature = "std")]
+use crate::order::KmerOrd;
+#[cfg(feature = "std")]
+use crate::small::Kmer;
+
+mod bloom;
+mod spectrum;
+
+pub use bloom::CountingBloomFilter;
+pub use spectrum::Spectrum;
+
+/// The number of high bits of a key's hash that choose its shard.
+#[cfg(feature = "std")]
+const SHARD_BITS: u32 = 6;
+
+/// Returns the shard that counts `key`.
+///
+/// Shards are chosen by hash rather than by the first bases of the k-mer,
+/// because canonical k-mers start with `C` or `A` far more often than with
+/// `T` or `G`, which would leave half of the shards nearly empty.
+#[cfg(feature = "std")]
+const fn shard(key: u64) -> usize {
+    (crate::hash::mix::hash(key) >> (64 - SHARD_BITS)) as usize
+}
+
+/// Counts of k-mers, stored in hash maps.
+///
+/// K-mers are ordered by their 2-bit encoding, which sorts bases as
+/// `C < A < T < G`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct KmerCounter<const K: usize> {
+    /// Counts indexed by the masked value of each k-mer, split into shards
+    /// by [`shard`] so that they can be merged in parallel.
+    shards: Vec<HashMap<u64, u64>>,
+    canonical: bool,
+}
+
+#[cfg(feature = "std")]
+impl<const K: usize> Default for KmerCounter<K> {
+    fn default() -> Self {
+        Self {
+            shards: (0..1 << SHARD_BITS).map(|_| HashMap::new()).collect(),
+            canonical: false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const K: usize> KmerCounter<K> {
+    /// Creates an empty counter that counts each k-mer as it is given.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty counter that counts each k-mer together with its
+    /// reverse complement, under their canonical form.
+    pub fn canonical() -> Self {
+        Self {
+            canonical: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns `true` if k-mers are counted under their canonical form.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Returns the key under which `kmer` is counted.
+    fn key(&self, kmer: Kmer<K>) -> u64 {
+        if self.canonical {
+            kmer.canonical().as_masked()
+        } else {
+            kmer.as_masked()
+        }
+    }
+
+    /// Counts one occurrence of `kmer`, and returns its new count.
+    pub fn insert(&mut self, kmer: Kmer<K>) -> u64 {
+        self.insert_n(kmer, 1)
+    }
+
+    /// Counts `n` occurrences of `kmer`, and returns its new count.
+    pub fn insert_n(&mut self, kmer: Kmer<K>, n: u64) -> u64 {
+        let key = self.key(kmer);
+        let count = self.shards[shard(key)].entry(key).or_default();
+        *count += n;
+        *count
+    }
+
+    /// Counts every k-mer in a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::count::KmerCounter;
+    /// use helicase::Sequence;
+    ///
+    /// let seq: Sequence = "GATTACAGATTACA".try_into().unwrap();
+    /// let mut counter = KmerCounter::<3>::new();
+    /// counter.count_sequence(&seq);
+    ///
+    /// let (kmer, count) = counter.sorted_by_count()[0];
+    /// assert_eq!((kmer.to_string(), count), ("ACA".to_string(), 2));
+    /// ```
+    #[cfg(feature = "bitvec")]
+    pub fn count_sequence<B: bitvec::store::BitStore>(&mut self, seq: &crate::Sequence<B>) {
+        self.extend(seq.kmers::<K>());
+    }
+
+    /// Counts every k-mer in a sequence in parallel.
+    ///
+    /// Each thread counts its chunk of the sequence into its own shards,
+    /// which are then merged into the counter's shards in parallel, since
+    /// no two shards share a k-mer. This is faster than
+    /// [`count_sequence`](Self::count_sequence) for long sequences, but
+    /// slower for short ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::count::KmerCounter;
+    /// use helicase::Sequence;
+    ///
+    /// let seq: Sequence = "GATTACA".repeat(1000).as_str().try_into().unwrap();
+    /// let mut counter = KmerCounter::<5>::canonical();
+    /// counter.count_parallel(&seq);
+    ///
+    /// let mut expected = KmerCounter::<5>::canonical();
+    /// expected.count_sequence(&seq);
+    /// assert_eq!(counter.len(), expected.len());
+    /// assert!(counter.iter().all(|(kmer, count)| expected.get(kmer) == count));
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn count_parallel<B: bitvec::store::BitStore + Sync>(&mut self, seq: &crate::Sequence<B>) {
+        use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+
+        let this = &*self;
+        let empty =
+            || -> Vec<HashMap<u64, u64>> { (0..1 << SHARD_BITS).map(|_| HashMap::new()).collect() };
+        let partials: Vec<_> = seq
+            .par_kmers::<K>()
+            .fold(empty, |mut shards, kmer| {
+                let key = this.key(kmer);
+                *shards[shard(key)].entry(key).or_default() += 1;
+                shards
+            })
+            .collect();
+
+        let mut by_shard: Vec<Vec<_>> = self.shards.iter().map(|_| Vec::new()).collect();
+        for shards in partials {
+            for (i, shard) in shards.into_iter().enumerate() {
+                by_shard[i].push(shard);
+            }
+        }
+        self.shards
+            .par_iter_mut()
+            .zip(by_shard)
+            .for_each(|(counts, partials)| {
+                for partial in partials {
+                    if counts.len() < partial.len() {
+                        let smaller = std::mem::replace(counts, partial);
+                        add_counts(counts, &smaller);
+                    } else {
+                        add_counts(counts, &partial);
+                    }
+                }
+            });
+    }
+
+    /// Returns the number of times `kmer` was counted.
+    pub fn get(&self, kmer: Kmer<K>) -> u64 {
+        let key = self.key(kmer);
+        self.shards[shard(key)].get(&key).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of distinct k-mers counted.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(HashMap::len).sum()
+    }
+
+    /// Returns `true` if no k-mers have been counted.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(HashMap::is_empty)
+    }
+
+    /// Returns the total number of occurrences counted.
+    pub fn total(&self) -> u64 {
+        self.counts().map(|(_, count)| count).sum()
+    }
+
+    /// Returns an iterator over the keys of the distinct k-mers and their
+    /// counts, in arbitrary order.
+    fn counts(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.shards
+            .iter()
+            .flatten()
+            .map(|(&kmer, &count)| (kmer, count))
+    }
+
+    /// Returns an iterator over the distinct k-mers and their counts, in
+    /// arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Kmer<K>, u64)> + '_ {
+        self.counts().map(|(kmer, count)| (Kmer::from(kmer), count))
+    }
+
+    /// Returns the spectrum of the counts: how many distinct k-mers have
+    /// each count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::count::KmerCounter;
+    /// use helicase::small::Kmer;
+    /// use helicase::Base::*;
+    ///
+    /// let mut counter = KmerCounter::new();
+    /// counter.extend([[G, A], [G, A], [T, A], [G, A], [C, A]].map(Kmer::from_bases));
+    ///
+    /// let spectrum = counter.spectrum();
+    /// assert_eq!(spectrum.get(1), 2);
+    /// assert_eq!(spectrum.get(3), 1);
+    /// assert_eq!(spectrum.total(), counter.total());
+    /// ```
+    pub fn spectrum(&self) -> Spectrum {
+        self.counts().map(|(_, count)| count).collect()
+    }
+
+    /// Returns the distinct k-mers and their counts, sorted by k-mer.
+    pub fn sorted_by_kmer(&self) -> Vec<(Kmer<K>, u64)> {
+        let mut counts: Vec<(u64, u64)> = self.counts().collect();
+        counts.sort_unstable();
+        counts
+            .into_iter()
+            .map(|(k, c)| (Kmer::from(k), c))
+            .collect()
+    }
+
+    /// Returns the distinct k-mers and their counts, sorted in the order
+    /// `O`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::count::KmerCounter;
+    /// use helicase::order::Lexicographic;
+    /// use helicase::small::Kmer;
+    /// use helicase::Base::*;
+    ///
+    /// let mut counter = KmerCounter::new();
+    /// counter.extend([[T, A], [C, A], [A, G]].map(Kmer::from_bases));
+    /// let sorted: Vec<String> = counter
+    ///     .sorted_by::<Lexicographic>()
+    ///     .iter()
+    ///     .map(|(kmer, _)| kmer.to_string())
+    ///     .collect();
+    /// assert_eq!(sorted, ["AG", "CA", "TA"]);
+    /// ```
+    pub fn sorted_by<O: KmerOrd<K>>(&self) -> Vec<(Kmer<K>, u64)> {
+        let mut counts: Vec<(Kmer<K>, u64)> = self.iter().collect();
+        counts.sort_unstable_by(|a, b| O::cmp(&a.0, &b.0));
+        counts
+    }
+
+    /// Returns the distinct k-mers and their counts, from most to least
+    /// abundant, with ties sorted by k-mer.
+    pub fn sorted_by_count(&self) -> Vec<(Kmer<K>, u64)> {
+        let mut counts: Vec<(u64, u64)> = self.counts().collect();
+        counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts
+            .into_iter()
+            .map(|(k, c)| (Kmer::from(k), c))
+            .collect()
+    }
+
+    /// Adds the counts of `other` to this counter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if only one of the counters is canonical.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.canonical, other.canonical,
+            "cannot merge canonical and non-canonical counters"
+        );
+        for (counts, other) in self.shards.iter_mut().zip(&other.shards) {
+            add_counts(counts, other);
+        }
+    }
+}
+
+/// Adds the counts of `other` to `counts`.
+#[cfg(feature = "std")]
+fn add_counts(counts: &mut HashMap<u64, u64>, other: &HashMap<u64, u64>) {
+    counts.reserve(other.len());
+    for (&kmer, &count) in other {
+        match counts.entry(kmer) {
+            Entry::Occupied(mut entry) => *entry.get_mut() += count,
+            Entry::Vacant(entry) => {
+                entry.insert(count);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const K: usize> Extend<Kmer<K>> for KmerCounter<K> {
+    fn extend<T: IntoIterator<Item = Kmer<K>>>(&mut self, iter: T) {
+        for kmer in iter {
+            self.insert(kmer);
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "rkyv"))]
+impl<const K: usize> ArchivedKmerCounter<K> {
+    /// Returns `true` if k-mers were counted under their canonical form.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Returns the number of times `kmer` was counted, without
+    /// deserializing the counter.
+    pub fn get(&self, kmer: Kmer<K>) -> u64 {
+        let key = if self.canonical {
+            kmer.canonical().as_masked()
+        } else {
+            kmer.as_masked()
+        };
+        self.shards[shard(key)]
+            .get(&rkyv::Archived::<u64>::from_native(key))
+            .map_or(0, |count| count.to_native())
+    }
+
+    /// Returns the number of distinct k-mers counted.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.len()).sum()
+    }
+
+    /// Returns `true` if no k-mers were counted.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.is_empty())
+    }
+
+    /// Returns an iterator over the distinct k-mers and their counts, in
+    /// arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Kmer<K>, u64)> + '_ {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.iter())
+            .map(|(kmer, count)| (Kmer::from(kmer.to_native()), count.to_native()))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let mut counter = KmerCounter::<3>::new();
+        assert!(counter.is_empty());
+        assert_eq!(counter.insert("GAT".parse().unwrap()), 1);
+        assert_eq!(counter.insert("GAT".parse().unwrap()), 2);
+        assert_eq!(counter.insert_n("ATC".parse().unwrap(), 3), 3);
+        assert_eq!(counter.get("GAT".parse().unwrap()), 2);
+        assert_eq!(counter.get("ATC".parse().unwrap()), 3);
+        assert_eq!(counter.get("CCC".parse().unwrap()), 0);
+        assert_eq!((counter.len(), counter.total()), (2, 5));
+    }
+
+    #[test]
+    fn canonical() {
+        let mut counter = KmerCounter::<3>::canonical();
+        counter.extend([
+            "GAT".parse().unwrap(),
+            "ATC".parse().unwrap(),
+            "CCC".parse().unwrap(),
+        ]);
+        assert!(counter.is_canonical());
+        assert_eq!(counter.get("GAT".parse().unwrap()), 2);
+        assert_eq!(counter.get("ATC".parse().unwrap()), 2);
+        assert_eq!(counter.get("GGG".parse().unwrap()), 1);
+        assert_eq!(counter.len(), 2);
+    }
+
+    #[test]
+    fn sorted() {
+        let mut counter = KmerCounter::<3>::new();
+        counter.extend(["GGG", "AAA", "CCC", "AAA", "TTT", "GGG"].map(|s| s.parse().unwrap()));
+        let by_kmer: Vec<(String, u64)> = counter
+            .sorted_by_kmer()
+            .iter()
+            .map(|(k, c)| (k.to_string(), *c))
+            .collect();
+        assert_eq!(
+            by_kmer,
+            [("CCC", 1), ("AAA", 2), ("TTT", 1), ("GGG", 2)].map(|(k, c)| (k.to_string(), c))
+        );
+        let by_count: Vec<String> = counter
+            .sorted_by_count()
+            .iter()
+            .map(|(k, _)| k.to_string())
+            .collect();
+        assert_eq!(by_count, ["AAA", "GGG", "CCC", "TTT"]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = KmerCounter::<3>::new();
+        a.extend(["GAT", "TAC"].map(|s| s.parse().unwrap()));
+        let mut b = KmerCounter::<3>::new();
+        b.extend(["GAT", "CAG"].map(|s| s.parse().unwrap()));
+        a.merge(&b);
+        assert_eq!(a.get("GAT".parse().unwrap()), 2);
+        assert_eq!(a.get("CAG".parse().unwrap()), 1);
+        assert_eq!(a.total(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_mismatch() {
+        KmerCounter::<3>::new().merge(&KmerCounter::canonical());
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn archive() {
+        let mut counter = KmerCounter::<3>::canonical();
+        counter.extend(["GAT", "ATC", "CCC", "TAC"].map(|s| s.parse().unwrap()));
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&counter).unwrap();
+
+        let archived = rkyv::access::<ArchivedKmerCounter<3>, rkyv::rancor::Error>(&bytes).unwrap();
+        assert!(archived.is_canonical());
+        assert_eq!(archived.len(), counter.len());
+        assert_eq!(archived.get("GAT".parse().unwrap()), 2);
+        assert_eq!(archived.get("GGG".parse().unwrap()), 1);
+        assert_eq!(archived.get("AAA".parse().unwrap()), 0);
+        assert_eq!(archived.iter().map(|(_, c)| c).sum::<u64>(), 4);
+
+        let counter: KmerCounter<3> =
+            rkyv::deserialize::<_, rkyv::rancor::Error>(archived).unwrap();
+        assert_eq!(counter.get("ATC".parse().unwrap()), 2);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn count_sequence() {
+        let seq = crate::Sequence::<u8>::try_from("GATTACAGATTACA").unwrap();
+        let mut counter = KmerCounter::<3>::new();
+        counter.count_sequence(&seq);
+        assert_eq!(counter.total(), 12);
+        assert_eq!(counter.get("GAT".parse().unwrap()), 2);
+        assert_eq!(counter.get("CAG".parse().unwrap()), 1);
+
+        let mut counter = KmerCounter::<3>::canonical();
+        counter.count_sequence(&seq);
+        // `ATC` is the reverse complement of `GAT`
+        assert_eq!(counter.get("ATC".parse().unwrap()), 2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn count_parallel() {
+        let s = crate::utils::test::random_ascii(100_000, 0);
+        let seq = crate::Sequence::<usize>::try_from(s.as_str()).unwrap();
+        for canonical in [false, true] {
+            let mut expected = if canonical {
+                KmerCounter::<3>::canonical()
+            } else {
+                KmerCounter::new()
+            };
+            let mut counter = expected.clone();
+            counter.insert("GAT".parse().unwrap());
+            counter.count_parallel(&seq);
+            expected.insert("GAT".parse().unwrap());
+            expected.count_sequence(&seq);
+            let sorted = |counter: &KmerCounter<3>| -> Vec<(u64, u64)> {
+                let counts = counter.sorted_by_kmer().into_iter();
+                counts.map(|(k, c)| (k.as_masked(), c)).collect()
+            };
+            assert_eq!(sorted(&counter), sorted(&expected));
+        }
+
+        let mut counter = KmerCounter::<3>::new();
+        counter.count_parallel(&crate::Sequence::<usize>::try_from("GA").unwrap());
+        assert!(counter.is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+use alloc::boxed::Box;
+use alloc::vec;
+
+/// An approximate counter of hashed k-mers, as a counting Bloom filter.
+///
+/// Each hash is counted in several small counters, and its count is
+/// estimated as the smallest of them. Estimates are never too low, but may
+/// be too high when other hashes share all of the same counters, which
+/// happens at the configured false-positive rate. Counters saturate at
+/// [`u8::MAX`].
+///
+/// Counters are updated conservatively: an insertion only increments the
+/// counters that hold the current estimate, which keeps overestimates small.
+///
+/// Keys are 64-bit hashes, such as those from
+/// [`nthash`](crate::hash::nthash) or `Sequence::kmer_hashes`.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::count::CountingBloomFilter;
+///
+/// let mut filter = CountingBloomFilter::new(1000, 0.01);
+/// filter.extend([7, 7, 42]);
+/// assert_eq!(filter.count(7), 2);
+/// assert_eq!(filter.count(42), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountingBloomFilter {
+    counters: Box<[u8]>,
+    hashes: u32,
+}
+
+impl CountingBloomFilter {
+    /// Creates a filter sized to hold `expected` distinct hashes with a
+    /// false-positive rate of about `fp_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fp_rate` is not strictly between 0 and 1.
+    #[cfg(feature = "std")]
+    pub fn new(expected: usize, fp_rate: f64) -> Self {
+        assert!(
+            fp_rate > 0.0 && fp_rate < 1.0,
+            "false-positive rate must be between 0 and 1"
+        );
+        let ln2 = core::f64::consts::LN_2;
+        let expected = expected.max(1) as f64;
+        let counters = (-expected * fp_rate.ln() / (ln2 * ln2)).ceil();
+        let hashes = (counters / expected * ln2).round().max(1.0);
+        Self::with_memory(counters as usize, hashes as u32)
+    }
+
+    /// Creates a filter with a memory budget of `bytes` one-byte counters,
+    /// using `hashes` counters per hash.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` or `hashes` is zero.
+    pub fn with_memory(bytes: usize, hashes: u32) -> Self {
+        assert!(bytes > 0, "a filter needs at least one counter");
+        assert!(hashes > 0, "a filter needs at least one counter per hash");
+        Self {
+            counters: vec![0; bytes].into_boxed_slice(),
+            hashes,
+        }
+    }
+
+    /// Returns the number of counters, which is also the size of the filter
+    /// in bytes.
+    pub fn memory(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Returns the number of counters used for each hash.
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the indices of the counters for `hash`.
+    fn indices(&self, hash: u64) -> impl Iterator<Item = usize> + use<> {
+        let len = self.counters.len() as u128;
+        // derive every index from two hashes, as in Kirsch and Mitzenmacher
+        let step = (hash ^ hash >> 31).wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        (0..self.hashes as u64).map(move |i| {
+            let mixed = hash.wrapping_add(i.wrapping_mul(step));
+            ((mixed as u128 * len) >> 64) as usize
+        })
+    }
+
+    /// Counts one occurrence of `hash`, and returns its new estimated
+    /// count.
+    pub fn insert(&mut self, hash: u64) -> u8 {
+        let count = self.count(hash);
+        if count == u8::MAX {
+            return count;
+        }
+        for i in self.indices(hash) {
+            if self.counters[i] == count {
+                self.counters[i] += 1;
+            }
+        }
+        count + 1
+    }
+
+    /// Returns the estimated number of times `hash` was counted.
+    pub fn count(&self, hash: u64) -> u8 {
+        self.indices(hash)
+            .map(|i| self.counters[i])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if `hash` has probably been counted.
+    pub fn contains(&self, hash: u64) -> bool {
+        self.count(hash) > 0
+    }
+
+    /// Estimates the false-positive rate after `items` distinct hashes have
+    /// been counted.
+    #[cfg(feature = "std")]
+    pub fn false_positive_rate(&self, items: usize) -> f64 {
+        let k = self.hashes as f64;
+        let fill = 1.0 - (-k * items as f64 / self.counters.len() as f64).exp();
+        fill.powf(k)
+    }
+
+    /// Resets every count to zero.
+    pub fn clear(&mut self) {
+        self.counters.fill(0);
+    }
+}
+
+impl Extend<u64> for CountingBloomFilter {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for hash in iter {
+            self.insert(hash);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn hash(i: u64) -> u64 {
+        i.wrapping_mul(0xD6E8_FEB8_6659_FD93).rotate_left(29)
+    }
+
+    #[test]
+    fn sizing() {
+        let filter = CountingBloomFilter::new(1000, 0.01);
+        assert!((9000..10_000).contains(&filter.memory()));
+        assert_eq!(filter.hashes(), 7);
+        assert!((filter.false_positive_rate(1000) - 0.01).abs() < 0.002);
+        assert_eq!(CountingBloomFilter::new(1000, 0.9).hashes(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_hashes() {
+        CountingBloomFilter::with_memory(16, 0);
+    }
+
+    #[test]
+    fn counts() {
+        let mut filter = CountingBloomFilter::new(2000, 0.01);
+        for i in 0..1000 {
+            for _ in 0..i % 5 + 1 {
+                filter.insert(hash(i));
+            }
+        }
+        for i in 0..1000 {
+            assert!(filter.count(hash(i)) > (i % 5) as u8);
+        }
+        let exact = (0..1000).filter(|&i| filter.count(hash(i)) == (i % 5) as u8 + 1);
+        assert!(exact.count() > 980);
+
+        let false_positives = (1000..11_000).filter(|&i| filter.contains(hash(i))).count();
+        assert!(false_positives < 100, "{false_positives} false positives");
+
+        filter.clear();
+        assert!(!filter.contains(hash(0)));
+    }
+
+    #[test]
+    fn saturates() {
+        let mut filter = CountingBloomFilter::with_memory(16, 2);
+        for _ in 0..300 {
+            filter.insert(1);
+        }
+        assert_eq!(filter.count(1), u8::MAX);
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::collections::BTreeMap;
+
+/// A k-mer spectrum: the number of distinct k-mers that occur each number
+/// of times.
+///
+/// In a spectrum of sequencing reads, k-mers containing errors mostly occur
+/// once or a few times, while k-mers from the genome form a peak around the
+/// coverage. The [`valley`](Self::valley) between them separates the two,
+/// and is the usual threshold for discarding erroneous k-mers.
+///
+/// Built from a counter with
+/// [`KmerCounter::spectrum`](super::KmerCounter::spectrum), or from a stream
+/// of counts with [`add`](Self::add) or `collect`.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::count::Spectrum;
+///
+/// // many errors seen once, and a genome covered about 4 times
+/// let counts = [1; 50].into_iter().chain([2; 10]).chain([3; 20]).chain([4; 30]).chain([5; 15]);
+/// let spectrum: Spectrum = counts.collect();
+/// assert_eq!(spectrum.get(4), 30);
+/// assert_eq!(spectrum.valley(), Some(2));
+/// assert_eq!(spectrum.peak(), Some(4));
+/// assert_eq!(spectrum.genome_size(), Some((3 * 20 + 4 * 30 + 5 * 15) / 4));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spectrum {
+    /// The number of distinct k-mers with each non-zero multiplicity.
+    histogram: BTreeMap<u64, u64>,
+}
+
+impl Spectrum {
+    /// Creates an empty spectrum.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a distinct k-mer that occurred `count` times.
+    ///
+    /// A count of zero is ignored.
+    pub fn add(&mut self, count: u64) {
+        self.add_n(count, 1);
+    }
+
+    /// Adds `n` distinct k-mers that each occurred `count` times.
+    ///
+    /// A count of zero is ignored.
+    pub fn add_n(&mut self, count: u64, n: u64) {
+        if count > 0 && n > 0 {
+            *self.histogram.entry(count).or_default() += n;
+        }
+    }
+
+    /// Returns the number of distinct k-mers that occurred `count` times.
+    pub fn get(&self, count: u64) -> u64 {
+        self.histogram.get(&count).copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if no k-mers have been added.
+    pub fn is_empty(&self) -> bool {
+        self.histogram.is_empty()
+    }
+
+    /// Returns the number of distinct k-mers.
+    pub fn distinct(&self) -> u64 {
+        self.histogram.values().sum()
+    }
+
+    /// Returns the total number of occurrences of all k-mers.
+    pub fn total(&self) -> u64 {
+        self.histogram.iter().map(|(&count, &n)| count * n).sum()
+    }
+
+    /// Returns the largest count of any k-mer, or `None` if the spectrum is
+    /// empty.
+    pub fn max_count(&self) -> Option<u64> {
+        self.histogram.last_key_value().map(|(&count, _)| count)
+    }
+
+    /// Returns an iterator over each count and the number of distinct k-mers
+    /// with that count, in increasing order of count.
+    ///
+    /// Counts that no k-mer has are skipped.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (u64, u64)> + '_ {
+        self.histogram.iter().map(|(&count, &n)| (count, n))
+    }
+
+    /// Returns the first local minimum of the number of k-mers after the
+    /// error peak, or `None` if there is none.
+    ///
+    /// This is the bottom of the valley between erroneous k-mers and those
+    /// from the genome. The error peak is the first local maximum, usually
+    /// at a count of 1. A run of two or more counts that no k-mer has is
+    /// part of the valley, so the valley is at the last of them, but a single
+    /// such count between two others is ignored as noise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::count::Spectrum;
+    ///
+    /// let mut spectrum = Spectrum::new();
+    /// spectrum.add_n(1, 100);
+    /// spectrum.add_n(2, 30);
+    /// assert_eq!(spectrum.valley(), None);
+    ///
+    /// spectrum.add_n(5, 40);
+    /// assert_eq!(spectrum.valley(), Some(4));
+    /// ```
+    pub fn valley(&self) -> Option<u64> {
+        let mut past_peak = false;
+        let mut entries = self.iter().peekable();
+        while let Some((count, n)) = entries.next() {
+            let &(next, next_n) = entries.peek()?;
+            if next > count + 2 {
+                // the counts in between have no k-mers
+                return Some(next - 1);
+            }
+            if past_peak && next_n > n {
+                return Some(count);
+            }
+            past_peak |= next_n < n;
+        }
+        None
+    }
+
+    /// Returns the count above the [`valley`](Self::valley) that the most
+    /// distinct k-mers have, or `None` if there is no valley.
+    ///
+    /// For sequencing reads, this estimates the k-mer coverage of the
+    /// genome. Ties are broken by the smaller count.
+    pub fn peak(&self) -> Option<u64> {
+        let valley = self.valley()?;
+        let (count, _) = self
+            .histogram
+            .range(valley + 1..)
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))?;
+        Some(*count)
+    }
+
+    /// Estimates the size of the genome, as the number of occurrences of
+    /// k-mers above the [`valley`](Self::valley) divided by the
+    /// [`peak`](Self::peak) coverage.
+    ///
+    /// Returns `None` if there is no valley.
+    pub fn genome_size(&self) -> Option<u64> {
+        let valley = self.valley()?;
+        let peak = self.peak()?;
+        let total: u64 = self
+            .histogram
+            .range(valley + 1..)
+            .map(|(&count, &n)| count * n)
+            .sum();
+        Some(total / peak)
+    }
+}
+
+impl Extend<u64> for Spectrum {
+    /// Adds a distinct k-mer for each count.
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for count in iter {
+            self.add(count);
+        }
+    }
+}
+
+impl FromIterator<u64> for Spectrum {
+    /// Collects the counts of distinct k-mers into a spectrum.
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut spectrum = Self::new();
+        spectrum.extend(iter);
+        spectrum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_get() {
+        let mut spectrum = Spectrum::new();
+        assert!(spectrum.is_empty());
+        assert_eq!(spectrum.max_count(), None);
+        spectrum.extend([3, 1, 3, 0]);
+        spectrum.add_n(7, 2);
+        spectrum.add_n(9, 0);
+        assert_eq!(spectrum.get(3), 2);
+        assert_eq!(spectrum.get(0), 0);
+        assert_eq!(spectrum.get(9), 0);
+        assert_eq!(spectrum.distinct(), 5);
+        assert_eq!(spectrum.total(), 1 + 3 * 2 + 7 * 2);
+        assert_eq!(spectrum.max_count(), Some(7));
+        assert!(spectrum.iter().eq([(1, 1), (3, 2), (7, 2)]));
+    }
+
+    #[test]
+    fn valley_and_peak() {
+        let mut spectrum = Spectrum::new();
+        assert_eq!((spectrum.valley(), spectrum.peak()), (None, None));
+
+        // a decreasing tail of errors with no peak
+        for (count, n) in [(1, 90), (2, 40), (3, 40), (4, 10)] {
+            spectrum.add_n(count, n);
+        }
+        assert_eq!(spectrum.valley(), None);
+        assert_eq!(spectrum.genome_size(), None);
+
+        // a gap before the peak
+        for (count, n) in [(8, 5), (9, 20), (10, 20), (11, 6)] {
+            spectrum.add_n(count, n);
+        }
+        assert_eq!(spectrum.valley(), Some(7));
+        assert_eq!(spectrum.peak(), Some(9));
+        assert_eq!(
+            spectrum.genome_size(),
+            Some((8 * 5 + 9 * 20 + 10 * 20 + 11 * 6) / 9)
+        );
+
+        // the valley is where the counts first increase
+        spectrum.add_n(5, 30);
+        assert_eq!(spectrum.valley(), Some(4));
+        assert_eq!(spectrum.peak(), Some(5));
+    }
+
+    #[test]
+    fn valley_after_error_peak() {
+        // errors that peak above a count of 1
+        let mut spectrum = Spectrum::new();
+        for (count, n) in [(1, 10), (2, 50), (3, 20), (4, 5), (5, 30), (6, 40), (7, 10)] {
+            spectrum.add_n(count, n);
+        }
+        assert_eq!(spectrum.valley(), Some(4));
+        assert_eq!(spectrum.peak(), Some(6));
+
+        // a single count with no k-mers in the tail of errors
+        let mut spectrum = Spectrum::new();
+        for (count, n) in [
+            (1, 100),
+            (2, 30),
+            (4, 20),
+            (5, 10),
+            (6, 15),
+            (7, 30),
+            (8, 15),
+        ] {
+            spectrum.add_n(count, n);
+        }
+        assert_eq!(spectrum.valley(), Some(5));
+        assert_eq!(spectrum.peak(), Some(7));
+    }
+}
@@ -0,0 +1,209 @@
+//! De Bruijn graphs of k-mers.
+//!
+//! A [`DeBruijnGraph`] has a node for each distinct canonical k-mer, and an
+//! edge between two k-mers when the last `K - 1` bases of one are the first
+//! `K - 1` bases of the other, on either strand. Edges are not stored, but
+//! found by looking up the four possible neighbors of a k-mer.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::graph::DeBruijnGraph;
+//! use helicase::small::Kmer;
+//! use helicase::Base;
+//!
+//! let mut graph = DeBruijnGraph::<3>::new();
+//! let gac = Kmer::from_bases([Base::G, Base::A, Base::C]);
+//! graph.insert(gac);
+//! graph.insert(Kmer::from_bases([Base::A, Base::C, Base::C]));
+//!
+//! let next: Vec<String> = graph.successors(gac).map(|k| k.to_string()).collect();
+//! assert_eq!(next, ["ACC"]);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::Base;
+use crate::count::KmerCounter;
+use crate::small::Kmer;
+
+const BASES: [Base; 4] = [Base::C, Base::A, Base::T, Base::G];
+
+/// A node-centric de Bruijn graph of canonical k-mers and their counts.
+#[derive(Debug, Clone, Default)]
+pub struct DeBruijnGraph<const K: usize> {
+    /// Counts indexed by the masked value of each canonical k-mer.
+    nodes: HashMap<u64, u64>,
+}
+
+impl<const K: usize> DeBruijnGraph<K> {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a graph of the k-mers in a counter seen at least
+    /// `min_count` times, which drops most erroneous k-mers from reads.
+    pub fn from_counter(counter: &KmerCounter<K>, min_count: u64) -> Self {
+        let mut graph = Self::new();
+        // the counter may hold both strands of a k-mer, so sum them first
+        for (kmer, count) in counter.iter() {
+            *graph.nodes.entry(kmer.canonical().as_masked()).or_default() += count;
+        }
+        graph.nodes.retain(|_, &mut count| count >= min_count);
+        graph
+    }
+
+    /// Adds an occurrence of a k-mer, on either strand.
+    pub fn insert(&mut self, kmer: Kmer<K>) {
+        *self.nodes.entry(kmer.canonical().as_masked()).or_default() += 1;
+    }
+
+    /// Adds every k-mer in a sequence.
+    #[cfg(feature = "bitvec")]
+    pub fn add_sequence<B: bitvec::store::BitStore>(&mut self, seq: &crate::Sequence<B>) {
+        for kmer in seq.kmers::<K>() {
+            self.insert(kmer);
+        }
+    }
+
+    /// Returns the number of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if the k-mer, or its reverse complement, is a node.
+    pub fn contains(&self, kmer: Kmer<K>) -> bool {
+        self.nodes.contains_key(&kmer.canonical().as_masked())
+    }
+
+    /// Returns the number of times the k-mer, or its reverse complement,
+    /// was added.
+    pub fn count(&self, kmer: Kmer<K>) -> u64 {
+        self.nodes
+            .get(&kmer.canonical().as_masked())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns an iterator over the nodes, as canonical k-mers with their
+    /// counts, in arbitrary order.
+    pub fn nodes(&self) -> impl Iterator<Item = (Kmer<K>, u64)> + '_ {
+        self.nodes
+            .iter()
+            .map(|(&kmer, &count)| (Kmer::from(kmer), count))
+    }
+
+    /// Returns an iterator over the k-mers in the graph that follow `kmer`
+    /// by one base, on the same strand as `kmer`.
+    pub fn successors(&self, kmer: Kmer<K>) -> impl Iterator<Item = Kmer<K>> + '_ {
+        BASES.into_iter().filter_map(move |base| {
+            let mut next = kmer;
+            next.push(base);
+            self.contains(next).then_some(next)
+        })
+    }
+
+    /// Returns an iterator over the k-mers in the graph that precede `kmer`
+    /// by one base, on the same strand as `kmer`.
+    pub fn predecessors(&self, kmer: Kmer<K>) -> impl Iterator<Item = Kmer<K>> + '_ {
+        BASES.into_iter().filter_map(move |base| {
+            let mut prev = kmer;
+            prev.push_front(base);
+            self.contains(prev).then_some(prev)
+        })
+    }
+
+    /// Returns the number of successors of `kmer`.
+    pub fn out_degree(&self, kmer: Kmer<K>) -> usize {
+        self.successors(kmer).count()
+    }
+
+    /// Returns the number of predecessors of `kmer`.
+    pub fn in_degree(&self, kmer: Kmer<K>) -> usize {
+        self.predecessors(kmer).count()
+    }
+}
+
+impl<const K: usize> Extend<Kmer<K>> for DeBruijnGraph<K> {
+    fn extend<T: IntoIterator<Item = Kmer<K>>>(&mut self, iter: T) {
+        for kmer in iter {
+            self.insert(kmer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(kmers: impl Iterator<Item = Kmer<4>>) -> Vec<String> {
+        let mut strings: Vec<String> = kmers.map(|k| k.to_string()).collect();
+        strings.sort();
+        strings
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut graph = DeBruijnGraph::<4>::new();
+        graph.extend(["GATT", "ATTA", "ATTC", "CGAT"].map(|s| s.parse().unwrap()));
+        assert_eq!(graph.len(), 4);
+
+        assert_eq!(
+            strings(graph.successors("GATT".parse().unwrap())),
+            ["ATTA", "ATTC"]
+        );
+        assert_eq!(
+            strings(graph.predecessors("GATT".parse().unwrap())),
+            ["CGAT"]
+        );
+        assert_eq!(graph.out_degree("GATT".parse().unwrap()), 2);
+        assert_eq!(graph.in_degree("ATTA".parse().unwrap()), 1);
+
+        // the same edges, seen from the reverse strand
+        assert!(graph.contains("AATC".parse().unwrap()));
+        assert_eq!(
+            strings(graph.predecessors("AATC".parse().unwrap())),
+            ["GAAT", "TAAT"]
+        );
+        assert_eq!(strings(graph.successors("AATC".parse().unwrap())), ["ATCG"]);
+    }
+
+    #[test]
+    fn from_counter() {
+        let mut counter = KmerCounter::<4>::new();
+        counter
+            .extend(["GATT", "GATT", "AATC", "CCCC", "GGCA", "TGCC"].map(|s| s.parse().unwrap()));
+        let graph = DeBruijnGraph::from_counter(&counter, 3);
+        assert_eq!(graph.len(), 1);
+        // two copies on one strand and one on the other
+        assert_eq!(graph.count("AATC".parse().unwrap()), 3);
+        assert!(!graph.contains("CCCC".parse().unwrap()));
+        assert!(!graph.contains("GGCA".parse().unwrap()));
+
+        let graph = DeBruijnGraph::from_counter(&counter, 2);
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.count("TGCC".parse().unwrap()), 2);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn add_sequence() {
+        let seq = crate::Sequence::<u8>::try_from("GATTACAGG").unwrap();
+        let mut graph = DeBruijnGraph::<4>::new();
+        graph.add_sequence(&seq);
+        assert_eq!(graph.len(), 6);
+        let mut kmer: Kmer<4> = "GATT".parse().unwrap();
+        for _ in 0..5 {
+            assert_eq!(graph.out_degree(kmer), 1);
+            kmer = graph.successors(kmer).next().unwrap();
+        }
+        assert_eq!(kmer.to_string(), "CAGG");
+        assert_eq!(graph.out_degree(kmer), 0);
+    }
+}
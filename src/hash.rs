@@ -0,0 +1,525 @@
+//! Hash functions for k-mers.
+//!
+//! # ntHash
+//!
+//! [ntHash] is a rolling hash: the hash of a k-mer can be updated in constant
+//! time as bases are pushed onto it, regardless of `k`.
+//!
+//! ```
+//! use helicase::hash::nthash;
+//! use helicase::Base;
+//!
+//! let bases = [Base::G, Base::A, Base::T, Base::T, Base::A];
+//! let hash = nthash::hash(bases[..4].iter().copied());
+//! let rolled = nthash::roll(hash, 4, Base::G, Base::A);
+//! assert_eq!(rolled, nthash::hash(bases[1..].iter().copied()));
+//! ```
+//!
+//! # ntHash2
+//!
+//! [ntHash2] replaces the 64-bit rotation of ntHash with a split rotation of
+//! 33 and 31 bits, which has a much longer period, and combines the hashes of
+//! both strands into a canonical hash by addition. Extra hashes for Bloom
+//! filters can be derived from each canonical hash cheaply.
+//!
+//! ```
+//! use helicase::hash::nthash2;
+//! use helicase::Base;
+//!
+//! let bases = [Base::G, Base::A, Base::T, Base::C];
+//! let rc = bases.iter().rev().map(|base| base.complement());
+//! let forward = nthash2::hash(bases);
+//! let reverse = nthash2::reverse_hash(bases);
+//! assert_eq!(reverse, nthash2::hash(rc));
+//! assert_eq!(
+//!     nthash2::canonical(forward, reverse),
+//!     nthash2::canonical(reverse, forward),
+//! );
+//! ```
+//!
+//! # Invertible mixing
+//!
+//! The [`mix`] hash scrambles a packed k-mer with a bijection, so a hash can
+//! be stored in place of the k-mer and mapped back with [`mix::unhash`].
+//!
+//! ```
+//! use helicase::hash::mix;
+//!
+//! let key = 0b00_01_01_11_10;
+//! let hash = mix::hash(key);
+//! assert_ne!(hash, key);
+//! assert_eq!(mix::unhash(hash), key);
+//! ```
+//!
+//! # MurmurHash3
+//!
+//! [`murmur3`] hashes bytes rather than packed k-mers. It is the hash used by
+//! sourmash, so sketches of the ASCII of canonical k-mers can be compared
+//! with sourmash sketches.
+//!
+//! ```
+//! use helicase::hash::murmur3;
+//!
+//! let (low, _) = murmur3::hash_x64_128(b"ACG", 42);
+//! assert_eq!(low, 1731421407650554201);
+//! ```
+//!
+//! [ntHash]: https://doi.org/10.1093/bioinformatics/btw397
+//! [ntHash2]: https://doi.org/10.1093/bioinformatics/btac564
+
+pub mod nthash {
+    //! The ntHash rolling hash.
+
+    use crate::Base;
+
+    /// Seeds for each base, indexed by 2-bit code.
+    const SEEDS: [u64; 4] = [
+        0x3193_c185_62a0_2b4c, // C
+        0x3c8b_fbb3_95c6_0474, // A
+        0x2955_49f5_4be2_4456, // T
+        0x2032_3ed0_8257_2324, // G
+    ];
+
+    /// Returns the hash of a single base.
+    pub const fn seed(base: Base) -> u64 {
+        SEEDS[base as usize]
+    }
+
+    /// Computes the forward hash of a k-mer from its bases.
+    pub fn hash(bases: impl IntoIterator<Item = Base>) -> u64 {
+        bases
+            .into_iter()
+            .fold(0, |hash, base| hash.rotate_left(1) ^ seed(base))
+    }
+
+    /// Updates the forward hash of a k-mer of size `k` when `out` is removed
+    /// from the front and `base` is pushed onto the back.
+    pub const fn roll(hash: u64, k: usize, out: Base, base: Base) -> u64 {
+        hash.rotate_left(1) ^ seed(out).rotate_left((k % 64) as u32) ^ seed(base)
+    }
+
+    /// Computes the hash of the reverse complement of a k-mer from its bases.
+    pub fn reverse_hash(bases: impl IntoIterator<Item = Base>) -> u64 {
+        bases.into_iter().enumerate().fold(0, |hash, (i, base)| {
+            hash ^ seed(base.complement()).rotate_left((i % 64) as u32)
+        })
+    }
+
+    /// Updates the reverse complement hash of a k-mer of size `k` when `out`
+    /// is removed from the front and `base` is pushed onto the back.
+    pub const fn roll_reverse(hash: u64, k: usize, out: Base, base: Base) -> u64 {
+        (hash ^ seed(out.complement())).rotate_right(1)
+            ^ seed(base.complement()).rotate_left(((k - 1) % 64) as u32)
+    }
+
+    /// Combines the forward and reverse complement hashes of a k-mer into a
+    /// hash that is the same for both strands.
+    pub const fn canonical(forward: u64, reverse: u64) -> u64 {
+        if forward < reverse { forward } else { reverse }
+    }
+}
+
+pub mod nthash2 {
+    //! The ntHash2 rolling hash.
+    //!
+    //! Bits `0..33` and `33..64` of a hash are rotated separately, so the
+    //! seed of a base only repeats after `31 * 33` positions.
+
+    use super::nthash::seed;
+    use crate::Base;
+
+    /// Multiplier used to derive extra hashes.
+    const MULTI_SEED: u64 = 0x90b4_5d39_fb6d_a1fa;
+
+    /// Shift used to mix extra hashes.
+    const MULTI_SHIFT: u32 = 27;
+
+    const LOW_BITS: u32 = 33;
+    const HIGH_BITS: u32 = 31;
+    const LOW_MASK: u64 = (1 << LOW_BITS) - 1;
+
+    /// Rotates the low `bits` bits of `value` left by `n < bits`.
+    const fn rotate(value: u64, bits: u32, n: u32) -> u64 {
+        (value << n | value >> (bits - n)) & ((1 << bits) - 1)
+    }
+
+    /// Rotates the 33-bit and 31-bit parts of `hash` left by `n`.
+    pub const fn split_rotate_left(hash: u64, n: usize) -> u64 {
+        let low = rotate(hash & LOW_MASK, LOW_BITS, (n % LOW_BITS as usize) as u32);
+        let high = rotate(hash >> LOW_BITS, HIGH_BITS, (n % HIGH_BITS as usize) as u32);
+        high << LOW_BITS | low
+    }
+
+    /// Rotates the 33-bit and 31-bit parts of `hash` right by one.
+    pub const fn split_rotate_right(hash: u64) -> u64 {
+        let low = rotate(hash & LOW_MASK, LOW_BITS, LOW_BITS - 1);
+        let high = rotate(hash >> LOW_BITS, HIGH_BITS, HIGH_BITS - 1);
+        high << LOW_BITS | low
+    }
+
+    /// Computes the forward hash of a k-mer from its bases.
+    pub fn hash(bases: impl IntoIterator<Item = Base>) -> u64 {
+        bases
+            .into_iter()
+            .fold(0, |hash, base| split_rotate_left(hash, 1) ^ seed(base))
+    }
+
+    /// Updates the forward hash of a k-mer of size `k` when `out` is removed
+    /// from the front and `base` is pushed onto the back.
+    pub const fn roll(hash: u64, k: usize, out: Base, base: Base) -> u64 {
+        split_rotate_left(hash, 1) ^ split_rotate_left(seed(out), k) ^ seed(base)
+    }
+
+    /// Computes the hash of the reverse complement of a k-mer from its bases.
+    pub fn reverse_hash(bases: impl IntoIterator<Item = Base>) -> u64 {
+        bases.into_iter().enumerate().fold(0, |hash, (i, base)| {
+            hash ^ split_rotate_left(seed(base.complement()), i)
+        })
+    }
+
+    /// Updates the reverse complement hash of a k-mer of size `k` when `out`
+    /// is removed from the front and `base` is pushed onto the back.
+    pub const fn roll_reverse(hash: u64, k: usize, out: Base, base: Base) -> u64 {
+        split_rotate_right(hash ^ seed(out.complement()))
+            ^ split_rotate_left(seed(base.complement()), k - 1)
+    }
+
+    /// Combines the forward and reverse complement hashes of a k-mer into a
+    /// hash that is the same for both strands.
+    pub const fn canonical(forward: u64, reverse: u64) -> u64 {
+        forward.wrapping_add(reverse)
+    }
+
+    /// Derives the `i`th of several hashes of a k-mer of size `k` from its
+    /// canonical hash, such as for the hash functions of a Bloom filter.
+    ///
+    /// The 0th hash is the canonical hash itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::hash::nthash2;
+    ///
+    /// let hash = 0x0123_4567_89ab_cdef;
+    /// let hashes: Vec<u64> = (0..4).map(|i| nthash2::extend(hash, 21, i)).collect();
+    /// assert_eq!(hashes[0], hash);
+    /// assert!(hashes[1..].iter().all(|&h| h != hash));
+    /// ```
+    pub const fn extend(hash: u64, k: usize, i: usize) -> u64 {
+        if i == 0 {
+            return hash;
+        }
+        let hash = hash.wrapping_mul(i as u64 ^ (k as u64).wrapping_mul(MULTI_SEED));
+        hash ^ hash >> MULTI_SHIFT
+    }
+
+    /// Computes the forward and reverse complement hashes of a spaced seed
+    /// from the bases of its window.
+    ///
+    /// Bit `i` of `shape` marks position `i` of the window as a care
+    /// position, as in [`Sequence::spaced_seeds`](crate::Sequence::spaced_seeds);
+    /// the other positions do not affect the hashes. The reverse complement
+    /// hash reads the care positions of the forward strand, so
+    /// [`canonical`] is only the same for both strands if `shape` is
+    /// symmetric.
+    pub fn spaced_hashes(bases: impl IntoIterator<Item = Base>, shape: u64) -> (u64, u64) {
+        let span = 64 - shape.leading_zeros() as usize;
+        bases
+            .into_iter()
+            .take(span)
+            .enumerate()
+            .filter(|&(i, _)| shape >> i & 1 == 1)
+            .fold((0, 0), |(forward, reverse), (i, base)| {
+                (
+                    forward ^ split_rotate_left(seed(base), span - 1 - i),
+                    reverse ^ split_rotate_left(seed(base.complement()), i),
+                )
+            })
+    }
+}
+
+pub mod mix {
+    //! An invertible hash of 64-bit keys.
+    //!
+    //! Based on the finalizer of MurmurHash3, which alternates xor-shifts with
+    //! multiplications by odd constants. Each step is a bijection, so the
+    //! hash can be undone one step at a time. [`hash_bits`] applies the same
+    //! steps modulo `2^bits`, keeping the hash of a k-mer within its `2k`
+    //! bits.
+
+    const MULTIPLIERS: [u64; 2] = [0xff51_afd7_ed55_8ccd, 0xc4ce_b9fe_1a85_ec53];
+
+    /// The inverses of [`MULTIPLIERS`] modulo `2^64`.
+    const INVERSES: [u64; 2] = [0x4f74_430c_22a5_4005, 0x9cb4_b2f8_1293_37db];
+
+    const fn mask(bits: u32) -> u64 {
+        u64::MAX >> (64 - bits)
+    }
+
+    /// Undoes `x ^= x >> shift` on a value of `bits` bits.
+    const fn unshift(x: u64, shift: u32, bits: u32) -> u64 {
+        let mut result = x;
+        let mut shifted = shift;
+        while shifted < bits {
+            result ^= x >> shifted;
+            shifted += shift;
+        }
+        result
+    }
+
+    /// Hashes a 64-bit key.
+    pub const fn hash(key: u64) -> u64 {
+        hash_bits(key, 64)
+    }
+
+    /// Recovers the key from a hash returned by [`hash`].
+    pub const fn unhash(hash: u64) -> u64 {
+        unhash_bits(hash, 64)
+    }
+
+    /// Hashes the low `bits` bits of `key` to a value of `bits` bits,
+    /// ignoring any higher bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not in `1..=64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::hash::mix;
+    ///
+    /// // every 10-bit key maps to a different 10-bit hash
+    /// let mut hashes: Vec<u64> = (0..1 << 10).map(|key| mix::hash_bits(key, 10)).collect();
+    /// assert!(hashes.iter().all(|&hash| hash < 1 << 10));
+    /// hashes.sort_unstable();
+    /// hashes.dedup();
+    /// assert_eq!(hashes.len(), 1 << 10);
+    /// ```
+    pub const fn hash_bits(key: u64, bits: u32) -> u64 {
+        assert!(bits > 0 && bits <= 64, "bits must be in 1..=64");
+        let mask = mask(bits);
+        let shift = bits / 2 + 1;
+        let mut x = key & mask;
+        x ^= x >> shift;
+        x = x.wrapping_mul(MULTIPLIERS[0]) & mask;
+        x ^= x >> shift;
+        x = x.wrapping_mul(MULTIPLIERS[1]) & mask;
+        x ^ x >> shift
+    }
+
+    /// Recovers the key from a hash returned by [`hash_bits`] with the same
+    /// `bits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not in `1..=64`.
+    pub const fn unhash_bits(hash: u64, bits: u32) -> u64 {
+        assert!(bits > 0 && bits <= 64, "bits must be in 1..=64");
+        let mask = mask(bits);
+        let shift = bits / 2 + 1;
+        let mut x = unshift(hash & mask, shift, bits);
+        x = x.wrapping_mul(INVERSES[1]) & mask;
+        x = unshift(x, shift, bits);
+        x = x.wrapping_mul(INVERSES[0]) & mask;
+        unshift(x, shift, bits)
+    }
+}
+
+pub mod murmur3 {
+    //! The 128-bit x64 variant of MurmurHash3.
+
+    const C1: u64 = 0x87c3_7b91_1142_53d5;
+    const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+    /// Mixes the final bits of a hash.
+    const fn fmix(mut x: u64) -> u64 {
+        x ^= x >> 33;
+        x = x.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        x ^= x >> 33;
+        x = x.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        x ^ x >> 33
+    }
+
+    const fn mix_k1(k1: u64) -> u64 {
+        k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2)
+    }
+
+    const fn mix_k2(k2: u64) -> u64 {
+        k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1)
+    }
+
+    /// Reads up to 8 bytes from `bytes[start..end]` as a little-endian
+    /// word.
+    const fn read_le(bytes: &[u8], start: usize, end: usize) -> u64 {
+        let mut word = 0;
+        let mut i = end;
+        while i > start {
+            i -= 1;
+            word = word << 8 | bytes[i] as u64;
+        }
+        word
+    }
+
+    /// Hashes `bytes` with MurmurHash3_x64_128, returning the low and high
+    /// 64 bits of the hash.
+    pub const fn hash_x64_128(bytes: &[u8], seed: u32) -> (u64, u64) {
+        let len = bytes.len();
+        let (mut h1, mut h2) = (seed as u64, seed as u64);
+
+        let blocks = len / 16;
+        let mut i = 0;
+        while i < blocks {
+            h1 ^= mix_k1(read_le(bytes, i * 16, i * 16 + 8));
+            h1 = h1.rotate_left(27).wrapping_add(h2);
+            h1 = h1.wrapping_mul(5).wrapping_add(0x52dc_e729);
+            h2 ^= mix_k2(read_le(bytes, i * 16 + 8, i * 16 + 16));
+            h2 = h2.rotate_left(31).wrapping_add(h1);
+            h2 = h2.wrapping_mul(5).wrapping_add(0x3849_5ab5);
+            i += 1;
+        }
+
+        let tail = blocks * 16;
+        if len > tail + 8 {
+            h2 ^= mix_k2(read_le(bytes, tail + 8, len));
+        }
+        if len > tail {
+            let end = if len > tail + 8 { tail + 8 } else { len };
+            h1 ^= mix_k1(read_le(bytes, tail, end));
+        }
+
+        h1 ^= len as u64;
+        h2 ^= len as u64;
+        h1 = h1.wrapping_add(h2);
+        h2 = h2.wrapping_add(h1);
+        h1 = fmix(h1);
+        h2 = fmix(h2);
+        h1 = h1.wrapping_add(h2);
+        h2 = h2.wrapping_add(h1);
+        (h1, h2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Base;
+    use crate::utils::test::random_bases;
+
+    #[test]
+    fn murmur3() {
+        // reference values from the murmur3 crate
+        for (bytes, expected) in [
+            (&b""[..], (17305828677633410339, 15060430851467758521)),
+            (b"ACG", (1731421407650554201, 16833509965258253025)),
+            (
+                b"ACGTGCGAGTGAG",
+                (15767317921485024853, 4421994828586104156),
+            ),
+            (
+                b"ATGCATGCATGCATGCATGCATGCATGCATGCA",
+                (13148226557147633486, 11226627917657006771),
+            ),
+        ] {
+            assert_eq!(murmur3::hash_x64_128(bytes, 42), expected);
+        }
+    }
+
+    #[test]
+    fn nthash_reverse() {
+        let bases = random_bases(200, 0);
+        for k in [1, 5, 31, 63, 64, 65, 100] {
+            let mut hash = nthash::reverse_hash(bases[..k].iter().copied());
+            for i in k..bases.len() {
+                hash = nthash::roll_reverse(hash, k, bases[i - k], bases[i]);
+                let window = &bases[i + 1 - k..=i];
+                let rc = window.iter().rev().map(|b| b.complement());
+                assert_eq!(
+                    hash,
+                    nthash::reverse_hash(window.iter().copied()),
+                    "k = {k}"
+                );
+                assert_eq!(hash, nthash::hash(rc), "k = {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn nthash_roll() {
+        let bases = random_bases(200, 0);
+        for k in [1, 5, 31, 63, 64, 65, 100] {
+            let mut hash = nthash::hash(bases[..k].iter().copied());
+            for i in k..bases.len() {
+                hash = nthash::roll(hash, k, bases[i - k], bases[i]);
+                assert_eq!(
+                    hash,
+                    nthash::hash(bases[i + 1 - k..=i].iter().copied()),
+                    "k = {k}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nthash2_roll() {
+        let bases = random_bases(200, 0);
+        for k in [1, 5, 31, 33, 64, 65, 100] {
+            let mut forward = nthash2::hash(bases[..k].iter().copied());
+            let mut reverse = nthash2::reverse_hash(bases[..k].iter().copied());
+            for i in k..bases.len() {
+                forward = nthash2::roll(forward, k, bases[i - k], bases[i]);
+                reverse = nthash2::roll_reverse(reverse, k, bases[i - k], bases[i]);
+                let window = &bases[i + 1 - k..=i];
+                let rc = window.iter().rev().map(|b| b.complement());
+                assert_eq!(forward, nthash2::hash(window.iter().copied()), "k = {k}");
+                assert_eq!(reverse, nthash2::hash(rc), "k = {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn nthash2_split_rotate() {
+        let hash = 0xdead_beef_0123_4567;
+        assert_eq!(nthash2::split_rotate_left(hash, 0), hash);
+        assert_eq!(nthash2::split_rotate_left(hash, 31 * 33), hash);
+        assert_ne!(nthash2::split_rotate_left(hash, 64), hash);
+        let left = nthash2::split_rotate_left(hash, 1);
+        assert_eq!(nthash2::split_rotate_right(left), hash);
+        // the top bit moves to bit 33, and bit 32 to bit 0
+        assert_eq!(nthash2::split_rotate_left(1 << 63, 1), 1 << 33);
+        assert_eq!(nthash2::split_rotate_left(1 << 32, 1), 1);
+    }
+
+    #[test]
+    fn nthash2_spaced() {
+        let bases = [Base::G, Base::A, Base::T, Base::T, Base::A];
+        let (forward, reverse) = nthash2::spaced_hashes(bases, 0b11111);
+        assert_eq!(forward, nthash2::hash(bases));
+        assert_eq!(reverse, nthash2::reverse_hash(bases));
+
+        // don't-care positions are ignored
+        let other = [Base::G, Base::C, Base::T, Base::G, Base::A];
+        assert_eq!(
+            nthash2::spaced_hashes(bases, 0b10101),
+            nthash2::spaced_hashes(other, 0b10101)
+        );
+        assert_ne!(
+            nthash2::spaced_hashes(bases, 0b10111),
+            nthash2::spaced_hashes(other, 0b10111)
+        );
+    }
+
+    #[test]
+    fn mix_inverse() {
+        assert_eq!(mix::hash(0), 0);
+        for bits in [1, 2, 10, 31, 33, 62, 64] {
+            for i in 0..1000u64 {
+                let key = i.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> (64 - bits);
+                let hash = mix::hash_bits(key, bits);
+                assert!(bits == 64 || hash >> bits == 0);
+                assert_eq!(mix::unhash_bits(hash, bits), key, "bits = {bits}");
+            }
+        }
+        assert_eq!(mix::unhash(mix::hash(u64::MAX)), u64::MAX);
+    }
+}
@@ -0,0 +1,174 @@
+//! An index of the positions of k-mers in a reference sequence.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::index::KmerIndex;
+//! use helicase::small::Kmer;
+//! use helicase::{Base, Sequence};
+//!
+//! let reference: Sequence = "GATTACAGATTACA".try_into().unwrap();
+//! let index = KmerIndex::<4>::new(&reference);
+//! let gatt = Kmer::from_bases([Base::G, Base::A, Base::T, Base::T]);
+//! assert_eq!(index.get(gatt), [0, 7]);
+//! ```
+
+use alloc::vec::Vec;
+
+use bitvec::store::BitStore;
+
+use crate::Sequence;
+use crate::small::Kmer;
+
+/// The positions of every k-mer in a sequence, sorted by k-mer.
+///
+/// Positions are the index of the first base of each occurrence. The index
+/// is stored as sorted arrays rather than a hash map, so it takes at most 24
+/// bytes per position and lookups are a binary search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmerIndex<const K: usize> {
+    /// The masked value of each distinct k-mer, in increasing order.
+    kmers: Vec<u64>,
+    /// The start of the positions of each k-mer, followed by the total
+    /// number of positions.
+    offsets: Vec<usize>,
+    /// The positions of each k-mer in turn, each in increasing order.
+    positions: Vec<usize>,
+    canonical: bool,
+}
+
+impl<const K: usize> KmerIndex<K> {
+    /// Builds an index of the k-mers of `seq`.
+    pub fn new<B: BitStore>(seq: &Sequence<B>) -> Self {
+        Self::build(seq.kmers::<K>().map(|kmer| kmer.as_masked()), false)
+    }
+
+    /// Builds an index of the canonical k-mers of `seq`, so that a k-mer and
+    /// its reverse complement share their positions.
+    pub fn canonical<B: BitStore>(seq: &Sequence<B>) -> Self {
+        Self::build(
+            seq.canonical_kmers::<K>().map(|kmer| kmer.as_masked()),
+            true,
+        )
+    }
+
+    fn build(kmers: impl Iterator<Item = u64>, canonical: bool) -> Self {
+        let mut pairs: Vec<(u64, usize)> = kmers.zip(0..).collect();
+        pairs.sort_unstable();
+
+        let mut index = Self {
+            kmers: Vec::new(),
+            offsets: Vec::new(),
+            positions: Vec::with_capacity(pairs.len()),
+            canonical,
+        };
+        for (kmer, pos) in pairs {
+            if index.kmers.last() != Some(&kmer) {
+                index.kmers.push(kmer);
+                index.offsets.push(index.positions.len());
+            }
+            index.positions.push(pos);
+        }
+        index.offsets.push(index.positions.len());
+        index
+    }
+
+    /// Returns `true` if the index is of canonical k-mers.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Returns the number of distinct k-mers in the index.
+    pub fn len(&self) -> usize {
+        self.kmers.len()
+    }
+
+    /// Returns `true` if the index holds no k-mers.
+    pub fn is_empty(&self) -> bool {
+        self.kmers.is_empty()
+    }
+
+    /// Returns the total number of positions in the index.
+    pub fn positions(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the positions of `kmer` in increasing order, or an empty
+    /// slice if it does not occur.
+    ///
+    /// For a canonical index, these are the positions of both `kmer` and
+    /// its reverse complement.
+    pub fn get(&self, kmer: Kmer<K>) -> &[usize] {
+        let kmer = if self.canonical {
+            kmer.canonical()
+        } else {
+            kmer
+        };
+        match self.kmers.binary_search(&kmer.as_masked()) {
+            Ok(i) => &self.positions[self.offsets[i]..self.offsets[i + 1]],
+            Err(_) => &[],
+        }
+    }
+
+    /// Returns `true` if `kmer` occurs in the indexed sequence.
+    pub fn contains(&self, kmer: Kmer<K>) -> bool {
+        !self.get(kmer).is_empty()
+    }
+
+    /// Returns an iterator over the distinct k-mers and their positions,
+    /// in increasing order of k-mer.
+    pub fn iter(&self) -> impl Iterator<Item = (Kmer<K>, &[usize])> + '_ {
+        self.kmers
+            .iter()
+            .zip(self.offsets.windows(2))
+            .map(|(&kmer, range)| (Kmer::from(kmer), &self.positions[range[0]..range[1]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn positions() {
+        let ascii = random_ascii(500, 0).into_bytes();
+        let seq = Sequence::<u32>::try_from_ascii(&ascii).unwrap();
+        let index = KmerIndex::<6>::new(&seq);
+        assert_eq!(index.positions(), 495);
+
+        for (i, kmer) in seq.kmers::<6>().enumerate() {
+            let positions = index.get(kmer);
+            assert!(positions.binary_search(&i).is_ok());
+            assert!(positions.is_sorted());
+            for &pos in positions {
+                assert_eq!(ascii[pos..pos + 6], ascii[i..i + 6]);
+            }
+        }
+        let total: usize = index.iter().map(|(_, positions)| positions.len()).sum();
+        assert_eq!(total, 495);
+        assert!(index.iter().map(|(kmer, _)| kmer.as_masked()).is_sorted());
+    }
+
+    #[test]
+    fn canonical() {
+        let seq = Sequence::<u8>::try_from("GATTACAATC").unwrap();
+        let index = KmerIndex::<3>::canonical(&seq);
+        assert!(index.is_canonical());
+        let gat = seq.kmers::<3>().next().unwrap();
+        // `ATC` at 7 is the reverse complement of `GAT`
+        assert_eq!(index.get(gat), [0, 7]);
+        assert_eq!(index.get(gat.reverse_complement()), [0, 7]);
+
+        let forward = KmerIndex::<3>::new(&seq);
+        assert_eq!(forward.get(gat), [0]);
+        assert!(!forward.contains(Kmer::from_bases([crate::Base::G; 3])));
+    }
+
+    #[test]
+    fn empty() {
+        let index = KmerIndex::<8>::new(&Sequence::<u8>::try_from("GATTACA").unwrap());
+        assert!(index.is_empty());
+        assert_eq!(index.iter().count(), 0);
+    }
+}
@@ -0,0 +1,8 @@
+//! Conversions from the types of other crates.
+//!
+//! Each integration is behind a feature named after the crate it supports.
+
+#[cfg(feature = "needletail")]
+pub mod needletail;
+#[cfg(feature = "noodles")]
+pub mod noodles;
@@ -0,0 +1,214 @@
+//! Conversions from [needletail](https://docs.rs/needletail) records.
+//!
+//! Records can be converted into a [`Sequence`], which fails on ambiguous
+//! bases, a [`MaskedSequence`], or a [`Record`] that also keeps the id and
+//! qualities. [`SequenceRecordExt`] iterates over the k-mers of a record
+//! directly from its ASCII, without packing the whole sequence first.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::interop::needletail::SequenceRecordExt;
+//! use helicase::Sequence;
+//!
+//! let mut reader = needletail::parse_fastx_reader(&b">seq\nGATT\nACA\n"[..]).unwrap();
+//! let record = reader.next().unwrap().unwrap();
+//!
+//! let seq = Sequence::<usize>::try_from(&record).unwrap();
+//! assert_eq!(seq.to_string(), "GATTACA");
+//!
+//! let kmers: Vec<String> = record.kmers::<5>().map(|(_, kmer)| kmer.to_string()).collect();
+//! assert_eq!(kmers, ["GATTA", "ATTAC", "TTACA"]);
+//! ```
+
+use std::borrow::Cow;
+use std::iter::FusedIterator;
+
+use ::needletail::parser::SequenceRecord;
+use bitvec::store::BitStore;
+
+use crate::base::{Base, ParseBaseError};
+use crate::sequence::{MaskedSequence, Sequence};
+use crate::small::Kmer;
+
+impl<B: BitStore> TryFrom<&SequenceRecord<'_>> for Sequence<B> {
+    type Error = ParseBaseError;
+
+    /// Packs the sequence of a record, failing at the first character that
+    /// is not a base.
+    ///
+    /// Positions in the error do not count the line breaks of multi-line
+    /// FASTA records.
+    fn try_from(record: &SequenceRecord<'_>) -> Result<Self, Self::Error> {
+        Self::try_from_ascii(&record.seq())
+    }
+}
+
+impl<B: BitStore> From<&SequenceRecord<'_>> for MaskedSequence<B> {
+    /// Packs the sequence of a record, marking ambiguous and soft-masked
+    /// positions.
+    fn from(record: &SequenceRecord<'_>) -> Self {
+        Self::from_ascii(&record.seq())
+    }
+}
+
+/// An owned record with its sequence packed.
+///
+/// Unlike a [`SequenceRecord`], which borrows the parser's buffer, a `Record`
+/// can be kept after the parser moves on to the next record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<B: BitStore = usize> {
+    id: Vec<u8>,
+    sequence: MaskedSequence<B>,
+    qualities: Option<Vec<u8>>,
+}
+
+impl<B: BitStore> From<&SequenceRecord<'_>> for Record<B> {
+    fn from(record: &SequenceRecord<'_>) -> Self {
+        Self {
+            id: record.id().to_vec(),
+            sequence: MaskedSequence::from(record),
+            qualities: record.qual().map(<[u8]>::to_vec),
+        }
+    }
+}
+
+impl<B: BitStore> Record<B> {
+    /// Returns the id line of the record, without the leading `>` or `@`.
+    pub fn id(&self) -> &[u8] {
+        &self.id
+    }
+
+    /// Returns the packed sequence of the record.
+    pub fn sequence(&self) -> &MaskedSequence<B> {
+        &self.sequence
+    }
+
+    /// Returns the ASCII qualities of a FASTQ record, or `None` for FASTA.
+    pub fn qualities(&self) -> Option<&[u8]> {
+        self.qualities.as_deref()
+    }
+
+    /// Returns the id, sequence and qualities of the record.
+    pub fn into_parts(self) -> (Vec<u8>, MaskedSequence<B>, Option<Vec<u8>>) {
+        (self.id, self.sequence, self.qualities)
+    }
+}
+
+/// Extends needletail records with k-mer iterators.
+pub trait SequenceRecordExt {
+    /// Returns an iterator over the k-mers of the record and their
+    /// positions, skipping k-mers that overlap a character that is not a
+    /// base.
+    ///
+    /// Positions do not count the line breaks of multi-line FASTA records,
+    /// matching [`MaskedSequence::kmers`].
+    fn kmers<const K: usize>(&self) -> RecordKmerIter<'_, K>;
+}
+
+impl SequenceRecordExt for SequenceRecord<'_> {
+    fn kmers<const K: usize>(&self) -> RecordKmerIter<'_, K> {
+        RecordKmerIter {
+            seq: self.seq(),
+            next: 0,
+            kmer: Kmer::new(),
+            run: 0,
+        }
+    }
+}
+
+/// An iterator over the k-mers of a needletail record.
+///
+/// Created with [`SequenceRecordExt::kmers`].
+#[derive(Debug)]
+pub struct RecordKmerIter<'a, const K: usize> {
+    seq: Cow<'a, [u8]>,
+    /// The index of the next character to read.
+    next: usize,
+    kmer: Kmer<K>,
+    /// The number of bases read since the last character that was not a
+    /// base.
+    run: usize,
+}
+
+impl<const K: usize> Iterator for RecordKmerIter<'_, K> {
+    type Item = (usize, Kmer<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&byte) = self.seq.get(self.next) {
+            self.next += 1;
+            match Base::from_ascii(byte) {
+                Some(base) => {
+                    self.kmer.push(base);
+                    self.run += 1;
+                    if self.run >= K {
+                        return Some((self.next - K, self.kmer));
+                    }
+                }
+                None => self.run = 0,
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.seq.len() - self.next;
+        (
+            0,
+            Some((remaining + self.run.min(K - 1) + 1).saturating_sub(K)),
+        )
+    }
+}
+
+impl<const K: usize> FusedIterator for RecordKmerIter<'_, K> {}
+
+#[cfg(test)]
+mod tests {
+    use ::needletail::parse_fastx_reader;
+
+    use super::*;
+
+    const FASTQ: &[u8] = b"@r1 desc\nGATTNACAGATtaca\n+\nIIIIIIIIIIIIIII\n@r2\nACGT\n+\n####\n";
+
+    #[test]
+    fn record() {
+        let mut reader = parse_fastx_reader(FASTQ).unwrap();
+        let first = reader.next().unwrap().unwrap();
+        assert!(Sequence::<u8>::try_from(&first).is_err());
+
+        let record = Record::<u8>::from(&first);
+        assert_eq!(record.id(), b"r1 desc");
+        assert_eq!(record.qualities(), Some(&b"IIIIIIIIIIIIIII"[..]));
+        assert_eq!(record.sequence().ambiguous_count(), 1);
+        assert!(record.sequence().masked_regions().eq(Some(11..15)));
+
+        let second = reader.next().unwrap().unwrap();
+        let seq = Sequence::<u8>::try_from(&second).unwrap();
+        assert_eq!(seq.to_string(), "ACGT");
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn kmers() {
+        let fasta = b">seq\nGATTNAC\nAGATtaca\n";
+        let mut reader = parse_fastx_reader(&fasta[..]).unwrap();
+        let record = reader.next().unwrap().unwrap();
+
+        let masked = MaskedSequence::<usize>::from(&record);
+        let expected: Vec<(usize, u64)> = masked
+            .kmers::<4>()
+            .map(|(i, kmer)| (i, kmer.as_masked()))
+            .collect();
+        let kmers: Vec<(usize, u64)> = record
+            .kmers::<4>()
+            .map(|(i, kmer)| (i, kmer.as_masked()))
+            .collect();
+        assert_eq!(kmers, expected);
+        assert_eq!(kmers.first().map(|&(i, _)| i), Some(0));
+        assert_eq!(kmers.get(1).map(|&(i, _)| i), Some(5));
+
+        let iter = record.kmers::<4>();
+        assert!(iter.size_hint().1.unwrap() >= kmers.len());
+        assert_eq!(record.kmers::<20>().count(), 0);
+    }
+}
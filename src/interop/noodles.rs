@@ -0,0 +1,412 @@
+//! Conversions to and from [noodles](https://docs.rs/noodles) records.
+//!
+//! FASTA, FASTQ and BAM records convert into a [`Sequence`], which fails on
+//! ambiguous bases, or an [`IupacSequence`], which keeps them. Both convert
+//! back into the sequence types of FASTA records and of the alignment
+//! records that noodles writes as SAM or BAM. A [`Record`] also keeps the
+//! name and quality scores, and converts back into a whole record.
+//!
+//! BAM sequences are stored as 4-bit masks that are the same as those of
+//! [`Iupac`] codes, so they are converted without going through ASCII. The
+//! BAM code `=`, for a base that matches the reference, is read as a gap,
+//! and gaps are written as `=`.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::interop::noodles::Record;
+//! use helicase::Sequence;
+//! use noodles::fastq;
+//!
+//! let mut reader = fastq::io::Reader::new(&b"@r1\nGATTACA\n+\nIIIII##\n"[..]);
+//! let fastq = reader.records().next().unwrap().unwrap();
+//!
+//! let seq = Sequence::<usize>::try_from(&fastq).unwrap();
+//! assert_eq!(seq.to_string(), "GATTACA");
+//!
+//! let record = Record::try_from(&fastq).unwrap();
+//! assert_eq!(record.quality_scores(), Some(&[40, 40, 40, 40, 40, 2, 2][..]));
+//! assert_eq!(record.to_fastq(), Some(fastq));
+//! ```
+
+use ::noodles::sam::alignment::{RecordBuf, record_buf};
+use ::noodles::{bam, fasta, fastq};
+use bitvec::store::BitStore;
+
+use crate::base::{Iupac, ParseBaseError};
+use crate::sequence::{IupacSequence, Sequence};
+
+/// The offset of Phred quality scores in FASTQ characters.
+const PHRED_OFFSET: u8 = b'!';
+
+/// The first byte of the quality scores of a BAM record that has none.
+const MISSING_QUALITY: u8 = 0xff;
+
+/// Returns the codes of a BAM record sequence.
+fn bam_codes<'a>(seq: &bam::record::Sequence<'a>) -> impl Iterator<Item = Iupac> + use<'a> {
+    seq.as_bytes()
+        .iter()
+        .flat_map(|&byte| [byte >> 4, byte & 0x0f])
+        .take(seq.len())
+        .map(|mask| Iupac::from_mask(mask).unwrap())
+}
+
+/// Returns the character that SAM and BAM use for a code.
+const fn bam_ascii(code: Iupac) -> u8 {
+    if code.mask() == Iupac::GAP.mask() {
+        b'='
+    } else {
+        code.to_ascii()
+    }
+}
+
+impl<B: BitStore> TryFrom<&fasta::Record> for Sequence<B> {
+    type Error = ParseBaseError;
+
+    /// Packs the sequence of a record, failing at the first character that
+    /// is not a base.
+    fn try_from(record: &fasta::Record) -> Result<Self, Self::Error> {
+        Self::try_from_ascii(record.sequence().as_ref())
+    }
+}
+
+impl TryFrom<&fasta::Record> for IupacSequence {
+    type Error = ParseBaseError;
+
+    /// Packs the sequence of a record, failing at the first character that
+    /// is not an IUPAC code.
+    fn try_from(record: &fasta::Record) -> Result<Self, Self::Error> {
+        Self::try_from_ascii(record.sequence().as_ref())
+    }
+}
+
+impl<B: BitStore> TryFrom<&fastq::Record> for Sequence<B> {
+    type Error = ParseBaseError;
+
+    /// Packs the sequence of a record, failing at the first character that
+    /// is not a base.
+    fn try_from(record: &fastq::Record) -> Result<Self, Self::Error> {
+        Self::try_from_ascii(record.sequence())
+    }
+}
+
+impl TryFrom<&fastq::Record> for IupacSequence {
+    type Error = ParseBaseError;
+
+    /// Packs the sequence of a record, failing at the first character that
+    /// is not an IUPAC code.
+    fn try_from(record: &fastq::Record) -> Result<Self, Self::Error> {
+        Self::try_from_ascii(record.sequence())
+    }
+}
+
+impl<B: BitStore> TryFrom<&bam::Record> for Sequence<B> {
+    type Error = ParseBaseError;
+
+    /// Packs the sequence of a record, failing at the first code that is not
+    /// a single base.
+    fn try_from(record: &bam::Record) -> Result<Self, Self::Error> {
+        let seq = record.sequence();
+        let mut packed = Self::with_capacity(seq.len());
+        for (i, code) in bam_codes(&seq).enumerate() {
+            packed.push(
+                code.to_base()
+                    .ok_or(ParseBaseError::new(i, bam_ascii(code)))?,
+            );
+        }
+        Ok(packed)
+    }
+}
+
+impl From<&bam::Record> for IupacSequence {
+    fn from(record: &bam::Record) -> Self {
+        bam_codes(&record.sequence()).collect()
+    }
+}
+
+impl<B: BitStore> From<&Sequence<B>> for fasta::record::Sequence {
+    fn from(seq: &Sequence<B>) -> Self {
+        seq.bases().map(|base| base.to_ascii()).collect()
+    }
+}
+
+impl From<&IupacSequence> for fasta::record::Sequence {
+    fn from(seq: &IupacSequence) -> Self {
+        seq.iter().map(Iupac::to_ascii).collect()
+    }
+}
+
+impl<B: BitStore> From<&Sequence<B>> for record_buf::Sequence {
+    fn from(seq: &Sequence<B>) -> Self {
+        seq.bases().map(|base| base.to_ascii()).collect()
+    }
+}
+
+impl From<&IupacSequence> for record_buf::Sequence {
+    /// Converts the codes to characters, writing gaps as `=`.
+    fn from(seq: &IupacSequence) -> Self {
+        seq.iter().map(bam_ascii).collect()
+    }
+}
+
+/// An owned record with its sequence packed as IUPAC codes.
+///
+/// Quality scores are stored as Phred scores, without the offset of FASTQ
+/// characters, as in BAM records.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::interop::noodles::Record;
+/// use helicase::sequence::IupacSequence;
+///
+/// let seq = IupacSequence::try_from_ascii(b"GATNACA").unwrap();
+/// let record = Record::new("r1", seq, Some(vec![30; 7]));
+///
+/// let fastq = record.to_fastq().unwrap();
+/// assert_eq!(fastq.sequence(), b"GATNACA");
+/// assert_eq!(fastq.quality_scores(), b"???????");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    name: Vec<u8>,
+    sequence: IupacSequence,
+    quality_scores: Option<Vec<u8>>,
+}
+
+impl TryFrom<&fasta::Record> for Record {
+    type Error = ParseBaseError;
+
+    /// Packs a FASTA record, keeping the name but not the description.
+    fn try_from(record: &fasta::Record) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: record.name().to_vec(),
+            sequence: IupacSequence::try_from(record)?,
+            quality_scores: None,
+        })
+    }
+}
+
+impl TryFrom<&fastq::Record> for Record {
+    type Error = ParseBaseError;
+
+    /// Packs a FASTQ record, keeping the name but not the description.
+    ///
+    /// Quality characters below `!` are read as a score of 0.
+    fn try_from(record: &fastq::Record) -> Result<Self, Self::Error> {
+        let quality_scores = record
+            .quality_scores()
+            .iter()
+            .map(|&c| c.saturating_sub(PHRED_OFFSET))
+            .collect();
+        Ok(Self {
+            name: record.name().to_vec(),
+            sequence: IupacSequence::try_from(record)?,
+            quality_scores: Some(quality_scores),
+        })
+    }
+}
+
+impl From<&bam::Record> for Record {
+    /// Packs a BAM record, keeping its name, or an empty name if it has
+    /// none.
+    fn from(record: &bam::Record) -> Self {
+        let scores = record.quality_scores().as_bytes();
+        let quality_scores = match scores.first() {
+            None | Some(&MISSING_QUALITY) => None,
+            Some(_) => Some(scores.to_vec()),
+        };
+        Self {
+            name: record.name().map_or_else(Vec::new, |name| name.to_vec()),
+            sequence: IupacSequence::from(record),
+            quality_scores,
+        }
+    }
+}
+
+impl From<&Record> for fasta::Record {
+    fn from(record: &Record) -> Self {
+        let definition = fasta::record::Definition::new(&record.name[..], None);
+        Self::new(definition, fasta::record::Sequence::from(&record.sequence))
+    }
+}
+
+impl From<&Record> for RecordBuf {
+    /// Converts the record to an unmapped alignment record, which can be
+    /// written as SAM or BAM.
+    fn from(record: &Record) -> Self {
+        let mut builder = Self::builder()
+            .set_flags(::noodles::sam::alignment::record::Flags::UNMAPPED)
+            .set_sequence(record_buf::Sequence::from(&record.sequence));
+        if !record.name.is_empty() {
+            builder = builder.set_name(&record.name[..]);
+        }
+        if let Some(scores) = &record.quality_scores {
+            builder = builder.set_quality_scores(scores.clone().into());
+        }
+        builder.build()
+    }
+}
+
+impl Record {
+    /// Creates a record from a name, a sequence and, optionally, a Phred
+    /// quality score for each position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are quality scores but not one for each position.
+    pub fn new(
+        name: impl Into<Vec<u8>>,
+        sequence: IupacSequence,
+        quality_scores: Option<Vec<u8>>,
+    ) -> Self {
+        if let Some(scores) = &quality_scores {
+            assert_eq!(
+                scores.len(),
+                sequence.len(),
+                "there must be one quality score per position"
+            );
+        }
+        Self {
+            name: name.into(),
+            sequence,
+            quality_scores,
+        }
+    }
+
+    /// Returns the name of the record.
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Returns the packed sequence of the record.
+    pub fn sequence(&self) -> &IupacSequence {
+        &self.sequence
+    }
+
+    /// Returns the Phred quality scores of the record, or `None` if it has
+    /// none, as for a FASTA record.
+    pub fn quality_scores(&self) -> Option<&[u8]> {
+        self.quality_scores.as_deref()
+    }
+
+    /// Returns the name, sequence and quality scores of the record.
+    pub fn into_parts(self) -> (Vec<u8>, IupacSequence, Option<Vec<u8>>) {
+        (self.name, self.sequence, self.quality_scores)
+    }
+
+    /// Converts the record to a FASTQ record, or returns `None` if it has no
+    /// quality scores.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a quality score is too high to be written as a character.
+    pub fn to_fastq(&self) -> Option<fastq::Record> {
+        let scores = self.quality_scores.as_ref()?;
+        let quality = scores
+            .iter()
+            .map(|&score| {
+                score
+                    .checked_add(PHRED_OFFSET)
+                    .filter(u8::is_ascii_graphic)
+                    .expect("quality score is too high for FASTQ")
+            })
+            .collect::<Vec<_>>();
+        let definition = fastq::record::Definition::new(&self.name[..], "");
+        let sequence: Vec<u8> = self.sequence.iter().map(Iupac::to_ascii).collect();
+        Some(fastq::Record::new(definition, sequence, quality))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::noodles::sam;
+    use ::noodles::sam::alignment::io::Write as _;
+
+    use super::*;
+
+    /// Writes a record as BAM and reads it back.
+    fn through_bam(record: &RecordBuf) -> bam::Record {
+        let header = sam::Header::default();
+        let mut writer = bam::io::Writer::from(Vec::new());
+        writer.write_header(&header).unwrap();
+        writer.write_alignment_record(&header, record).unwrap();
+
+        let mut reader = bam::io::Reader::from(&writer.get_ref()[..]);
+        reader.read_header().unwrap();
+        let mut bam = bam::Record::default();
+        assert!(reader.read_record(&mut bam).unwrap() > 0);
+        bam
+    }
+
+    #[test]
+    fn fasta() {
+        let definition = fasta::record::Definition::new("seq", Some("desc".into()));
+        let fasta = fasta::Record::new(definition, b"GATTACA".to_vec().into());
+        let seq = Sequence::<u8>::try_from(&fasta).unwrap();
+        assert_eq!(seq.to_string(), "GATTACA");
+        assert_eq!(fasta::record::Sequence::from(&seq), *fasta.sequence());
+
+        let ambiguous = fasta::Record::new(
+            fasta::record::Definition::new("seq", None),
+            b"GATRACA".to_vec().into(),
+        );
+        assert!(Sequence::<u8>::try_from(&ambiguous).is_err());
+        let record = Record::try_from(&ambiguous).unwrap();
+        assert_eq!(record.name(), b"seq");
+        assert_eq!(record.quality_scores(), None);
+        assert_eq!(record.sequence().ambiguous_count(), 1);
+        assert_eq!(fasta::Record::from(&record), ambiguous);
+        assert_eq!(record.to_fastq(), None);
+    }
+
+    #[test]
+    fn fastq() {
+        let definition = fastq::record::Definition::new("r1", "");
+        let fastq = fastq::Record::new(definition, "GATNACA", "+5?IIII");
+        let seq = IupacSequence::try_from(&fastq).unwrap();
+        assert!(Sequence::<u8>::try_from(&fastq).is_err());
+
+        let record = Record::try_from(&fastq).unwrap();
+        assert_eq!(record.sequence(), &seq);
+        assert_eq!(
+            record.quality_scores(),
+            Some(&[10, 20, 30, 40, 40, 40, 40][..])
+        );
+        assert_eq!(record.to_fastq(), Some(fastq));
+    }
+
+    #[test]
+    #[should_panic]
+    fn fastq_score_too_high() {
+        let seq = IupacSequence::try_from_ascii(b"A").unwrap();
+        Record::new("r1", seq, Some(vec![200])).to_fastq();
+    }
+
+    #[test]
+    fn bam() {
+        let seq = IupacSequence::try_from_ascii(b"-ACMGRSVTWYHKDBN").unwrap();
+        let record = Record::new("r1", seq.clone(), Some((0..16).collect()));
+        let bam = through_bam(&RecordBuf::from(&record));
+        assert_eq!(IupacSequence::from(&bam), seq);
+        assert_eq!(Record::from(&bam), record);
+        assert_eq!(
+            Sequence::<u8>::try_from(&bam),
+            Err(ParseBaseError::new(0, b'='))
+        );
+
+        // odd lengths leave half of the last byte unused
+        let seq = Sequence::<u8>::try_from_ascii(b"GATTACA").unwrap();
+        let mut buf = RecordBuf::builder()
+            .set_sequence(record_buf::Sequence::from(&seq))
+            .build();
+        let bam = through_bam(&buf);
+        assert_eq!(Sequence::<u8>::try_from(&bam), Ok(seq.clone()));
+
+        let record = Record::from(&bam);
+        assert_eq!(record.name(), b"");
+        assert_eq!(record.quality_scores(), None);
+        *buf.flags_mut() = sam::alignment::record::Flags::UNMAPPED;
+        assert_eq!(RecordBuf::from(&record), buf);
+    }
+}
@@ -0,0 +1,190 @@
+//! Opening sequence files, with transparent decompression.
+//!
+//! Compressed input is detected from its first bytes rather than from the
+//! file extension. Decompressing gzip (including BGZF) requires the `gzip`
+//! feature, and zstd the `zstd` feature; without them, compressed input is
+//! reported as an error instead of being parsed as garbage.
+//!
+//! # Example
+//!
+//! ```
+//! use std::io::Read;
+//!
+//! use helicase::io::{self, Compression};
+//!
+//! let mut reader = io::decompress(&b">seq\nGATTACA\n"[..]).unwrap();
+//! let mut fasta = String::new();
+//! reader.read_to_string(&mut fasta).unwrap();
+//! assert_eq!(fasta, ">seq\nGATTACA\n");
+//!
+//! assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// A compression format of sequence files.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Compression {
+    /// Uncompressed input.
+    None,
+    /// gzip, including blocked gzip (BGZF).
+    Gzip,
+    /// Zstandard.
+    Zstd,
+}
+
+impl Compression {
+    const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+    const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+    /// Detects the compression of input from its first bytes.
+    ///
+    /// Input that is too short to tell is assumed to be uncompressed.
+    pub fn detect(start: &[u8]) -> Self {
+        if start.starts_with(&Self::GZIP_MAGIC) {
+            Self::Gzip
+        } else if start.starts_with(&Self::ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+
+    /// Returns the name of the feature that enables the format.
+    #[cfg(not(all(feature = "gzip", feature = "zstd")))]
+    const fn feature(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Returns `true` if this build can decompress the format.
+    pub const fn is_supported(self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Gzip => cfg!(feature = "gzip"),
+            Self::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+}
+
+/// Opens the file at `path`, decompressing it if necessary.
+///
+/// See [`decompress`].
+///
+/// # Errors
+///
+/// Returns any error from opening the file, or from [`decompress`].
+pub fn open(path: impl AsRef<Path>) -> io::Result<Box<dyn BufRead + Send>> {
+    decompress(File::open(path)?)
+}
+
+/// Wraps `reader` to decompress it if its content is compressed.
+///
+/// # Errors
+///
+/// Returns an error of kind [`Unsupported`](io::ErrorKind::Unsupported) if
+/// the input is compressed in a format this build cannot decompress, or any
+/// error from reading the first bytes of `reader`.
+pub fn decompress(reader: impl Read + Send + 'static) -> io::Result<Box<dyn BufRead + Send>> {
+    let mut reader = BufReader::new(reader);
+    let compression = Compression::detect(reader.fill_buf()?);
+    match compression {
+        Compression::None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        ))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(zstd::Decoder::with_buffer(
+            reader,
+        )?))),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => Err(unsupported(compression)),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(unsupported(compression)),
+    }
+}
+
+/// Returns the error for input compressed in a format this build cannot
+/// decompress.
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn unsupported(compression: Compression) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{0} input requires the `{0}` feature",
+            compression.feature()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const FASTA: &[u8] = b">seq\nGATTACA\n";
+
+    fn read(reader: io::Result<Box<dyn BufRead + Send>>) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        reader?.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(Compression::detect(FASTA), Compression::None);
+        assert_eq!(Compression::detect(&[0x1f]), Compression::None);
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0]),
+            Compression::Zstd
+        );
+        assert_eq!(read(decompress(FASTA)).unwrap(), FASTA);
+        assert_eq!(read(decompress(&b""[..])).unwrap(), b"");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip() {
+        use flate2::Compression as Level;
+        use flate2::write::GzEncoder;
+
+        // concatenated members, as in BGZF
+        let mut gz = Vec::new();
+        for _ in 0..2 {
+            let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+            encoder.write_all(FASTA).unwrap();
+            gz.extend(encoder.finish().unwrap());
+        }
+        assert_eq!(Compression::detect(&gz), Compression::Gzip);
+        assert_eq!(
+            read(decompress(std::io::Cursor::new(gz))).unwrap(),
+            FASTA.repeat(2)
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd() {
+        let zst = zstd::encode_all(FASTA, 0).unwrap();
+        assert_eq!(Compression::detect(&zst), Compression::Zstd);
+        assert_eq!(read(decompress(std::io::Cursor::new(zst))).unwrap(), FASTA);
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut input = vec![0x28, 0xb5, 0x2f, 0xfd];
+        input.write_all(FASTA).unwrap();
+        if !Compression::Zstd.is_supported() {
+            let err = decompress(std::io::Cursor::new(input)).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+            assert_eq!(err.to_string(), "zstd input requires the `zstd` feature");
+        }
+    }
+}
@@ -1,5 +1,7 @@
 #[cfg(feature = "bitvec")]
 pub mod growable;
+pub mod large;
+pub mod order;
 pub mod small;
 #[cfg(feature = "bitvec")]
 pub mod unbounded;
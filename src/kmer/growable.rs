@@ -1,21 +1,622 @@
-use bitvec::bitvec;
+//! Variable-size k-mers that can grow and shrink.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::growable::Kmer;
+//! use helicase::Base;
+//!
+//! let mut kmer = Kmer::new();
+//! kmer.push(Base::G);
+//! kmer.push(Base::A);
+//! kmer.push(Base::T);
+//! assert_eq!(kmer.len(), 3);
+//! assert_eq!(kmer.to_string(), "GAT");
+//!
+//! assert_eq!(kmer.pop(), Some(Base::T));
+//! assert_eq!(kmer.to_string(), "GA");
+//! ```
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use core::str::FromStr;
+
+use bitvec::field::BitField;
 use bitvec::order::Lsb0;
+use bitvec::slice::{BitSlice, ChunksExact};
 use bitvec::vec::BitVec;
+use bitvec::view::BitView;
+
+use crate::alphabet::{Alphabet, Dna, ParseSymbolError};
+use crate::base::{self, Base, ParseBaseError};
+use crate::sequence::Symbols;
+use crate::utils::{self, lanes};
+use crate::{small, unbounded};
 
 /// A growable k-mer represented as a bit vector.
-#[derive(Debug)]
-struct Kmer {
+///
+/// Each base occupies two bits, with the first base in the lowest bits.
+/// K-mers of symbols from another [`Alphabet`] than [`Dna`] occupy
+/// [`A::BITS`](Alphabet::BITS) bits per symbol, and only have the methods
+/// that do not depend on the alphabet. They are created with `default`,
+/// [`parse_ascii`](Self::parse_ascii) or `collect`.
+pub struct Kmer<A: Alphabet = Dna> {
     inner: BitVec<usize, Lsb0>,
+    alphabet: PhantomData<A>,
 }
 
-impl Kmer {
-    pub fn new(k: usize) -> Self {
+impl<A: Alphabet> core::fmt::Debug for Kmer<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let ascii = self.to_ascii();
+        f.debug_struct("Kmer")
+            .field("k", &self.len())
+            .field("bases", &ascii.escape_ascii().to_string())
+            .finish()
+    }
+}
+
+impl<A: Alphabet> Clone for Kmer<A> {
+    fn clone(&self) -> Self {
+        Self::from_inner(self.inner.clone())
+    }
+}
+
+impl<A: Alphabet> Default for Kmer<A> {
+    fn default() -> Self {
+        Self::from_inner(BitVec::new())
+    }
+}
+
+impl<A: Alphabet> PartialEq for Kmer<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<A: Alphabet> Eq for Kmer<A> {}
+
+impl<A: Alphabet> Hash for Kmer<A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl Display for Kmer {
+    /// Formats the k-mer as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        base::write_bases(f, self.bases())
+    }
+}
+
+impl<A: Alphabet> Extend<A::Symbol> for Kmer<A> {
+    fn extend<I: IntoIterator<Item = A::Symbol>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for symbol in iter {
+            self.push(symbol);
+        }
+    }
+}
+
+impl FromStr for Kmer {
+    type Err = ParseBaseError;
+
+    /// Parses a k-mer from ASCII bases.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_ascii(s.as_bytes())
+    }
+}
+
+impl<A: Alphabet> FromIterator<A::Symbol> for Kmer<A> {
+    fn from_iter<I: IntoIterator<Item = A::Symbol>>(iter: I) -> Self {
+        let mut kmer = Self::default();
+        kmer.extend(iter);
+        kmer
+    }
+}
+
+impl<A: Alphabet> Kmer<A> {
+    /// Fails to compile if the symbols of `A` do not fit in a byte.
+    const VALID_BITS: () = assert!(
+        A::BITS > 0 && A::BITS <= 8,
+        "symbols must be between 1 and 8 bits"
+    );
+
+    fn from_inner(inner: BitVec<usize, Lsb0>) -> Self {
+        let () = Self::VALID_BITS;
         Self {
-            inner: bitvec!(usize, Lsb0; 0; k * 2),
+            inner,
+            alphabet: PhantomData,
+        }
+    }
+
+    /// Returns the number of bases the k-mer can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity() / A::BITS
+    }
+
+    /// Reserves space for at least `additional` more bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::growable::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer = Kmer::with_capacity(10);
+    /// assert!(kmer.capacity() >= 10);
+    /// kmer.extend_from_slice(&[Base::A; 10]);
+    /// kmer.reserve(100);
+    /// assert!(kmer.capacity() >= 110);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional * A::BITS);
+    }
+
+    /// Appends a base to the end of the k-mer.
+    pub fn push(&mut self, base: A::Symbol) {
+        let code = A::encode(base);
+        self.inner
+            .extend_from_bitslice(&code.view_bits::<Lsb0>()[..A::BITS]);
+    }
+
+    /// Appends the bases of `other` to the end of the k-mer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::growable::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer = Kmer::new();
+    /// kmer.extend_from_slice(&[Base::G, Base::A]);
+    /// let other = kmer.clone();
+    /// kmer.append(&other);
+    /// assert_eq!(kmer.to_string(), "GAGA");
+    /// ```
+    pub fn append(&mut self, other: &Self) {
+        self.inner.extend_from_bitslice(&other.inner);
+    }
+
+    /// Appends a slice of bases to the end of the k-mer.
+    pub fn extend_from_slice(&mut self, bases: &[A::Symbol]) {
+        self.extend(bases.iter().copied());
+    }
+
+    /// Removes the last base from the k-mer and returns it, or `None` if the
+    /// k-mer is empty.
+    pub fn pop(&mut self) -> Option<A::Symbol> {
+        let start = self.inner.len().checked_sub(A::BITS)?;
+        let code = self.inner[start..].load_le::<u8>();
+        self.inner.truncate(start);
+        Some(A::decode(code))
+    }
+
+    /// Returns the number of bases in the k-mer.
+    pub fn len(&self) -> usize {
+        self.inner.len() / A::BITS
+    }
+
+    /// Returns `true` if the k-mer contains no bases.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the base at index `i`, or `None` if `i` is out of bounds.
+    pub fn get(&self, i: usize) -> Option<A::Symbol> {
+        let chunk = self.inner.get(i * A::BITS..(i + 1) * A::BITS)?;
+        Some(A::decode(chunk.load_le()))
+    }
+
+    /// Returns an iterator over the symbols in the k-mer.
+    ///
+    /// For DNA, [`bases`](Self::bases) is faster.
+    pub fn symbols(&self) -> Symbols<'_, usize, A> {
+        Symbols::new(&self.inner)
+    }
+
+    /// Creates a k-mer from the ASCII characters of symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the position of the first character that is not
+    /// a symbol of the alphabet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::alphabet::Protein;
+    /// use helicase::growable::Kmer;
+    /// use helicase::translate::AminoAcid;
+    ///
+    /// let mut kmer = Kmer::<Protein>::parse_ascii(b"MKV").unwrap();
+    /// assert_eq!(kmer.pop(), Some(AminoAcid::Val));
+    /// assert_eq!(kmer.to_ascii(), b"MK");
+    /// assert_eq!(Kmer::<Protein>::parse_ascii(b"MKB").unwrap_err().position(), 2);
+    /// ```
+    pub fn parse_ascii(ascii: &[u8]) -> Result<Self, ParseSymbolError> {
+        ascii
+            .iter()
+            .enumerate()
+            .map(|(position, &byte)| {
+                A::from_ascii(byte).ok_or(ParseSymbolError::new(position, byte))
+            })
+            .collect()
+    }
+
+    /// Returns the symbols in the k-mer as ASCII characters.
+    pub fn to_ascii(&self) -> Vec<u8> {
+        self.symbols().map(A::to_ascii).collect()
+    }
+}
+
+impl Kmer {
+    /// Creates a new, empty k-mer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty k-mer with space for at least `bases` bases.
+    pub fn with_capacity(bases: usize) -> Self {
+        Self::from_inner(BitVec::with_capacity(bases * 2))
+    }
+
+    /// Creates a k-mer from ASCII bases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the position of the first character that is not
+    /// a valid base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::growable::Kmer;
+    ///
+    /// let kmer = Kmer::from_ascii(b"GATTACA").unwrap();
+    /// assert_eq!(kmer.len(), 7);
+    ///
+    /// let err = Kmer::from_ascii(b"GATNACA").unwrap_err();
+    /// assert_eq!(err.position(), 3);
+    /// ```
+    pub fn from_ascii(ascii: &[u8]) -> Result<Self, ParseBaseError> {
+        base::parse_ascii(ascii).collect()
+    }
+
+    /// Converts the k-mer into a [`small::Kmer`].
+    ///
+    /// Returns `None` if the length of the k-mer is not `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::growable::Kmer;
+    ///
+    /// let kmer = Kmer::from_ascii(b"GATTACA").unwrap();
+    /// assert_eq!(kmer.to_small::<7>().unwrap().to_string(), "GATTACA");
+    /// assert!(kmer.to_small::<6>().is_none());
+    /// ```
+    pub fn to_small<const K: usize>(&self) -> Option<small::Kmer<K>> {
+        utils::const_eval::assert_less::<0, K>();
+        utils::const_eval::assert_leq::<K, 32>();
+        if self.len() != K {
+            return None;
+        }
+        // the first base is in the lowest bits here, but the highest in
+        // `small::Kmer`
+        Some(small::Kmer::from(self.inner.load_le::<u64>()).reverse())
+    }
+
+    /// Converts the k-mer into a fixed-size [`unbounded::Kmer`].
+    ///
+    /// The storage is reused, and converted a word at a time.
+    pub fn freeze(self) -> unbounded::Kmer {
+        let mut inner = self.inner;
+        inner.force_align();
+        let mut store = inner.into_boxed_bitslice();
+        let bits = store.len();
+        lanes::reverse_slice(store.as_raw_mut_slice(), bits);
+        unbounded::Kmer::from_store(store)
+    }
+
+    /// Returns an iterator over the bases in the k-mer.
+    pub fn bases(&self) -> Bases<'_> {
+        Bases {
+            chunks: self.inner.chunks_exact(2),
         }
     }
 
-    pub fn size(&self) -> usize {
-        self.inner.len()
+    /// Returns a view of the bases in `range`, without copying them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::growable::Kmer;
+    ///
+    /// let a = Kmer::from_ascii(b"GATTACA").unwrap();
+    /// let b = Kmer::from_ascii(b"TACAGG").unwrap();
+    /// // check for a 4-base overlap
+    /// assert_eq!(a.slice(3..), b.slice(..4));
+    /// assert_eq!(a.slice(3..).to_string(), "TACA");
+    /// ```
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> KmerSlice<'_> {
+        let range = utils::range(range, self.len());
+        KmerSlice {
+            inner: &self.inner[range.start * 2..range.end * 2],
+        }
+    }
+
+    /// Returns a view of the whole k-mer.
+    pub fn as_slice(&self) -> KmerSlice<'_> {
+        KmerSlice { inner: &self.inner }
+    }
+}
+
+/// A borrowed view of a contiguous range of bases in a [`Kmer`].
+///
+/// Created with [`Kmer::slice`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KmerSlice<'a> {
+    inner: &'a BitSlice<usize, Lsb0>,
+}
+
+impl core::fmt::Debug for KmerSlice<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KmerSlice")
+            .field("k", &self.len())
+            .field("bases", &self.to_string())
+            .finish()
+    }
+}
+
+impl Display for KmerSlice<'_> {
+    /// Formats the bases as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        base::write_bases(f, self.bases())
+    }
+}
+
+impl PartialEq<Kmer> for KmerSlice<'_> {
+    fn eq(&self, other: &Kmer) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl PartialEq<KmerSlice<'_>> for Kmer {
+    fn eq(&self, other: &KmerSlice<'_>) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<'a> KmerSlice<'a> {
+    /// Returns the number of bases in the view.
+    pub fn len(&self) -> usize {
+        self.inner.len() / 2
+    }
+
+    /// Returns `true` if the view contains no bases.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns an iterator over the bases in the view.
+    pub fn bases(&self) -> Bases<'a> {
+        Bases {
+            chunks: self.inner.chunks_exact(2),
+        }
+    }
+
+    /// Copies the bases in the view into a new [`Kmer`].
+    pub fn to_kmer(&self) -> Kmer {
+        Kmer::from_inner(self.inner.to_bitvec())
+    }
+}
+
+/// An iterator over the bases in a k-mer.
+#[derive(Debug)]
+pub struct Bases<'a> {
+    chunks: ChunksExact<'a, usize, Lsb0>,
+}
+
+impl<'a> Iterator for Bases<'a> {
+    type Item = Base;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: 2 bit bases are always valid.
+        Some(unsafe { Base::from_u8_unchecked(self.chunks.next()?.load::<u8>()) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Bases<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // SAFETY: 2 bit bases are always valid.
+        Some(unsafe { Base::from_u8_unchecked(self.chunks.next_back()?.load::<u8>()) })
+    }
+}
+
+impl<'a> FusedIterator for Bases<'a> {}
+
+impl<'a> ExactSizeIterator for Bases<'a> {}
+
+impl<'a> IntoIterator for &'a Kmer {
+    type Item = Base;
+    type IntoIter = Bases<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bases()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_pop() {
+        let mut kmer = Kmer::new();
+        assert!(kmer.is_empty());
+        assert_eq!(kmer.pop(), None);
+
+        let bases = [Base::G, Base::A, Base::T, Base::C].repeat(20);
+        for base in &bases {
+            kmer.push(*base);
+        }
+        assert_eq!(kmer.len(), 80);
+        assert_eq!(kmer.bases().collect::<Vec<_>>(), bases);
+        assert_eq!(
+            kmer.bases().rev().collect::<Vec<_>>(),
+            bases.iter().rev().copied().collect::<Vec<_>>()
+        );
+
+        for base in bases.iter().rev() {
+            assert_eq!(kmer.pop(), Some(*base));
+        }
+        assert!(kmer.is_empty());
+    }
+
+    #[test]
+    fn append() {
+        let s = "GATTACA".repeat(13);
+        let mut kmer = Kmer::new();
+        let mut expected = String::new();
+        // append at every alignment relative to the storage words
+        for n in 0..s.len() {
+            let mut other = Kmer::new();
+            other.extend(s[..n].bytes().map(|c| Base::from_ascii(c).unwrap()));
+            kmer.append(&other);
+            expected.push_str(&s[..n]);
+        }
+        assert_eq!(kmer.to_string(), expected);
+        assert_eq!(kmer.len(), expected.len());
+    }
+
+    #[test]
+    fn from_iter() {
+        let bases = [Base::C, Base::A, Base::T];
+        let kmer: Kmer = bases.into_iter().collect();
+        assert_eq!(kmer.bases().collect::<Vec<_>>(), bases);
+        assert_eq!(Kmer::from_ascii(b"cat"), Ok(kmer));
+        assert_eq!(Kmer::from_ascii(b""), Ok(Kmer::new()));
+        assert_eq!(Kmer::from_ascii(b"ca-").unwrap_err().byte(), b'-');
+    }
+
+    #[test]
+    fn conversions() {
+        let s = "GATTACACCTG".repeat(9);
+        for k in [1, 31, 32, 33, 64, 65, 99] {
+            let mut kmer = Kmer::from_ascii(&s.as_bytes()[..k]).unwrap();
+            // leave stale bits beyond the end of the storage
+            kmer.push(Base::G);
+            kmer.pop();
+
+            let frozen = kmer.clone().freeze();
+            assert_eq!(frozen.to_string(), s[..k], "k = {k}");
+            assert_eq!(frozen, s[..k].parse().unwrap());
+        }
+
+        let kmer = Kmer::from_ascii(&s.as_bytes()[..32]).unwrap();
+        assert_eq!(kmer.to_small::<32>().unwrap().to_string(), s[..32]);
+        assert!(kmer.to_small::<31>().is_none());
+        assert_eq!(Kmer::new().freeze().size(), 0);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut kmer = Kmer::with_capacity(100);
+        let capacity = kmer.capacity();
+        assert!(capacity >= 100);
+        for _ in 0..capacity {
+            kmer.push(Base::T);
+        }
+        assert_eq!(kmer.capacity(), capacity);
+
+        kmer.reserve(1);
+        assert!(kmer.capacity() > capacity);
+    }
+
+    #[test]
+    fn slice() {
+        use std::collections::HashSet;
+
+        let a = Kmer::from_ascii("ACGGT".repeat(20).as_bytes()).unwrap();
+        let b = Kmer::from_ascii("GGTAC".repeat(20).as_bytes()).unwrap();
+        assert_eq!(a.slice(2..97), b.slice(..95));
+        assert_ne!(a.slice(2..97), b.slice(1..96));
+        assert_eq!(a.slice(..5).to_string(), "ACGGT");
+        assert_eq!(a.slice(95..).bases().len(), 5);
+        assert_eq!(a.slice(..), a);
+        assert_eq!(a.slice(10..20).to_kmer(), a.slice(10..20));
+
+        let slices: HashSet<KmerSlice<'_>> = [a.slice(2..97), b.slice(..95)].into_iter().collect();
+        assert_eq!(slices.len(), 1);
+    }
+
+    #[test]
+    fn parse() {
+        let s = "TTAGGGCA".repeat(10);
+        let kmer: Kmer = s.parse().unwrap();
+        assert_eq!(kmer.to_string(), s);
+        assert_eq!(kmer.to_string().parse::<Kmer>(), Ok(kmer));
+
+        let err = "ACGT ".parse::<Kmer>().unwrap_err();
+        assert_eq!((err.position(), err.byte()), (4, b' '));
+    }
+
+    #[test]
+    fn display_and_eq() {
+        let mut a = Kmer::new();
+        let mut b = Kmer::new();
+        for base in [Base::T, Base::A, Base::G] {
+            a.push(base);
+            b.push(base);
+        }
+        assert_eq!(a.to_string(), "TAG");
+        assert_eq!(format!("{a:#}"), "tag");
+        assert_eq!(format!("{a:?}"), r#"Kmer { k: 3, bases: "TAG" }"#);
+        assert_eq!(a, b);
+
+        b.pop();
+        assert_ne!(a, b);
+        b.push(Base::C);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn protein() {
+        use crate::alphabet::Protein;
+        use crate::translate::AminoAcid;
+
+        let ascii = b"MSTNPKLVW*";
+        let mut kmer = Kmer::<Protein>::parse_ascii(ascii).unwrap();
+        assert_eq!(kmer.len(), 10);
+        assert_eq!(kmer.to_ascii(), ascii);
+        assert_eq!(kmer.get(4), Some(AminoAcid::Pro));
+        assert_eq!(kmer.get(10), None);
+        assert_eq!(
+            format!("{kmer:?}"),
+            r#"Kmer { k: 10, bases: "MSTNPKLVW*" }"#
+        );
+
+        let other: Kmer<Protein> = kmer.symbols().rev().collect();
+        assert_eq!(other.to_ascii(), b"*WVLKPNTSM");
+        kmer.append(&other);
+        assert_eq!(kmer.len(), 20);
+        while kmer.len() > 10 {
+            kmer.pop();
+        }
+        assert_eq!(kmer, Kmer::parse_ascii(ascii).unwrap());
     }
 }
@@ -0,0 +1,544 @@
+//! Fixed-size k-mers packed into several 64-bit words.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::large::LargeKmer;
+//! use helicase::Base;
+//!
+//! // a 63-mer, which fits in two words
+//! let mut kmer = LargeKmer::<63, 2>::new();
+//! for _ in 0..21 {
+//!     kmer.push(Base::G).push(Base::A).push(Base::T);
+//! }
+//! assert_eq!(kmer.to_string(), "GAT".repeat(21));
+//! assert_eq!(kmer.reverse_complement().to_string(), "ATC".repeat(21));
+//! ```
+//!
+//! # Limitations
+//!
+//! The number of words is a separate parameter from the number of bases, as
+//! it cannot be computed from `K` on stable Rust. It must be at least
+//! `K.div_ceil(32)`.
+
+use core::fmt::Display;
+use core::iter::FusedIterator;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::base::{self, Base};
+use crate::hash::mix;
+use crate::small;
+use crate::utils;
+
+/// A fixed-size k-mer of `K` bases, stored in `WORDS` 64-bit words on the
+/// stack.
+///
+/// This has the same API as [`small::Kmer`], for k-mers longer than 32
+/// bases. The bases are packed as one `64 * WORDS` bit integer, with the
+/// first word most significant and the first base in the most significant
+/// of the low `2K` bits. Higher bits are always cleared, so k-mers can be
+/// compared and hashed directly, and are ordered like their bases.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct LargeKmer<const K: usize, const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const K: usize, const WORDS: usize> core::fmt::Debug for LargeKmer<K, WORDS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LargeKmer")
+            .field("k", &K)
+            // quoted like a string, without allocating one
+            .field("bases", &format_args!("\"{self}\""))
+            .field("raw", &format_args!("{:#x?}", self.words))
+            .finish()
+    }
+}
+
+impl<const K: usize, const WORDS: usize> Display for LargeKmer<K, WORDS> {
+    /// Formats the k-mer as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        base::write_bases(f, self.bases())
+    }
+}
+
+impl<const K: usize, const WORDS: usize> Default for LargeKmer<K, WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const K: usize, const WORDS: usize> From<[u64; WORDS]> for LargeKmer<K, WORDS> {
+    /// Creates a k-mer from its packed words, ignoring the bits above the
+    /// `K` bases.
+    fn from(words: [u64; WORDS]) -> Self {
+        let mut kmer = Self::new();
+        kmer.words = words;
+        kmer.mask();
+        kmer
+    }
+}
+
+impl<const K: usize, const WORDS: usize> From<small::Kmer<K>> for LargeKmer<K, WORDS> {
+    fn from(kmer: small::Kmer<K>) -> Self {
+        let mut large = Self::new();
+        large.words[WORDS - 1] = kmer.as_masked();
+        large
+    }
+}
+
+impl<const K: usize, const WORDS: usize> LargeKmer<K, WORDS> {
+    /// The number of bits above the `K` bases.
+    const UNUSED: usize = WORDS * 64 - K * 2;
+
+    /// Creates a new k-mer.
+    ///
+    /// All bases are initialized to `Base::C`.
+    pub const fn new() -> Self {
+        utils::const_eval::assert_less::<0, K>();
+        utils::const_eval::assert_fits_words::<K, WORDS>();
+        Self { words: [0; WORDS] }
+    }
+
+    /// Creates a k-mer from an array of bases.
+    pub fn from_bases(bases: [Base; K]) -> Self {
+        let mut kmer = Self::new();
+        for base in bases {
+            kmer.push(base);
+        }
+        kmer
+    }
+
+    /// Returns the bits of word `i` that hold bases.
+    const fn word_mask(i: usize) -> u64 {
+        if (i + 1) * 64 <= Self::UNUSED {
+            0
+        } else if i * 64 >= Self::UNUSED {
+            u64::MAX
+        } else {
+            u64::MAX >> (Self::UNUSED - i * 64)
+        }
+    }
+
+    /// Clears the bits above the `K` bases.
+    const fn mask(&mut self) {
+        let mut i = 0;
+        while i < WORDS {
+            self.words[i] &= Self::word_mask(i);
+            i += 1;
+        }
+    }
+
+    /// Returns the word and the shift within it of base `i`.
+    const fn locate(i: usize) -> (usize, usize) {
+        let offset = (K - i - 1) * 2;
+        (WORDS - 1 - offset / 64, offset % 64)
+    }
+
+    /// Returns the base at position `i`, which must be less than `K`.
+    const fn base_at(&self, i: usize) -> Base {
+        let (word, shift) = Self::locate(i);
+        // SAFETY: masked to 2 bits, and every 2 bit code is a base.
+        unsafe { Base::from_u8_unchecked((self.words[word] >> shift) as u8 & 0b11) }
+    }
+
+    /// Pushes a base onto the k-mer.
+    ///
+    /// Bases are pushed to the end of the k-mer, and the bases are shifted
+    /// to the left, across words, removing the first base.
+    pub const fn push(&mut self, base: Base) -> &mut Self {
+        let mut i = 0;
+        while i + 1 < WORDS {
+            self.words[i] = self.words[i] << 2 | self.words[i + 1] >> 62;
+            i += 1;
+        }
+        self.words[WORDS - 1] = self.words[WORDS - 1] << 2 | base as u64;
+        self.mask();
+        self
+    }
+
+    /// Pushes a base onto the front of the k-mer, removing the last base.
+    pub const fn push_front(&mut self, base: Base) -> &mut Self {
+        let mut i = WORDS - 1;
+        while i > 0 {
+            self.words[i] = self.words[i] >> 2 | self.words[i - 1] << 62;
+            i -= 1;
+        }
+        self.words[0] >>= 2;
+        // the first lane was shifted in from the cleared bits above it
+        let (word, shift) = Self::locate(0);
+        self.words[word] |= (base as u64) << shift;
+        self
+    }
+
+    /// Returns an iterator over the bases in the k-mer.
+    pub const fn bases(&self) -> Bases<'_, K, WORDS> {
+        Bases {
+            inner: self,
+            pos: 0,
+            end: K,
+        }
+    }
+
+    /// Returns a copy of the k-mer with the base at position `i` replaced by
+    /// `base`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than `K`.
+    pub const fn with_base_at(mut self, i: usize, base: Base) -> Self {
+        assert!(i < K, "base index out of bounds");
+        let (word, shift) = Self::locate(i);
+        self.words[word] = self.words[word] & !(0b11 << shift) | (base as u64) << shift;
+        self
+    }
+
+    /// Counts the occurrences of `base` in the k-mer.
+    pub const fn count(&self, base: Base) -> u32 {
+        let mut count = 0;
+        let mut i = 0;
+        while i < WORDS {
+            // XOR with the base in every lane zeroes exactly the matching lanes.
+            let diff = self.words[i] ^ (small::LOW_BITS * base as u64);
+            count += (!(diff | diff >> 1) & small::LOW_BITS & Self::word_mask(i)).count_ones();
+            i += 1;
+        }
+        count
+    }
+
+    /// Counts the `G` and `C` bases in the k-mer.
+    pub const fn gc_count(&self) -> u32 {
+        let mut count = 0;
+        let mut i = 0;
+        while i < WORDS {
+            // `C` (`0b00`) and `G` (`0b11`) are the only bases with equal bits.
+            let word = self.words[i];
+            count += (!(word ^ word >> 1) & small::LOW_BITS & Self::word_mask(i)).count_ones();
+            i += 1;
+        }
+        count
+    }
+
+    /// Returns the length of the longest run of identical bases in the
+    /// k-mer.
+    pub fn longest_homopolymer(&self) -> u32 {
+        let mut bases = self.bases();
+        let mut prev = bases.next().expect("k-mers are not empty");
+        let (mut run, mut longest) = (1, 1);
+        for base in bases {
+            run = if base == prev { run + 1 } else { 1 };
+            longest = longest.max(run);
+            prev = base;
+        }
+        longest
+    }
+
+    /// Returns the Shannon entropy of the bases in the k-mer, in bits.
+    ///
+    /// The entropy is 0 for a homopolymer and 2 for a k-mer with equal
+    /// numbers of each base.
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        let counts = [Base::C, Base::A, Base::T, Base::G].map(|base| self.count(base) as u64);
+        utils::complexity::entropy(counts)
+    }
+
+    /// Returns the linguistic complexity of the k-mer.
+    ///
+    /// See [`small::Kmer::linguistic_complexity`].
+    pub fn linguistic_complexity(&self) -> f64 {
+        let mut codes = [0; K];
+        for (code, base) in codes.iter_mut().zip(self.bases()) {
+            *code = base as u8;
+        }
+        utils::complexity::linguistic_complexity(&codes, &mut [0; K])
+    }
+
+    /// Reverses the order of the bases in the k-mer, without complementing
+    /// them.
+    pub const fn reverse(self) -> Self {
+        // Reverse every lane of the words, which leaves the bases at the top,
+        // then shift them down past the unused bits.
+        let mut reversed = [0; WORDS];
+        let mut i = 0;
+        while i < WORDS {
+            reversed[WORDS - 1 - i] = utils::lanes::reverse(self.words[i]);
+            i += 1;
+        }
+
+        let (skip, shift) = (Self::UNUSED / 64, Self::UNUSED % 64);
+        let mut words = [0; WORDS];
+        let mut i = skip;
+        while i < WORDS {
+            words[i] = reversed[i - skip] >> shift;
+            if shift > 0 && i > skip {
+                words[i] |= reversed[i - skip - 1] << (64 - shift);
+            }
+            i += 1;
+        }
+        Self { words }
+    }
+
+    /// Returns the reverse complement of the k-mer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::large::LargeKmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer = LargeKmer::<40, 2>::new();
+    /// for _ in 0..10 {
+    ///     kmer.push(Base::G).push(Base::A).push(Base::T).push(Base::T);
+    /// }
+    /// assert_eq!(kmer.reverse_complement().to_string(), "AATC".repeat(10));
+    /// ```
+    pub const fn reverse_complement(self) -> Self {
+        let mut kmer = self.reverse();
+        let mut i = 0;
+        while i < WORDS {
+            // Complementing a base flips both of its bits.
+            kmer.words[i] ^= Self::word_mask(i);
+            i += 1;
+        }
+        kmer
+    }
+
+    /// Returns `true` if the k-mer is not greater than its reverse
+    /// complement.
+    pub const fn is_canonical(&self) -> bool {
+        let reverse = self.reverse_complement();
+        let mut i = 0;
+        while i < WORDS {
+            if self.words[i] != reverse.words[i] {
+                return self.words[i] < reverse.words[i];
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Returns the smaller of the k-mer and its reverse complement.
+    ///
+    /// Bases are ordered by their 2-bit encoding, `C < A < T < G`.
+    pub const fn canonical(self) -> Self {
+        if self.is_canonical() {
+            self
+        } else {
+            self.reverse_complement()
+        }
+    }
+
+    /// Returns a 64-bit hash of the k-mer.
+    ///
+    /// K-mers of up to 32 bases hash like [`small::Kmer::mix_hash`], so the
+    /// hash can be inverted with [`small::Kmer::from_mix_hash`]. Longer
+    /// k-mers mix each word in turn with [`mix::hash`], and their hashes
+    /// cannot be inverted.
+    pub const fn mix_hash(&self) -> u64 {
+        if K <= 32 {
+            return mix::hash_bits(self.words[WORDS - 1], K as u32 * 2);
+        }
+        let mut hash = 0;
+        let mut i = 0;
+        while i < WORDS {
+            hash = mix::hash(hash ^ self.words[i]);
+            i += 1;
+        }
+        hash
+    }
+
+    /// Returns the k-mer as a lowercase string.
+    ///
+    /// Equivalent to `format!("{kmer:#}")`.
+    #[cfg(feature = "alloc")]
+    pub fn to_lowercase_string(&self) -> String {
+        alloc::format!("{self:#}")
+    }
+
+    /// Returns the k-mer as an RNA string, using `U` in place of `T`.
+    #[cfg(feature = "alloc")]
+    pub fn to_rna_string(&self) -> String {
+        self.bases().map(|b| b.to_rna_ascii() as char).collect()
+    }
+
+    /// Returns the packed words of the k-mer, with the bits above the `K`
+    /// bases cleared.
+    pub const fn as_words(&self) -> [u64; WORDS] {
+        self.words
+    }
+
+    /// Converts the k-mer into a [`small::Kmer`], if it has at most 32
+    /// bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is greater than 32.
+    pub fn to_small(self) -> small::Kmer<K> {
+        utils::const_eval::assert_leq::<K, 32>();
+        small::Kmer::from(self.words[WORDS - 1])
+    }
+}
+
+/// An iterator over the bases in a k-mer.
+#[derive(Debug, Clone)]
+pub struct Bases<'a, const K: usize, const WORDS: usize> {
+    inner: &'a LargeKmer<K, WORDS>,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a, const K: usize, const WORDS: usize> Iterator for Bases<'a, K, WORDS> {
+    type Item = Base;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        self.pos += 1;
+        Some(self.inner.base_at(self.pos - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.pos;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.pos = self.pos.saturating_add(n).min(self.end);
+        self.next()
+    }
+}
+
+impl<'a, const K: usize, const WORDS: usize> DoubleEndedIterator for Bases<'a, K, WORDS> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(self.inner.base_at(self.end))
+    }
+}
+
+impl<'a, const K: usize, const WORDS: usize> FusedIterator for Bases<'a, K, WORDS> {}
+
+impl<'a, const K: usize, const WORDS: usize> ExactSizeIterator for Bases<'a, K, WORDS> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test::random_bases;
+
+    fn reverse_complement(s: &str) -> String {
+        s.bytes()
+            .rev()
+            .map(|b| Base::from_ascii(b).unwrap().complement().to_ascii() as char)
+            .collect()
+    }
+
+    #[test]
+    fn matches_small() {
+        let mut small = small::Kmer::<29>::new();
+        let mut large = LargeKmer::<29, 1>::new();
+        let mut wide = LargeKmer::<29, 3>::new();
+        for base in random_bases(100, 0) {
+            small.push(base);
+            large.push(base);
+            wide.push(base);
+            assert_eq!(large, small.into());
+            assert_eq!(wide.as_words()[..2], [0, 0]);
+            assert_eq!(
+                large.reverse_complement(),
+                small.reverse_complement().into()
+            );
+            assert_eq!(wide.reverse_complement(), small.reverse_complement().into());
+            assert_eq!(large.canonical(), small.canonical().into());
+            assert_eq!(wide.to_string(), small.to_string());
+            assert_eq!(wide.count(Base::C), small.count(Base::C));
+            assert_eq!(wide.gc_count(), small.gc_count());
+            assert_eq!(wide.longest_homopolymer(), small.longest_homopolymer());
+            assert_eq!(large.to_small().as_masked(), small.as_masked());
+        }
+    }
+
+    #[test]
+    fn multiword() {
+        fn check<const K: usize, const WORDS: usize>() {
+            let bases = random_bases(K + 50, 0);
+            let mut kmer = LargeKmer::<K, WORDS>::new();
+            for (i, &base) in bases.iter().enumerate() {
+                kmer.push(base);
+                let expected: String = bases[(i + 1).saturating_sub(K)..=i]
+                    .iter()
+                    .map(|b| b.to_ascii() as char)
+                    .collect();
+                if i + 1 < K {
+                    assert!(kmer.to_string().ends_with(&expected));
+                    continue;
+                }
+                assert_eq!(kmer.to_string(), expected);
+                assert!(
+                    kmer.bases()
+                        .rev()
+                        .eq(expected.bytes().rev().map(|b| Base::from_ascii(b).unwrap()))
+                );
+                let revcomp = kmer.reverse_complement();
+                assert_eq!(revcomp.to_string(), reverse_complement(&expected));
+                assert_eq!(revcomp.reverse_complement(), kmer);
+                assert_eq!(kmer.canonical(), kmer.min(revcomp));
+                assert_eq!(kmer.is_canonical(), kmer <= revcomp);
+                assert_eq!(
+                    kmer.count(Base::A) as usize,
+                    expected.bytes().filter(|&b| b == b'A').count()
+                );
+            }
+
+            let mut front = kmer;
+            front.push_front(Base::G);
+            assert_eq!(front.bases().next(), Some(Base::G));
+            assert!(front.bases().skip(1).eq(kmer.bases().take(K - 1)));
+            assert_eq!(
+                kmer.with_base_at(K - 1, Base::T).bases().next_back(),
+                Some(Base::T)
+            );
+            assert_eq!(
+                LargeKmer::<K, WORDS>::from([u64::MAX; WORDS]).count(Base::G),
+                K as u32
+            );
+        }
+
+        check::<31, 1>();
+        check::<32, 1>();
+        check::<33, 2>();
+        check::<63, 2>();
+        check::<64, 2>();
+        check::<65, 3>();
+        check::<100, 4>();
+    }
+
+    #[test]
+    fn hash() {
+        let mut a = LargeKmer::<63, 2>::new();
+        let mut b = a;
+        a.push(Base::A);
+        b.push(Base::T);
+        assert_ne!(a.mix_hash(), b.mix_hash());
+        assert_eq!(
+            a.mix_hash(),
+            LargeKmer::<63, 2>::from(a.as_words()).mix_hash()
+        );
+
+        let small = small::Kmer::<21>::from(0x1234_5678_9abc);
+        let hash = LargeKmer::<21, 1>::from(small).mix_hash();
+        assert_eq!(hash, small.mix_hash());
+        assert_eq!(
+            small::Kmer::<21>::from_mix_hash(hash).as_masked(),
+            small.as_masked()
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Orderings of k-mers.
+//!
+//! A [`KmerOrd`] is a strategy for comparing k-mers, used to sort them with
+//! [`sort`](KmerOrd::sort) or to give them an [`Ord`] implementation with
+//! [`Ordered`]. The k-mers themselves are unordered, as there is no single
+//! natural order: sorting by their 2-bit encoding puts bases in the order
+//! `C < A < T < G`, which differs from the alphabetical order that tools
+//! building a BWT expect.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::order::{Colexicographic, KmerOrd, Lexicographic};
+//! use helicase::small::Kmer;
+//! use helicase::Base::*;
+//!
+//! let mut kmers = [[T, A], [A, C], [C, A]].map(Kmer::from_bases);
+//! Lexicographic::sort(&mut kmers);
+//! assert_eq!(kmers.map(|k| k.to_string()), ["AC", "CA", "TA"]);
+//! Colexicographic::sort(&mut kmers);
+//! assert_eq!(kmers.map(|k| k.to_string()), ["CA", "TA", "AC"]);
+//! ```
+
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use crate::hash::nthash;
+use crate::small::Kmer;
+
+/// A strategy for comparing k-mers of `K` bases.
+pub trait KmerOrd<const K: usize> {
+    /// Compares two k-mers.
+    fn cmp(a: &Kmer<K>, b: &Kmer<K>) -> Ordering;
+
+    /// Sorts k-mers in this order.
+    fn sort(kmers: &mut [Kmer<K>]) {
+        kmers.sort_unstable_by(Self::cmp);
+    }
+}
+
+/// Returns the lanes of a k-mer of `K` bases.
+const fn lanes<const K: usize>() -> u64 {
+    u64::MAX >> (64 - K * 2)
+}
+
+/// Orders k-mers by their 2-bit encoding, so bases are ordered
+/// `C < A < T < G` from the first base.
+///
+/// This is the cheapest order, and the one used by sorted k-mer
+/// collections in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Encoded;
+
+impl<const K: usize> KmerOrd<K> for Encoded {
+    fn cmp(a: &Kmer<K>, b: &Kmer<K>) -> Ordering {
+        a.as_masked().cmp(&b.as_masked())
+    }
+}
+
+/// Orders k-mers alphabetically (`A < C < G < T`) from the first base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Lexicographic;
+
+impl Lexicographic {
+    /// Maps each lane to the alphabetical rank of its base, which swaps
+    /// `C` with `A` and `T` with `G`.
+    const fn key<const K: usize>(kmer: &Kmer<K>) -> u64 {
+        kmer.as_masked() ^ (lanes::<K>() & 0x5555_5555_5555_5555)
+    }
+}
+
+impl<const K: usize> KmerOrd<K> for Lexicographic {
+    fn cmp(a: &Kmer<K>, b: &Kmer<K>) -> Ordering {
+        Self::key(a).cmp(&Self::key(b))
+    }
+}
+
+/// Orders k-mers alphabetically (`A < C < G < T`) from the last base, as
+/// used to build the BWT of a de Bruijn graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Colexicographic;
+
+impl<const K: usize> KmerOrd<K> for Colexicographic {
+    fn cmp(a: &Kmer<K>, b: &Kmer<K>) -> Ordering {
+        Lexicographic::cmp(&a.reverse(), &b.reverse())
+    }
+}
+
+/// Orders k-mers by the ntHash of their minimizer of `M` bases, and then by
+/// encoding, so that k-mers sharing a minimizer are adjacent.
+///
+/// This groups k-mers into the partitions used by minimizer-based
+/// k-mer counters and graph builders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MinimizerOrder<const M: usize>;
+
+impl<const M: usize> MinimizerOrder<M> {
+    /// Returns the smallest ntHash of the `M`-mers of a k-mer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `M` is 0 or greater than `K`.
+    pub fn minimizer<const K: usize>(kmer: &Kmer<K>) -> u64 {
+        assert!(0 < M && M <= K, "minimizer length must be between 1 and K");
+        let mut bases = kmer.bases();
+        let mut out = kmer.bases();
+        let mut hash = nthash::hash(bases.by_ref().take(M));
+        let mut min = hash;
+        for base in bases {
+            hash = nthash::roll(hash, M, out.next().unwrap(), base);
+            min = min.min(hash);
+        }
+        min
+    }
+}
+
+impl<const K: usize, const M: usize> KmerOrd<K> for MinimizerOrder<M> {
+    fn cmp(a: &Kmer<K>, b: &Kmer<K>) -> Ordering {
+        Self::minimizer(a)
+            .cmp(&Self::minimizer(b))
+            .then_with(|| Encoded::cmp(a, b))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn sort(kmers: &mut [Kmer<K>]) {
+        // compute each minimizer once rather than at every comparison
+        kmers.sort_by_cached_key(|kmer| (Self::minimizer(kmer), kmer.as_masked()));
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn sort(kmers: &mut [Kmer<K>]) {
+        // caching the keys needs somewhere to put them
+        kmers.sort_unstable_by_key(|kmer| (Self::minimizer(kmer), kmer.as_masked()));
+    }
+}
+
+/// A k-mer that implements [`Ord`] using the order `O`, for use in sorted
+/// collections such as a [`BTreeSet`](std::collections::BTreeSet) or a
+/// [`BinaryHeap`](std::collections::BinaryHeap).
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+///
+/// use helicase::order::{Lexicographic, Ordered};
+/// use helicase::small::Kmer;
+/// use helicase::Base::*;
+///
+/// let set: BTreeSet<Ordered<2, Lexicographic>> =
+///     [[T, A], [A, C], [T, A]].map(|b| Ordered::new(Kmer::from_bases(b))).into();
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(set.first().unwrap().kmer().to_string(), "AC");
+/// ```
+pub struct Ordered<const K: usize, O> {
+    kmer: Kmer<K>,
+    order: PhantomData<O>,
+}
+
+impl<const K: usize, O> Ordered<K, O> {
+    /// Wraps a k-mer.
+    pub const fn new(kmer: Kmer<K>) -> Self {
+        Self {
+            kmer,
+            order: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped k-mer.
+    pub const fn kmer(&self) -> Kmer<K> {
+        self.kmer
+    }
+}
+
+impl<const K: usize, O> core::fmt::Debug for Ordered<K, O> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Ordered").field(&self.kmer).finish()
+    }
+}
+
+impl<const K: usize, O> Clone for Ordered<K, O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const K: usize, O> Copy for Ordered<K, O> {}
+
+impl<const K: usize, O: KmerOrd<K>> PartialEq for Ordered<K, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<const K: usize, O: KmerOrd<K>> Eq for Ordered<K, O> {}
+
+impl<const K: usize, O: KmerOrd<K>> PartialOrd for Ordered<K, O> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const K: usize, O: KmerOrd<K>> Ord for Ordered<K, O> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        O::cmp(&self.kmer, &other.kmer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Base;
+
+    fn kmers(strings: &[&str]) -> Vec<Kmer<3>> {
+        strings.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    fn sorted<O: KmerOrd<3>>(strings: &[&str]) -> Vec<String> {
+        let mut kmers = kmers(strings);
+        O::sort(&mut kmers);
+        kmers.iter().map(|k| k.to_string()).collect()
+    }
+
+    const ALL: [&str; 6] = ["TGA", "ACG", "GAT", "CAT", "ACT", "TTT"];
+
+    #[test]
+    fn lexicographic() {
+        let mut expected = ALL.to_vec();
+        expected.sort();
+        assert_eq!(sorted::<Lexicographic>(&ALL), expected);
+        assert_eq!(
+            sorted::<Encoded>(&ALL),
+            ["CAT", "ACT", "ACG", "TTT", "TGA", "GAT"]
+        );
+    }
+
+    #[test]
+    fn colexicographic() {
+        let mut expected = ALL.to_vec();
+        expected.sort_by_key(|s| s.bytes().rev().collect::<Vec<_>>());
+        assert_eq!(sorted::<Colexicographic>(&ALL), expected);
+    }
+
+    #[test]
+    fn minimizer_order() {
+        let sorted = sorted::<MinimizerOrder<2>>(&ALL);
+        let minimizers: Vec<u64> = kmers(&sorted.iter().map(String::as_str).collect::<Vec<_>>())
+            .iter()
+            .map(MinimizerOrder::<2>::minimizer)
+            .collect();
+        assert!(minimizers.is_sorted());
+
+        // the cached sort agrees with the comparison
+        let mut by_cmp = kmers(&ALL);
+        by_cmp.sort_by(<MinimizerOrder<2> as KmerOrd<3>>::cmp);
+        let by_cmp: Vec<String> = by_cmp.iter().map(|k| k.to_string()).collect();
+        assert_eq!(by_cmp, sorted);
+
+        let gat = kmers(&["GAT"])[0];
+        let expected = [[Base::G, Base::A], [Base::A, Base::T]]
+            .map(nthash::hash)
+            .into_iter()
+            .min()
+            .unwrap();
+        assert_eq!(MinimizerOrder::<2>::minimizer(&gat), expected);
+    }
+
+    #[test]
+    fn ordered() {
+        let [a, b] = [kmers(&["CAT"])[0], kmers(&["ACG"])[0]];
+        assert!(Ordered::<3, Lexicographic>::new(a) > Ordered::new(b));
+        assert!(Ordered::<3, Encoded>::new(a) < Ordered::new(b));
+    }
+}
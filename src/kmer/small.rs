@@ -16,35 +16,57 @@
 //! This implementation is not suitable for k-mers with more than 32 bases, as
 //! it uses a `u64` to store the k-mer.
 
-use std::fmt::Display;
-use std::iter::FusedIterator;
+use core::fmt::Display;
+use core::iter::FusedIterator;
+use core::str::FromStr;
 
-use crate::base::Base;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::base::{self, Base, ParseBaseError};
+use crate::hash::mix;
 use crate::utils;
 
+mod dynamic;
+
+pub use dynamic::DynKmer;
+
 /// A fixed-size k-mer represented as a 64-bit integer.
 ///
 /// Stores between 1 and 32 bases. For larger k-mers, see
 #[cfg_attr(feature = "unstable_nightly", doc = "[`unbounded`] and")]
-/// [`growable`].
+/// [`growable`], or [`LargeKmer`] to keep them on the stack.
 ///
+/// [`LargeKmer`]: crate::large::LargeKmer
 #[cfg_attr(
     feature = "unstable_nightly",
     doc = "[`unbounded`]: crate::kmer::unbounded"
 )]
 /// [`growable`]: crate::kmer::growable
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone, Copy)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct Kmer<const K: usize> {
     inner: u64,
 }
 
+impl<const K: usize> core::fmt::Debug for Kmer<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Kmer")
+            .field("k", &K)
+            // quoted like a string, without allocating one
+            .field("bases", &format_args!("\"{self}\""))
+            .field("raw", &format_args!("{:#x}", self.as_masked()))
+            .finish()
+    }
+}
+
 impl<const K: usize> Display for Kmer<K> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.bases().map(|b| b.to_string()).collect::<String>()
-        )
+    /// Formats the k-mer as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        base::write_bases(f, self.bases())
     }
 }
 
@@ -60,7 +82,67 @@ impl<const K: usize> From<u64> for Kmer<K> {
     }
 }
 
+impl<const K: usize> FromStr for Kmer<K> {
+    type Err = ParseKmerError;
+
+    /// Parses a k-mer from exactly `K` ASCII bases.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_ascii(s.as_bytes())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const K: usize> From<&ArchivedKmer<K>> for Kmer<K> {
+    /// Reads a k-mer in place from an archive.
+    fn from(archived: &ArchivedKmer<K>) -> Self {
+        Self {
+            inner: archived.inner.to_native(),
+        }
+    }
+}
+
+/// An error returned by [`Kmer::from_ascii`] and [`Kmer::from_str`] for
+/// invalid input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseKmerError {
+    /// The input was not `K` bases long.
+    Length {
+        /// The number of bases in the k-mer.
+        expected: usize,
+        /// The number of characters given.
+        actual: usize,
+    },
+    /// The input contained a character that is not a valid base.
+    Base(ParseBaseError),
+}
+
+impl Display for ParseKmerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseKmerError::Length { expected, actual } => {
+                write!(f, "expected {expected} bases, got {actual}")
+            }
+            ParseKmerError::Base(err) => err.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for ParseKmerError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ParseKmerError::Length { .. } => None,
+            ParseKmerError::Base(err) => Some(err),
+        }
+    }
+}
+
+/// The low bit of every 2-bit lane.
+pub(super) const LOW_BITS: u64 = 0x5555_5555_5555_5555;
+
 impl<const K: usize> Kmer<K> {
+    /// The low bit of each of the `K` lanes in use.
+    const LANES: u64 = LOW_BITS >> (64 - K * 2);
+
     /// Creates a new k-mer.
     ///
     /// All bases are initialized to `Base::C`.
@@ -75,6 +157,43 @@ impl<const K: usize> Kmer<K> {
         Self { inner }
     }
 
+    /// Creates a k-mer from exactly `K` ASCII bases, in either case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ascii` is not `K` bytes long, or contains a
+    /// character that is not a valid base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::{Kmer, ParseKmerError};
+    ///
+    /// let kmer = Kmer::<5>::from_ascii(b"gatta").unwrap();
+    /// assert_eq!(kmer.to_string(), "GATTA");
+    /// assert_eq!("GATTA".parse::<Kmer<5>>().unwrap().as_masked(), kmer.as_masked());
+    ///
+    /// assert_eq!(
+    ///     Kmer::<5>::from_ascii(b"GATTACA").unwrap_err(),
+    ///     ParseKmerError::Length { expected: 5, actual: 7 }
+    /// );
+    /// let err = Kmer::<5>::from_ascii(b"GANTA").unwrap_err();
+    /// assert!(matches!(err, ParseKmerError::Base(err) if err.position() == 2));
+    /// ```
+    pub fn from_ascii(ascii: &[u8]) -> Result<Self, ParseKmerError> {
+        if ascii.len() != K {
+            return Err(ParseKmerError::Length {
+                expected: K,
+                actual: ascii.len(),
+            });
+        }
+        let mut kmer = Self::new();
+        for base in base::parse_ascii(ascii) {
+            kmer.push(base.map_err(ParseKmerError::Base)?);
+        }
+        Ok(kmer)
+    }
+
     /// Pushes a base onto the k-mer.
     ///
     /// Bases are pushed to the end of the k-mer, and the bases are shifted to the left, removing the first base.
@@ -83,6 +202,23 @@ impl<const K: usize> Kmer<K> {
         self
     }
 
+    /// Pushes a base onto the front of the k-mer, removing the last base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer = Kmer::<3>::from_bases([Base::G, Base::A, Base::T]);
+    /// kmer.push_front(Base::C);
+    /// assert_eq!(kmer.to_string(), "CGA");
+    /// ```
+    pub const fn push_front(&mut self, base: Base) -> &mut Self {
+        self.inner = (self.as_masked() >> 2) | (base as u64) << ((K - 1) * 2);
+        self
+    }
+
     /// Returns an iterator over the bases in the k-mer.
     pub const fn bases(&self) -> Bases<'_, K> {
         Bases {
@@ -91,6 +227,227 @@ impl<const K: usize> Kmer<K> {
         }
     }
 
+    /// Returns a copy of the k-mer with the base at position `i` replaced by
+    /// `base`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<4>::from_bases([Base::A, Base::C, Base::G, Base::T]);
+    /// assert_eq!(kmer.with_base_at(1, Base::T).to_string(), "ATGT");
+    /// ```
+    pub const fn with_base_at(self, i: usize, base: Base) -> Self {
+        assert!(i < K, "base index out of bounds");
+        let shift = (K - i - 1) * 2;
+        Self {
+            inner: self.inner & !(0b11 << shift) | (base as u64) << shift,
+        }
+    }
+
+    /// Counts the occurrences of `base` in the k-mer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<5>::from_bases([Base::A, Base::C, Base::A, Base::G, Base::A]);
+    /// assert_eq!(kmer.count(Base::A), 3);
+    /// assert_eq!(kmer.count(Base::T), 0);
+    /// ```
+    pub const fn count(&self, base: Base) -> u32 {
+        // XOR with the base in every lane zeroes exactly the matching lanes.
+        let diff = self.as_masked() ^ (LOW_BITS * base as u64);
+        (!(diff | diff >> 1) & Self::LANES).count_ones()
+    }
+
+    /// Counts the `G` and `C` bases in the k-mer.
+    pub const fn gc_count(&self) -> u32 {
+        // `C` (`0b00`) and `G` (`0b11`) are the only bases with equal bits.
+        let inner = self.as_masked();
+        (!(inner ^ inner >> 1) & Self::LANES).count_ones()
+    }
+
+    /// Returns the length of the longest run of identical bases in the
+    /// k-mer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<6>::from_bases([Base::G, Base::A, Base::A, Base::A, Base::T, Base::T]);
+    /// assert_eq!(kmer.longest_homopolymer(), 3);
+    /// ```
+    pub const fn longest_homopolymer(&self) -> u32 {
+        // Each lane but the last is set if its base equals the next one, so
+        // a homopolymer of n bases is a run of n - 1 set lanes.
+        let inner = self.as_masked();
+        let diff = inner ^ inner >> 2;
+        let mut runs = !(diff | diff >> 1) & (Self::LANES >> 2);
+        let mut longest = 1;
+        while runs != 0 {
+            runs &= runs >> 2;
+            longest += 1;
+        }
+        longest
+    }
+
+    /// Returns the Shannon entropy of the bases in the k-mer, in bits.
+    ///
+    /// The entropy is 0 for a homopolymer and 2 for a k-mer with equal
+    /// numbers of each base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<4>::from_bases([Base::G, Base::A, Base::T, Base::C]);
+    /// assert_eq!(kmer.entropy(), 2.0);
+    /// assert_eq!(Kmer::<4>::from_bases([Base::A; 4]).entropy(), 0.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        let counts = [Base::C, Base::A, Base::T, Base::G].map(|base| self.count(base) as u64);
+        utils::complexity::entropy(counts)
+    }
+
+    /// Returns the linguistic complexity of the k-mer: the number of its
+    /// distinct substrings, of every length, divided by the most that any
+    /// k-mer could have.
+    ///
+    /// The complexity is 1 for a k-mer with no repeated substrings that could
+    /// have been avoided, and smallest for a homopolymer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<4>::from_bases([Base::G, Base::A, Base::T, Base::C]);
+    /// assert_eq!(kmer.linguistic_complexity(), 1.0);
+    /// // 4 of the 10 substrings of a 4-mer
+    /// assert_eq!(Kmer::<4>::from_bases([Base::A; 4]).linguistic_complexity(), 0.4);
+    /// ```
+    pub fn linguistic_complexity(&self) -> f64 {
+        let mut codes = [0; K];
+        for (code, base) in codes.iter_mut().zip(self.bases()) {
+            *code = base as u8;
+        }
+        utils::complexity::linguistic_complexity(&codes, &mut [0; K])
+    }
+
+    /// Reverses the order of the bases in the k-mer, without complementing
+    /// them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<3>::from_bases([Base::G, Base::A, Base::T]);
+    /// assert_eq!(kmer.reverse().to_string(), "TAG");
+    /// ```
+    pub const fn reverse(self) -> Self {
+        Self {
+            inner: utils::lanes::reverse(self.as_masked()) >> (64 - K * 2),
+        }
+    }
+
+    /// Returns the reverse complement of the k-mer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<4>::from_bases([Base::G, Base::A, Base::T, Base::T]);
+    /// assert_eq!(kmer.reverse_complement().to_string(), "AATC");
+    /// ```
+    pub const fn reverse_complement(self) -> Self {
+        // Complementing a base flips both of its bits.
+        Self {
+            inner: self.reverse().inner ^ (Self::LANES * 0b11),
+        }
+    }
+
+    /// Returns `true` if the k-mer is not greater than its reverse
+    /// complement.
+    pub const fn is_canonical(&self) -> bool {
+        self.as_masked() <= self.reverse_complement().inner
+    }
+
+    /// Returns the smaller of the k-mer and its reverse complement.
+    ///
+    /// Bases are ordered by their 2-bit encoding, `C < A < T < G`.
+    pub const fn canonical(self) -> Self {
+        if self.is_canonical() {
+            self
+        } else {
+            self.reverse_complement()
+        }
+    }
+
+    /// Returns an invertible hash of the k-mer that fits in its `2K` bits.
+    ///
+    /// Distinct k-mers have distinct hashes, so the hash can be stored in
+    /// place of the k-mer and turned back into it with
+    /// [`from_mix_hash`](Self::from_mix_hash). See [`mix`].
+    ///
+    /// [`LargeKmer::mix_hash`](crate::large::LargeKmer::mix_hash) gives the
+    /// same hash for k-mers of up to 32 bases, but the hashes of longer
+    /// k-mers cannot be inverted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::<4>::from_bases([Base::G, Base::A, Base::T, Base::T]);
+    /// let hash = kmer.mix_hash();
+    /// assert!(hash < 1 << 8);
+    /// assert_eq!(Kmer::<4>::from_mix_hash(hash).to_string(), "GATT");
+    /// ```
+    pub const fn mix_hash(&self) -> u64 {
+        mix::hash_bits(self.as_masked(), K as u32 * 2)
+    }
+
+    /// Recovers a k-mer from its [`mix_hash`](Self::mix_hash).
+    pub const fn from_mix_hash(hash: u64) -> Self {
+        Self {
+            inner: mix::unhash_bits(hash, K as u32 * 2),
+        }
+    }
+
+    /// Returns the k-mer as a lowercase string.
+    ///
+    /// Equivalent to `format!("{kmer:#}")`.
+    #[cfg(feature = "alloc")]
+    pub fn to_lowercase_string(&self) -> String {
+        alloc::format!("{self:#}")
+    }
+
+    /// Returns the k-mer as an RNA string, using `U` in place of `T`.
+    #[cfg(feature = "alloc")]
+    pub fn to_rna_string(&self) -> String {
+        self.bases().map(|b| b.to_rna_ascii() as char).collect()
+    }
+
     /// Convert the k-mer into its inner value, masked to `K` bases.
     pub const fn as_masked(&self) -> u64 {
         bitfrob::u64_get_region(0, if K > 31 { 63 } else { K as u32 * 2 - 1 }, self.inner)
@@ -133,7 +490,7 @@ impl<const K: usize> Kmer<K> {
 }
 
 /// An iterator over the bases in a k-mer.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Bases<'a, const K: usize> {
     inner: &'a Kmer<K>,
     pos: usize,
@@ -158,6 +515,54 @@ impl<'a, const K: usize> Iterator for Bases<'a, K> {
         let remaining = K - self.pos;
         (remaining, Some(remaining))
     }
+
+    fn count(self) -> usize {
+        K - self.pos
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        // SAFETY: `base` is always in the range `0..4`.
+        (self.pos < K).then(|| unsafe { Base::from_u8_unchecked(self.inner.inner as u8 & 3) })
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.pos = self.pos.saturating_add(n).min(K);
+        self.next()
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        // Shift the remaining bases out of a copy of the k-mer, rather than
+        // tracking the position.
+        let word = self.inner.inner;
+        (0..K - self.pos).rev().fold(init, |acc, i| {
+            // SAFETY: `base` is always in the range `0..4`.
+            f(acc, unsafe {
+                Base::from_u8_unchecked((word >> (i * 2)) as u8 & 3)
+            })
+        })
+    }
+
+    #[cfg(feature = "unstable_nightly")]
+    fn try_fold<B, F, R>(&mut self, init: B, mut f: F) -> R
+    where
+        F: FnMut(B, Self::Item) -> R,
+        R: core::ops::Try<Output = B>,
+    {
+        let word = self.inner.inner;
+        let mut acc = init;
+        while self.pos < K {
+            let i = K - self.pos - 1;
+            self.pos += 1;
+            // SAFETY: `base` is always in the range `0..4`.
+            acc = f(acc, unsafe {
+                Base::from_u8_unchecked((word >> (i * 2)) as u8 & 3)
+            })?;
+        }
+        R::from_output(acc)
+    }
 }
 
 impl<'a, const K: usize> FusedIterator for Bases<'a, K> {}
@@ -225,6 +630,42 @@ mod tests {
         assert_eq!(bases[19..23], vec![Base::T, Base::T, Base::T, Base::T]);
     }
 
+    #[test]
+    fn bases_internal_iteration() {
+        let kmer = Kmer::<32>::from(0x1234_5678_9ABC_DEF0 | 1 << 63);
+        let expected: Vec<Base> = core::iter::from_fn({
+            let mut bases = kmer.bases();
+            move || bases.next()
+        })
+        .collect();
+        assert_eq!(expected.len(), 32);
+
+        for skip in [0, 1, 17, 31, 32] {
+            let mut bases = kmer.bases();
+            if skip > 0 {
+                assert_eq!(bases.nth(skip - 1), Some(expected[skip - 1]));
+            }
+            let rest = &expected[skip..];
+            assert_eq!(bases.clone().count(), rest.len());
+            assert_eq!(bases.clone().last(), rest.last().copied());
+            let folded = bases.clone().fold(Vec::new(), |mut acc, base| {
+                acc.push(base);
+                acc
+            });
+            assert_eq!(folded, rest);
+
+            // `position` stops early through `try_fold`
+            let t = rest.iter().position(|&base| base == Base::T);
+            assert_eq!(bases.position(|base| base == Base::T), t);
+            assert_eq!(
+                bases.collect::<Vec<_>>(),
+                rest[t.map_or(rest.len(), |t| t + 1)..]
+            );
+        }
+        assert_eq!(kmer.bases().nth(32), None);
+        assert_eq!(kmer.bases().nth(usize::MAX), None);
+    }
+
     #[test]
     fn convert() {
         let mut kmer = Kmer::<5>::new();
@@ -246,6 +687,116 @@ mod tests {
         assert_eq!(kmer.as_masked(), 0x05);
     }
 
+    #[test]
+    fn display() {
+        let kmer = Kmer::<4>::from_bases([Base::A, Base::C, Base::G, Base::T]);
+        assert_eq!(kmer.to_string(), "ACGT");
+        assert_eq!(format!("{kmer:#}"), "acgt");
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(kmer.to_lowercase_string(), "acgt");
+            assert_eq!(kmer.to_rna_string(), "ACGU");
+        }
+    }
+
+    #[test]
+    fn count() {
+        let mut kmer = Kmer::<32>::new();
+        for _ in 0..8 {
+            kmer.push(Base::A).push(Base::C).push(Base::G).push(Base::G);
+        }
+        assert_eq!(kmer.count(Base::A), 8);
+        assert_eq!(kmer.count(Base::C), 8);
+        assert_eq!(kmer.count(Base::G), 16);
+        assert_eq!(kmer.count(Base::T), 0);
+        assert_eq!(kmer.gc_count(), 24);
+
+        // bases shifted out of the k-mer are not counted
+        let mut kmer = Kmer::<3>::new();
+        kmer.push(Base::T)
+            .push(Base::T)
+            .push(Base::A)
+            .push(Base::C)
+            .push(Base::G);
+        assert_eq!(kmer.count(Base::T), 0);
+        assert_eq!(kmer.count(Base::C), 1);
+        assert_eq!(kmer.gc_count(), 2);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut kmer = Kmer::<32>::new();
+        for _ in 0..8 {
+            kmer.push(Base::A).push(Base::C).push(Base::G).push(Base::T);
+        }
+        assert_eq!(kmer.reverse().to_string(), "TGCA".repeat(8));
+
+        let mut kmer = Kmer::<5>::new();
+        kmer.push(Base::G)
+            .push(Base::A)
+            .push(Base::A)
+            .push(Base::C)
+            .push(Base::T);
+        kmer.push(Base::T);
+        assert_eq!(kmer.reverse().to_string(), "TTCAA");
+        assert_eq!(kmer.reverse().reverse().as_masked(), kmer.as_masked());
+    }
+
+    #[test]
+    fn reverse_complement() {
+        let mut kmer = Kmer::<32>::new();
+        for _ in 0..8 {
+            kmer.push(Base::A).push(Base::A).push(Base::G).push(Base::T);
+        }
+        assert_eq!(kmer.reverse_complement().to_string(), "ACTT".repeat(8));
+
+        let kmer = Kmer::<3>::from_bases([Base::G, Base::G, Base::A]);
+        assert_eq!(kmer.reverse_complement().to_string(), "TCC");
+        assert!(!kmer.is_canonical());
+        assert_eq!(kmer.canonical().to_string(), "TCC");
+        assert_eq!(
+            kmer.canonical().canonical().as_masked(),
+            kmer.canonical().as_masked()
+        );
+
+        // palindromes are their own reverse complement
+        let kmer = Kmer::<4>::from_bases([Base::A, Base::C, Base::G, Base::T]);
+        assert!(kmer.is_canonical());
+        assert_eq!(kmer.reverse_complement().as_masked(), kmer.as_masked());
+    }
+
+    #[test]
+    fn push_front() {
+        let mut kmer = Kmer::<32>::new();
+        kmer.push_front(Base::G).push_front(Base::A);
+        assert_eq!(kmer.as_masked(), 0b01_11 << 60);
+    }
+
+    #[test]
+    fn with_base_at() {
+        let kmer = Kmer::<32>::from_bases([Base::C; 32]);
+        let first = kmer.with_base_at(0, Base::G);
+        let last = kmer.with_base_at(31, Base::A);
+        assert_eq!(first.as_masked(), 0b11 << 62);
+        assert_eq!(last.as_masked(), 0b01);
+        assert_eq!(first.with_base_at(0, Base::C).as_masked(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_base_at_out_of_bounds() {
+        Kmer::<3>::new().with_base_at(3, Base::A);
+    }
+
+    #[test]
+    fn debug() {
+        let kmer = Kmer::<3>::from_bases([Base::G, Base::A, Base::T]);
+        assert_eq!(
+            format!("{kmer:?}"),
+            r#"Kmer { k: 3, bases: "GAT", raw: 0x36 }"#
+        );
+    }
+
     #[cfg(feature = "unstable_nightly")]
     #[test]
     fn join() {
@@ -257,4 +808,34 @@ mod tests {
         let bases: Vec<Base> = kmer3.bases().collect();
         assert_eq!(bases, vec![Base::A, Base::C, Base::G, Base::T]);
     }
+
+    #[test]
+    fn mix_hash() {
+        let mut hashes: Vec<u64> = (0..1 << 12)
+            .map(|i| Kmer::<6>::from(i).mix_hash())
+            .collect();
+        for (i, &hash) in hashes.iter().enumerate() {
+            assert_eq!(Kmer::<6>::from_mix_hash(hash).as_masked(), i as u64);
+        }
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), 1 << 12);
+        assert_eq!(hashes.last(), Some(&((1 << 12) - 1)));
+
+        let kmer = Kmer::<32>::from(0x0123_4567_89ab_cdef);
+        assert_eq!(
+            Kmer::<32>::from_mix_hash(kmer.mix_hash()).as_masked(),
+            kmer.as_masked()
+        );
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn archive() {
+        let kmers = [Kmer::<5>::from(0b11_01_10_10_01), Kmer::from(u64::MAX)];
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&kmers).unwrap();
+        let archived = rkyv::access::<[ArchivedKmer<5>; 2], rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(Kmer::from(&archived[0]).to_string(), "GATTA");
+        assert_eq!(Kmer::from(&archived[1]).as_masked(), (1 << 10) - 1);
+    }
 }
@@ -0,0 +1,251 @@
+use core::fmt::{self, Display};
+
+use super::{Kmer, LOW_BITS};
+use crate::base::{self, Base};
+use crate::hash::mix;
+use crate::utils::lanes;
+
+/// A k-mer of up to 32 bases packed into a `u64`, whose `k` is chosen at
+/// runtime.
+///
+/// This is the same representation as [`Kmer<K>`], with `k` stored alongside
+/// the bases, for programs that only learn `k` at runtime. The bases are
+/// always masked to `k`, so k-mers can be compared and hashed directly; two
+/// k-mers with different `k` are never equal.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::small::{DynKmer, Kmer};
+/// use helicase::Base;
+///
+/// let k = 5;
+/// let mut kmer = DynKmer::new(k);
+/// for base in [Base::G, Base::A, Base::T, Base::T, Base::A, Base::C] {
+///     kmer.push(base);
+/// }
+/// assert_eq!(kmer.to_string(), "ATTAC");
+/// assert_eq!(kmer.canonical().to_string(), "ATTAC");
+/// assert_eq!(kmer.reverse_complement().to_string(), "GTAAT");
+///
+/// let fixed: Kmer<5> = kmer.to_small().unwrap();
+/// assert_eq!(DynKmer::from(fixed), kmer);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DynKmer {
+    /// The bases, with the first in the most significant of the low `2k`
+    /// bits, and all higher bits cleared.
+    value: u64,
+    k: usize,
+}
+
+impl fmt::Debug for DynKmer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynKmer")
+            .field("k", &self.k)
+            // quoted like a string, without allocating one
+            .field("bases", &format_args!("\"{self}\""))
+            .field("raw", &format_args!("{:#x}", self.value))
+            .finish()
+    }
+}
+
+impl Display for DynKmer {
+    /// Formats the k-mer as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        base::write_bases(f, self.bases())
+    }
+}
+
+impl<const K: usize> From<Kmer<K>> for DynKmer {
+    fn from(kmer: Kmer<K>) -> Self {
+        Self {
+            value: kmer.as_masked(),
+            k: K,
+        }
+    }
+}
+
+impl DynKmer {
+    /// Creates a k-mer of `k` bases, all initialized to `Base::C`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is not in `1..=32`.
+    pub const fn new(k: usize) -> Self {
+        assert!(k > 0 && k <= 32, "k must be between 1 and 32");
+        Self { value: 0, k }
+    }
+
+    /// Creates a k-mer of `k` bases from a packed value, with the first base
+    /// in the most significant of the low `2k` bits. Higher bits are
+    /// ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is not in `1..=32`.
+    pub const fn from_value(value: u64, k: usize) -> Self {
+        let kmer = Self::new(k);
+        Self {
+            value: value & kmer.mask(),
+            k,
+        }
+    }
+
+    /// Creates a k-mer from a slice of bases, with `k` equal to its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty or longer than 32 bases.
+    pub fn from_bases(bases: &[Base]) -> Self {
+        let mut kmer = Self::new(bases.len());
+        for &base in bases {
+            kmer.push(base);
+        }
+        kmer
+    }
+
+    /// Returns the number of bases in the k-mer.
+    pub const fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the packed bases, with the first base in the most significant
+    /// of the low `2k` bits, and higher bits cleared.
+    pub const fn as_masked(&self) -> u64 {
+        self.value
+    }
+
+    /// The low `2k` bits.
+    const fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.k * 2)
+    }
+
+    /// Pushes a base onto the end of the k-mer, removing the first base.
+    pub const fn push(&mut self, base: Base) -> &mut Self {
+        self.value = (self.value << 2 | base as u64) & self.mask();
+        self
+    }
+
+    /// Pushes a base onto the front of the k-mer, removing the last base.
+    pub const fn push_front(&mut self, base: Base) -> &mut Self {
+        self.value = self.value >> 2 | (base as u64) << ((self.k - 1) * 2);
+        self
+    }
+
+    /// Returns the base at index `i`, or `None` if `i` is out of bounds.
+    pub const fn get(&self, i: usize) -> Option<Base> {
+        if i >= self.k {
+            return None;
+        }
+        let code = (self.value >> ((self.k - i - 1) * 2)) as u8 & 0b11;
+        // SAFETY: masked to 2 bits, and every 2 bit code is a base.
+        Some(unsafe { Base::from_u8_unchecked(code) })
+    }
+
+    /// Returns an iterator over the bases in the k-mer.
+    pub fn bases(&self) -> impl DoubleEndedIterator<Item = Base> + ExactSizeIterator + use<> {
+        let kmer = *self;
+        (0..self.k).map(move |i| kmer.get(i).expect("index is less than k"))
+    }
+
+    /// Returns the reverse complement of the k-mer.
+    pub const fn reverse_complement(self) -> Self {
+        let reversed = lanes::reverse(self.value) >> (64 - self.k * 2);
+        // Complementing a base flips both of its bits.
+        Self {
+            value: reversed ^ ((LOW_BITS * 0b11) & self.mask()),
+            k: self.k,
+        }
+    }
+
+    /// Returns `true` if the k-mer is not greater than its reverse
+    /// complement.
+    pub const fn is_canonical(&self) -> bool {
+        self.value <= self.reverse_complement().value
+    }
+
+    /// Returns the smaller of the k-mer and its reverse complement.
+    ///
+    /// Bases are ordered by their 2-bit encoding, `C < A < T < G`.
+    pub const fn canonical(self) -> Self {
+        if self.is_canonical() {
+            self
+        } else {
+            self.reverse_complement()
+        }
+    }
+
+    /// Returns an invertible hash of the k-mer that fits in its `2k` bits,
+    /// equal to [`Kmer::mix_hash`] for the same bases.
+    pub const fn mix_hash(&self) -> u64 {
+        mix::hash_bits(self.value, self.k as u32 * 2)
+    }
+
+    /// Recovers a k-mer of `k` bases from its [`mix_hash`](Self::mix_hash).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is not in `1..=32`.
+    pub const fn from_mix_hash(hash: u64, k: usize) -> Self {
+        Self::from_value(mix::unhash_bits(hash, Self::new(k).k as u32 * 2), k)
+    }
+
+    /// Converts the k-mer into a [`Kmer<K>`].
+    ///
+    /// Returns `None` if `k` is not `K`.
+    pub fn to_small<const K: usize>(&self) -> Option<Kmer<K>> {
+        (self.k == K).then(|| Kmer::from(self.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test::random_bases;
+
+    #[test]
+    fn matches_small() {
+        let bases = random_bases(40, 0);
+        let mut fixed = Kmer::<13>::new();
+        let mut kmer = DynKmer::new(13);
+        for &base in &bases {
+            fixed.push(base);
+            kmer.push(base);
+            assert_eq!(kmer.as_masked(), fixed.as_masked());
+            assert_eq!(kmer.reverse_complement(), fixed.reverse_complement().into());
+            assert_eq!(kmer.canonical(), fixed.canonical().into());
+            assert_eq!(kmer.mix_hash(), fixed.mix_hash());
+            assert_eq!(DynKmer::from_mix_hash(kmer.mix_hash(), 13), kmer);
+            assert_eq!(kmer.to_string(), fixed.to_string());
+        }
+
+        let mut front = kmer;
+        front.push_front(Base::G);
+        assert_eq!(front.to_string(), "G".to_string() + &kmer.to_string()[..12]);
+        assert_eq!(kmer.bases().next_back(), bases.last().copied());
+        assert!(kmer.to_small::<12>().is_none());
+        assert_eq!(
+            kmer.to_small::<13>().map(|kmer| kmer.as_masked()),
+            Some(fixed.as_masked())
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let full = DynKmer::from_value(u64::MAX, 32);
+        assert_eq!(full.to_string(), "G".repeat(32));
+        assert_eq!(full.reverse_complement().as_masked(), 0);
+        assert_eq!(DynKmer::from_value(u64::MAX, 1).as_masked(), 0b11);
+        assert_eq!(DynKmer::from_bases(&[Base::T]).get(0), Some(Base::T));
+        assert_eq!(DynKmer::new(3).get(3), None);
+        // equal bases with different lengths
+        assert_ne!(DynKmer::new(3), DynKmer::new(4));
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_long() {
+        DynKmer::new(33);
+    }
+}
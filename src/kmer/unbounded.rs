@@ -1,51 +1,253 @@
-use std::iter::FusedIterator;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::Display;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::ops::RangeBounds;
+use core::str::FromStr;
 
 use bitvec::bitbox;
 use bitvec::boxed::BitBox;
 use bitvec::field::BitField;
 use bitvec::order::Lsb0;
-use bitvec::view::BitView;
 
-use crate::Base;
+use crate::base::{self, Base, ParseBaseError};
+use crate::hash::nthash;
+use crate::utils::{self, lanes};
 
 /// A fixed-size k-mer represented as a bit vector.
 ///
 /// Stores 1 to `usize::MAX / 2` bases.
-#[derive(Debug)]
+///
+/// Equality and hashing are defined over the logical order of the bases,
+/// regardless of how they are laid out in storage.
+#[derive(Clone)]
 pub struct Kmer {
     store: BitBox<usize, Lsb0>,
     /// Index of the first base, in bits.
     start: usize,
+    /// Rolling hash of the bases, if enabled.
+    hash: Option<u64>,
+}
+
+impl core::fmt::Debug for Kmer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Kmer")
+            .field("k", &self.size())
+            .field("bases", &self.to_string())
+            .finish()
+    }
+}
+
+impl Display for Kmer {
+    /// Formats the k-mer as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        base::write_bases(f, self.bases())
+    }
+}
+
+impl FromStr for Kmer {
+    type Err = ParseBaseError;
+
+    /// Parses a k-mer from ASCII bases, with `k` equal to the length of the
+    /// string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        base::parse_ascii(s.as_bytes()).collect()
+    }
+}
+
+impl PartialEq for Kmer {
+    fn eq(&self, other: &Self) -> bool {
+        if self.start == other.start {
+            return self.store == other.store;
+        }
+        self.size() == other.size() && self.bases().eq(other.bases())
+    }
+}
+
+impl Eq for Kmer {}
+
+impl PartialOrd for Kmer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Kmer {
+    /// Compares k-mers lexicographically by the 2-bit codes of their bases
+    /// (`C < A < T < G`), like [`small::Kmer::as_masked`]. A k-mer that is a
+    /// prefix of another is ordered first.
+    ///
+    /// [`small::Kmer::as_masked`]: crate::small::Kmer::as_masked
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bases()
+            .map(|base| base as u8)
+            .cmp(other.bases().map(|base| base as u8))
+    }
+}
+
+impl Hash for Kmer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        // pack 32 bases at a time, in logical order
+        let mut word = 0u64;
+        for (i, base) in self.bases().enumerate() {
+            word = word << 2 | base as u64;
+            if i % 32 == 31 {
+                state.write_u64(word);
+                word = 0;
+            }
+        }
+        state.write_u64(word);
+    }
+}
+
+impl FromIterator<Base> for Kmer {
+    /// Collects bases into a k-mer, with `k` equal to the number of bases.
+    fn from_iter<I: IntoIterator<Item = Base>>(iter: I) -> Self {
+        Self::from_bases(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<const K: usize> From<crate::small::Kmer<K>> for Kmer {
+    fn from(kmer: crate::small::Kmer<K>) -> Self {
+        let mut store = bitbox![usize, Lsb0; 0; K * 2];
+        // both layouts store the first base in the most significant bits
+        store.store_le(kmer.as_masked());
+        Self::from_store(store)
+    }
 }
 
 impl Kmer {
     /// Creates a new k-mer with the given size.
     pub fn new(k: usize) -> Self {
+        Self::from_store(bitbox![usize, Lsb0; 0; k * 2])
+    }
+
+    /// Creates a k-mer from storage with the first base in the most
+    /// significant bits.
+    pub(crate) fn from_store(store: BitBox<usize, Lsb0>) -> Self {
         Self {
-            store: bitbox![usize, Lsb0; 0; k * 2],
-            start: k * 2,
+            start: store.len(),
+            store,
+            hash: None,
         }
     }
 
+    /// Creates a k-mer from a slice of bases, with `k` equal to the number of
+    /// bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let kmer = Kmer::from_bases(&[Base::G, Base::A, Base::T]);
+    /// assert_eq!(kmer.size(), 3);
+    /// assert!(kmer.bases().eq([Base::G, Base::A, Base::T]));
+    /// ```
+    pub fn from_bases(bases: &[Base]) -> Self {
+        Self::from_codes(bases.len(), bases.iter().map(|&base| base as u8))
+    }
+
+    /// Creates a k-mer of `k` bases from bytes in the layout produced by
+    /// [`to_packed_bytes`](Self::to_packed_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not exactly `k.div_ceil(4)` bytes long,
+    /// or if the padding bits after the last base are not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::{FromPackedError, Kmer};
+    ///
+    /// let kmer = Kmer::from_packed(5, &[0b11_01_10_10, 0b01_000000]).unwrap();
+    /// assert_eq!(kmer.to_string(), "GATTA");
+    ///
+    /// assert_eq!(
+    ///     Kmer::from_packed(5, &[0b11_01_10_10]),
+    ///     Err(FromPackedError::Length { expected: 2, actual: 1 })
+    /// );
+    /// assert_eq!(
+    ///     Kmer::from_packed(5, &[0b11_01_10_10, 0b01_000001]),
+    ///     Err(FromPackedError::Padding)
+    /// );
+    /// ```
+    pub fn from_packed(k: usize, bytes: &[u8]) -> Result<Self, FromPackedError> {
+        let expected = k.div_ceil(4);
+        if bytes.len() != expected {
+            return Err(FromPackedError::Length {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        if let Some(last) = bytes.last()
+            && !k.is_multiple_of(4)
+            && last & (0xFF >> (k % 4 * 2)) != 0
+        {
+            return Err(FromPackedError::Padding);
+        }
+
+        let codes = bytes
+            .iter()
+            .flat_map(|byte| [byte >> 6, byte >> 4, byte >> 2, *byte].map(|code| code & 0b11));
+        Ok(Self::from_codes(k, codes))
+    }
+
+    /// Creates a k-mer from the 2-bit codes of its first `k` bases.
+    fn from_codes(k: usize, codes: impl Iterator<Item = u8>) -> Self {
+        let mut store = bitbox![usize, Lsb0; 0; k * 2];
+        // the first base is stored in the most significant bits
+        for (chunk, code) in store.chunks_exact_mut(2).rev().zip(codes) {
+            chunk.store(code);
+        }
+        Self::from_store(store)
+    }
+
+    /// Creates a k-mer from packed bytes, four bases per byte, with `k` equal
+    /// to `4 * bytes.len()`.
+    ///
+    /// Every bit of the input is part of a base, so there is no padding. To
+    /// create a k-mer whose size is not a multiple of 4, use
+    /// [`from_packed`](Self::from_packed).
     pub fn from_bytes(bytes: &[u8]) -> Self {
         let mut inner = bitbox!(usize, Lsb0; 0; bytes.len() * 8);
         for (byte, chunk) in bytes.iter().rev().zip(inner.chunks_exact_mut(8)) {
             chunk.store(*byte);
         }
-        Self {
-            store: inner,
-            start: bytes.len() * 8,
-        }
+        Self::from_store(inner)
     }
 
+    /// Appends a base to the end of the k-mer, dropping its first base.
+    ///
+    /// The bases are stored in a ring buffer, so the new base overwrites the
+    /// first one in place, and nothing else is moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
     pub fn push(&mut self, base: Base) {
-        self.store
-            .get_mut(self.start - 2..self.start)
-            .expect("slice is too small")
-            .copy_from_bitslice(&(base as usize).view_bits()[..2]);
+        // bases are stored downwards from `start`, so the first base is in
+        // the two bits below it, which never straddle a word
+        let bit = self.start - 2;
+        let word = &mut self.store.as_raw_mut_slice()[bit / usize::BITS as usize];
+        let shift = bit % usize::BITS as usize;
+        let first = (*word >> shift) as u8 & 0b11;
+        *word = *word & !(0b11 << shift) | (base as usize) << shift;
+
+        if let Some(hash) = self.hash {
+            // SAFETY: `first` is masked to two bits
+            let first = unsafe { Base::from_u8_unchecked(first) };
+            self.hash = Some(nthash::roll(hash, self.size(), first, base));
+        }
 
-        self.start = (self.start - 2) % self.store.len();
-        self.start += (self.start == 0) as usize * self.store.len(); // wrap around at 0
+        // wrap around at 0
+        self.start = bit + (bit == 0) as usize * self.store.len();
     }
 
     pub fn size(&self) -> usize {
@@ -54,9 +256,405 @@ impl Kmer {
 
     pub fn bases(&self) -> Bases<'_> {
         Bases {
+            cursor: Cursor::new(self),
             kmer: self,
+        }
+    }
+
+    /// Converts the k-mer into an iterator over its bases.
+    pub fn into_bases(self) -> IntoBases {
+        IntoBases {
+            cursor: Cursor::new(&self),
+            kmer: self,
+        }
+    }
+
+    /// Packs the k-mer into bytes, four bases per byte.
+    ///
+    /// The first base occupies the two most significant bits of the first
+    /// byte, using the same 2-bit codes as [`Base`]. If `k` is not a multiple
+    /// of 4, the unused low bits of the last byte are zero. The layout does
+    /// not depend on how the k-mer is stored internally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    ///
+    /// let kmer: Kmer = "GATTACA".parse().unwrap();
+    /// let bytes = kmer.to_packed_bytes();
+    /// assert_eq!(bytes, [0b11_01_10_10, 0b01_00_01_00]);
+    /// assert_eq!(Kmer::from_packed(7, &bytes), Ok(kmer));
+    /// ```
+    pub fn to_packed_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.size().div_ceil(4)];
+        for (i, base) in self.bases().enumerate() {
+            bytes[i / 4] |= (base as u8) << (6 - i % 4 * 2);
+        }
+        bytes
+    }
+
+    /// Converts the k-mer into a [`small::Kmer`](crate::small::Kmer).
+    ///
+    /// Returns `None` if the size of the k-mer is not `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    ///
+    /// let kmer: Kmer = "GATTACA".parse().unwrap();
+    /// assert_eq!(kmer.to_small::<7>().unwrap().to_string(), "GATTACA");
+    /// assert!(kmer.to_small::<8>().is_none());
+    /// ```
+    pub fn to_small<const K: usize>(&self) -> Option<crate::small::Kmer<K>> {
+        if self.size() != K {
+            return None;
+        }
+        let inner = self.bases().fold(0, |acc, base| acc << 2 | base as u64);
+        Some(crate::small::Kmer::from(inner))
+    }
+
+    /// Sets every base of the k-mer to `Base::C`, reusing the existing
+    /// storage.
+    pub fn clear(&mut self) {
+        self.fill(Base::C);
+    }
+
+    /// Sets every base of the k-mer to `base`, reusing the existing storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer: Kmer = "GATTACA".parse().unwrap();
+    /// kmer.fill(Base::T);
+    /// assert_eq!(kmer.to_string(), "TTTTTTT");
+    /// kmer.clear();
+    /// assert_eq!(kmer.to_string(), "CCCCCCC");
+    /// ```
+    pub fn fill(&mut self, base: Base) {
+        let word = lanes::LOW_BITS * base as usize;
+        self.store.as_raw_mut_slice().fill(word);
+        self.start = self.store.len();
+        self.rehash();
+    }
+
+    /// Reverses the order of the bases in the k-mer, without complementing
+    /// them.
+    pub fn reverse(&mut self) {
+        self.reverse_store();
+        self.rehash();
+    }
+
+    /// Reverse complements the k-mer in place.
+    ///
+    /// Operates on whole words of the underlying storage rather than on
+    /// individual bases.
+    pub fn reverse_complement(&mut self) {
+        self.reverse_store();
+        let bits = self.store.len();
+        lanes::complement_slice(self.store.as_raw_mut_slice(), bits);
+        self.rehash();
+    }
+
+    fn reverse_store(&mut self) {
+        self.normalize();
+        let bits = self.store.len();
+        lanes::reverse_slice(self.store.as_raw_mut_slice(), bits);
+    }
+
+    /// Enables the rolling [ntHash](nthash) of the k-mer, which is then
+    /// updated in constant time on every [`push`](Self::push).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::hash::nthash;
+    /// use helicase::unbounded::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer = Kmer::new(40).with_rolling_hash();
+    /// for _ in 0..50 {
+    ///     kmer.push(Base::A);
+    ///     kmer.push(Base::T);
+    /// }
+    /// assert_eq!(kmer.rolling_hash(), Some(nthash::hash(kmer.bases())));
+    /// ```
+    pub fn with_rolling_hash(mut self) -> Self {
+        self.hash = Some(nthash::hash(self.bases()));
+        self
+    }
+
+    /// Returns the rolling hash of the k-mer, or `None` if it was not enabled
+    /// with [`with_rolling_hash`](Self::with_rolling_hash).
+    pub fn rolling_hash(&self) -> Option<u64> {
+        self.hash
+    }
+
+    /// Recomputes the rolling hash, if enabled, after the bases have changed.
+    fn rehash(&mut self) {
+        if self.hash.is_some() {
+            self.hash = Some(nthash::hash(self.bases()));
+        }
+    }
+
+    /// Returns `true` if the k-mer is not greater than its reverse
+    /// complement.
+    ///
+    /// Bases are compared by their 2-bit encoding. The reverse complement is
+    /// never materialized, and comparison stops at the first differing base.
+    pub fn is_canonical(&self) -> bool {
+        let k = self.size();
+        for (i, base) in self.bases().enumerate() {
+            let rc = self.base_at(k - i - 1).complement();
+            if base != rc {
+                return (base as u8) < (rc as u8);
+            }
+        }
+        true
+    }
+
+    /// Returns the canonical form of the k-mer: the lesser of the k-mer and
+    /// its reverse complement.
+    ///
+    /// Already-canonical k-mers are returned unchanged, and others are reverse
+    /// complemented in place.
+    pub fn canonical(mut self) -> Self {
+        if !self.is_canonical() {
+            self.reverse_complement();
+        }
+        self
+    }
+
+    /// Returns a new k-mer containing the bases in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    ///
+    /// let kmer: Kmer = "GATTACA".parse().unwrap();
+    /// assert_eq!(kmer.subkmer(..3).to_string(), "GAT");
+    /// assert_eq!(kmer.subkmer(4..).to_string(), "ACA");
+    /// ```
+    pub fn subkmer(&self, range: impl RangeBounds<usize>) -> Self {
+        let range = utils::range(range, self.size());
+        let bits = range.len() * 2;
+        let mut store = bitbox![usize, Lsb0; 0; bits];
+        if bits > 0 {
+            // the range is stored downwards from the end of its first base,
+            // possibly wrapping around the start of the storage
+            let end = self.bit_end(range.start);
+            if end >= bits {
+                store.copy_from_bitslice(&self.store[end - bits..end]);
+            } else {
+                let wrapped = bits - end;
+                store[..wrapped].copy_from_bitslice(&self.store[self.store.len() - wrapped..]);
+                store[wrapped..].copy_from_bitslice(&self.store[..end]);
+            }
+        }
+        Self::from_store(store)
+    }
+
+    /// Returns the number of positions at which the bases of two k-mers
+    /// differ.
+    ///
+    /// Compares a word of bases at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the k-mers have different sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    ///
+    /// let a: Kmer = "GATTACA".parse().unwrap();
+    /// let b: Kmer = "GACTATA".parse().unwrap();
+    /// assert_eq!(a.hamming_distance(&b), 2);
+    /// ```
+    pub fn hamming_distance(&self, other: &Self) -> u32 {
+        assert_eq!(self.size(), other.size(), "k-mers have different sizes");
+        let per_word = usize::BITS as usize / 2;
+        (0..self.size())
+            .step_by(per_word)
+            .map(|i| {
+                let n = per_word.min(self.size() - i);
+                lanes::mismatches(self.load_bases(i, n), other.load_bases(i, n))
+            })
+            .sum()
+    }
+
+    /// Loads `n` bases starting at index `i` into the low bits of a word, with
+    /// base `i` in the most significant position.
+    fn load_bases(&self, i: usize, n: usize) -> usize {
+        let end = self.bit_end(i);
+        let bits = n * 2;
+        if end >= bits {
+            self.store[end - bits..end].load_le()
+        } else {
+            // the bases wrap around the start of the storage
+            let wrapped = bits - end;
+            let high: usize = self.store[..end].load_le();
+            high << wrapped | self.store[self.store.len() - wrapped..].load_le::<usize>()
+        }
+    }
+
+    /// Returns the base at index `i`.
+    fn base_at(&self, i: usize) -> Base {
+        let end = self.bit_end(i);
+        // SAFETY: 2 bit bases are always valid.
+        unsafe { Base::from_u8_unchecked(self.store[end - 2..end].load::<u8>()) }
+    }
+
+    /// Returns the index one past the last bit of the base at index `i`,
+    /// which must be less than `k`.
+    fn bit_end(&self, i: usize) -> usize {
+        // bases are stored downwards from `start`, wrapping at 0
+        let bits = i * 2;
+        self.start + (bits >= self.start) as usize * self.store.len() - bits
+    }
+
+    /// Rotates the storage so that the first base occupies the most
+    /// significant bits.
+    ///
+    /// Pushing bases onto a k-mer moves its first base around the underlying
+    /// ring buffer. Once normalized, the storage holds the bases as one
+    /// little-endian multi-word integer with the last base in bits `0..2`,
+    /// matching the layout of [`small::Kmer`](crate::small::Kmer), and can be
+    /// read directly with [`as_raw_slice`](Self::as_raw_slice).
+    ///
+    /// New k-mers are always normalized, and normalizing an already
+    /// normalized k-mer does nothing.
+    pub fn normalize(&mut self) {
+        if !self.is_normalized() {
+            self.store.rotate_left(self.start);
+            self.start = self.store.len();
+        }
+    }
+
+    /// Returns `true` if the storage is [normalized](Self::normalize).
+    pub fn is_normalized(&self) -> bool {
+        self.start == self.store.len()
+    }
+
+    /// Returns the words of the underlying storage, or `None` if the k-mer is
+    /// not [normalized](Self::normalize).
+    ///
+    /// Bits of the last word beyond `2 * k` are unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::unbounded::Kmer;
+    /// use helicase::Base;
+    ///
+    /// let mut kmer: Kmer = "GATTACA".parse().unwrap();
+    /// kmer.push(Base::T);
+    /// assert_eq!(kmer.as_raw_slice(), None);
+    ///
+    /// kmer.normalize();
+    /// let words = kmer.as_raw_slice().unwrap();
+    /// assert_eq!(words[0] & 0x3FFF, 0b01_10_10_01_00_01_10);
+    /// ```
+    pub fn as_raw_slice(&self) -> Option<&[usize]> {
+        self.is_normalized().then(|| self.store.as_raw_slice())
+    }
+}
+
+/// An error returned by [`Kmer::from_packed`],
+/// [`Sequence::from_packed_bytes`](crate::Sequence::from_packed_bytes) and
+/// [`Sequence::from_packed_2bit`](crate::Sequence::from_packed_2bit) for
+/// invalid input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromPackedError {
+    /// The input was not exactly long enough to hold the bases.
+    Length {
+        /// The number of bytes needed to hold the bases.
+        expected: usize,
+        /// The number of bytes given.
+        actual: usize,
+    },
+    /// The padding bits after the last base were not zero.
+    Padding,
+}
+
+impl Display for FromPackedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FromPackedError::Length { expected, actual } => {
+                write!(f, "expected {expected} packed bytes, got {actual}")
+            }
+            FromPackedError::Padding => write!(f, "padding bits are not zero"),
+        }
+    }
+}
+
+impl core::error::Error for FromPackedError {}
+
+/// Position of a base iterator within a k-mer.
+///
+/// Bases are read a word at a time and emitted from a register, rather than
+/// loading each base separately.
+#[derive(Debug, Clone)]
+struct Cursor {
+    /// Number of bases that have already been read.
+    num_read: usize,
+    /// Index one past the last bit of the next word to load.
+    end: usize,
+    /// Bases that have been loaded but not read, left-aligned.
+    word: usize,
+    /// Number of bases remaining in `word`.
+    buffered: usize,
+}
+
+impl Cursor {
+    const BITS: usize = usize::BITS as usize;
+
+    fn new(kmer: &Kmer) -> Self {
+        Self {
             num_read: 0,
+            end: kmer.start,
+            word: 0,
+            buffered: 0,
+        }
+    }
+
+    fn next(&mut self, kmer: &Kmer) -> Option<Base> {
+        if self.num_read >= kmer.size() {
+            return None;
+        }
+
+        if self.buffered == 0 {
+            // bases are stored downwards from `end`, wrapping at 0
+            let bits = self.end.min(Self::BITS);
+            self.word =
+                kmer.store[self.end - bits..self.end].load_le::<usize>() << (Self::BITS - bits);
+            self.buffered = bits / 2;
+            self.end -= bits;
+            if self.end == 0 {
+                self.end = kmer.store.len();
+            }
         }
+
+        let base = (self.word >> (Self::BITS - 2)) as u8;
+        self.word <<= 2;
+        self.buffered -= 1;
+        self.num_read += 1;
+        // SAFETY: `base` is always in the range `0..4`.
+        Some(unsafe { Base::from_u8_unchecked(base) })
+    }
+
+    fn remaining(&self, kmer: &Kmer) -> usize {
+        kmer.size() - self.num_read
     }
 }
 
@@ -64,34 +662,18 @@ impl Kmer {
 #[derive(Debug)]
 pub struct Bases<'a> {
     kmer: &'a Kmer,
-    /// Number of bases that have already been read.
-    num_read: usize,
+    cursor: Cursor,
 }
 
 impl<'a> Iterator for Bases<'a> {
     type Item = Base;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.num_read >= self.kmer.size() {
-            return None;
-        }
-
-        let bit_pos = match (self.kmer.start as isize - self.num_read as isize * 2)
-            .rem_euclid(self.kmer.store.len() as isize)
-        {
-            0 => self.kmer.store.len(),
-            p => p as usize,
-        };
-
-        let base = unsafe {
-            Base::from_u8_unchecked(self.kmer.store.get(bit_pos - 2..bit_pos)?.load::<u8>())
-        };
-        self.num_read += 1;
-        Some(base)
+        self.cursor.next(self.kmer)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.kmer.size() - self.num_read;
+        let remaining = self.cursor.remaining(self.kmer);
         (remaining, Some(remaining))
     }
 }
@@ -100,11 +682,54 @@ impl<'a> FusedIterator for Bases<'a> {}
 
 impl<'a> ExactSizeIterator for Bases<'a> {}
 
+impl<'a> IntoIterator for &'a Kmer {
+    type Item = Base;
+    type IntoIter = Bases<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bases()
+    }
+}
+
+/// An owning iterator over the bases in a k-mer.
+#[derive(Debug)]
+pub struct IntoBases {
+    kmer: Kmer,
+    cursor: Cursor,
+}
+
+impl Iterator for IntoBases {
+    type Item = Base;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next(&self.kmer)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.cursor.remaining(&self.kmer);
+        (remaining, Some(remaining))
+    }
+}
+
+impl FusedIterator for IntoBases {}
+
+impl ExactSizeIterator for IntoBases {}
+
+impl IntoIterator for Kmer {
+    type Item = Base;
+    type IntoIter = IntoBases;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_bases()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bitvec::field::BitField;
 
     use super::*;
+    use crate::utils::test::{random_ascii, random_bases};
 
     #[test]
     fn new() {
@@ -154,6 +779,325 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reverse() {
+        let mut kmer = Kmer::new(47);
+        let mut bases = random_bases(60, 0);
+        for &base in &bases {
+            kmer.push(base);
+        }
+        kmer.reverse();
+        bases.reverse();
+        assert_eq!(kmer.bases().collect::<Vec<_>>(), bases[..47]);
+    }
+
+    #[test]
+    fn reverse_complement() {
+        for k in [1, 31, 32, 33, 64, 100] {
+            let mut kmer = Kmer::new(k);
+            let bases = random_bases(k + 5, 0);
+            for &base in &bases {
+                kmer.push(base);
+            }
+            kmer.reverse_complement();
+            let expected: Vec<Base> = bases[5..].iter().rev().map(|b| b.complement()).collect();
+            assert_eq!(kmer.bases().collect::<Vec<_>>(), expected, "k = {k}");
+        }
+    }
+
+    #[test]
+    fn canonical() {
+        let from_str = |s: &str| {
+            let mut kmer = Kmer::new(s.len());
+            for c in s.bytes() {
+                kmer.push(Base::from_ascii(c).unwrap());
+            }
+            kmer
+        };
+        let to_string = |kmer: &Kmer| kmer.bases().map(|b| b.to_string()).collect::<String>();
+
+        // C < A < T < G
+        let kmer = from_str("CATG");
+        assert!(kmer.is_canonical()); // palindrome
+        assert_eq!(to_string(&kmer.canonical()), "CATG");
+
+        let kmer = from_str("GGA");
+        assert!(!kmer.is_canonical());
+        assert_eq!(to_string(&kmer.canonical()), "TCC");
+
+        let kmer = from_str("ACT");
+        assert!(kmer.is_canonical());
+        assert_eq!(to_string(&kmer.canonical()), "ACT");
+    }
+
+    #[test]
+    fn eq_and_hash() {
+        use std::collections::HashSet;
+
+        let mut a = Kmer::new(40);
+        let mut b = Kmer::new(40);
+        for i in 0..40 {
+            a.push(Base::try_from((i % 4) as u8).unwrap());
+        }
+        // same bases, different rotation
+        for i in 0..53 {
+            b.push(Base::try_from(((i + 27) % 4) as u8).unwrap());
+        }
+        assert_ne!(a.start, b.start);
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), b);
+
+        let set: HashSet<Kmer> = [a.clone(), b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+
+        a.push(Base::G);
+        assert_ne!(a, set.into_iter().next().unwrap());
+        assert_ne!(Kmer::new(3), Kmer::new(4));
+    }
+
+    #[test]
+    fn from_bases() {
+        let bases = random_bases(70, 0);
+        let kmer = Kmer::from_bases(&bases);
+        assert_eq!(kmer.size(), 70);
+        assert_eq!(kmer.bases().collect::<Vec<_>>(), bases);
+
+        let mut pushed = Kmer::new(70);
+        for base in &bases {
+            pushed.push(*base);
+        }
+        assert_eq!(kmer, pushed);
+        assert_eq!(bases.iter().copied().collect::<Kmer>(), kmer);
+    }
+
+    #[test]
+    fn display_and_parse() {
+        let s = "ACGT".repeat(20);
+        let kmer: Kmer = s.parse().unwrap();
+        assert_eq!(kmer.size(), 80);
+        assert_eq!(kmer.to_string(), s);
+        assert_eq!(format!("{kmer:#}"), s.to_lowercase());
+
+        let err = "ACGTNACGT".parse::<Kmer>().unwrap_err();
+        assert_eq!((err.position(), err.byte()), (4, b'N'));
+    }
+
+    #[test]
+    fn into_iter() {
+        let kmer: Kmer = "GATTACA".parse().unwrap();
+        let mut bases = Vec::new();
+        for base in &kmer {
+            bases.push(base);
+        }
+        assert_eq!(bases, kmer.bases().collect::<Vec<_>>());
+
+        let owned = kmer.clone().into_bases();
+        assert_eq!(owned.len(), 7);
+        assert_eq!(owned.collect::<Vec<_>>(), bases);
+        assert_eq!(kmer.into_iter().position(|b| b == Base::T), Some(2));
+    }
+
+    #[test]
+    fn small_conversion() {
+        let mut small = crate::small::Kmer::<32>::new();
+        for base in random_bases(40, 0) {
+            small.push(base);
+        }
+        let kmer = Kmer::from(small);
+        assert_eq!(kmer.size(), 32);
+        assert_eq!(kmer.to_string(), small.to_string());
+        assert_eq!(
+            kmer.to_small::<32>().unwrap().as_masked(),
+            small.as_masked()
+        );
+        assert!(kmer.to_small::<31>().is_none());
+
+        let small = crate::small::Kmer::<1>::from_bases([Base::T]);
+        assert_eq!(Kmer::from(small).to_string(), "T");
+    }
+
+    #[test]
+    fn subkmer() {
+        let s = "GATTACACCGGTATGCATGCAAACGTTT".repeat(3);
+        let mut kmer = Kmer::new(s.len());
+        // push extra bases so that the storage is rotated
+        for c in s[17..].bytes().chain(s.bytes()) {
+            kmer.push(Base::from_ascii(c).unwrap());
+        }
+        assert_eq!(kmer.to_string(), s);
+
+        for (from, to) in [(0, 84), (0, 1), (3, 40), (30, 84), (50, 70), (84, 84)] {
+            let sub = kmer.subkmer(from..to);
+            assert_eq!(sub.to_string(), s[from..to], "{from}..{to}");
+            assert_eq!(sub.size(), to - from);
+        }
+        assert_eq!(kmer.subkmer(..=4).to_string(), "GATTA");
+    }
+
+    #[test]
+    #[should_panic]
+    fn subkmer_out_of_bounds() {
+        Kmer::new(5).subkmer(2..6);
+    }
+
+    #[test]
+    fn packed_bytes() {
+        let s = "ACGTTGCAAACCGGTTA";
+        for k in 1..=s.len() {
+            let mut kmer = Kmer::new(k);
+            for c in s.bytes() {
+                kmer.push(Base::from_ascii(c).unwrap());
+            }
+            let bytes = kmer.to_packed_bytes();
+            assert_eq!(bytes.len(), k.div_ceil(4));
+            assert_eq!(Kmer::from_packed(k, &bytes), Ok(kmer));
+        }
+
+        let bytes = [0x1B, 0xAA, 0xF0, 0x0F];
+        assert_eq!(Kmer::from_packed(16, &bytes), Ok(Kmer::from_bytes(&bytes)));
+        assert_eq!(Kmer::from_packed(15, &bytes), Err(FromPackedError::Padding));
+        assert_eq!(
+            Kmer::from_packed(17, &bytes),
+            Err(FromPackedError::Length {
+                expected: 5,
+                actual: 4
+            })
+        );
+        assert_eq!(Kmer::from_packed(0, &[]), Ok(Kmer::new(0)));
+        assert_eq!(Kmer::from_bytes(&bytes).to_packed_bytes(), bytes);
+    }
+
+    #[test]
+    fn normalize() {
+        let mut kmer: Kmer = "ACGT".repeat(20).parse().unwrap();
+        assert!(kmer.is_normalized());
+        for _ in 0..7 {
+            kmer.push(Base::G);
+        }
+        assert!(!kmer.is_normalized());
+
+        let before = kmer.to_string();
+        kmer.normalize();
+        assert!(kmer.is_normalized());
+        assert_eq!(kmer.to_string(), before);
+        assert_eq!(kmer, before.parse().unwrap());
+        assert_eq!(kmer.as_raw_slice().unwrap()[0] & 0x3FFF, 0x3FFF);
+    }
+
+    #[test]
+    fn bases_across_words() {
+        let s = random_ascii(150, 0);
+        for k in [1, 2, 31, 32, 33, 64, 65, 100, 150] {
+            let mut kmer = Kmer::new(k);
+            for c in s.bytes() {
+                kmer.push(Base::from_ascii(c).unwrap());
+            }
+            let expected: Vec<Base> = s[s.len() - k..]
+                .bytes()
+                .map(|c| Base::from_ascii(c).unwrap())
+                .collect();
+            assert_eq!(kmer.bases().collect::<Vec<_>>(), expected, "k = {k}");
+            assert_eq!(kmer.into_bases().collect::<Vec<_>>(), expected, "k = {k}");
+        }
+    }
+
+    #[test]
+    fn ord() {
+        use alloc::collections::BTreeSet;
+
+        let mut rotated = Kmer::new(3);
+        for c in "TTGAC".bytes() {
+            rotated.push(Base::from_ascii(c).unwrap());
+        }
+        let set: BTreeSet<Kmer> = ["GAT", "CAT", "CA", "ATC", "TGG", "CATG"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .chain([rotated])
+            .collect();
+        let sorted: Vec<String> = set.iter().map(|kmer| kmer.to_string()).collect();
+        assert_eq!(sorted, ["CA", "CAT", "CATG", "ATC", "TGG", "GAC", "GAT"]);
+    }
+
+    #[test]
+    fn hamming_distance() {
+        let s = random_ascii(150, 0);
+        for k in [1, 31, 32, 33, 100, 150] {
+            let mut a = Kmer::new(k);
+            for c in s.bytes() {
+                a.push(Base::from_ascii(c).unwrap());
+            }
+            let mut b: Kmer = s[s.len() - k..].parse().unwrap();
+            assert_eq!(a.hamming_distance(&b), 0);
+
+            let mut expected = 0;
+            for i in (0..k).step_by(3) {
+                let base = s.as_bytes()[s.len() - k + i];
+                let other = if base == b'G' { Base::C } else { Base::G };
+                b = b
+                    .subkmer(..i)
+                    .into_iter()
+                    .chain([other])
+                    .chain(b.subkmer(i + 1..))
+                    .collect();
+                expected += 1;
+            }
+            assert_eq!(a.hamming_distance(&b), expected, "k = {k}");
+            assert_eq!(b.hamming_distance(&a), expected, "k = {k}");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn hamming_distance_size_mismatch() {
+        Kmer::new(3).hamming_distance(&Kmer::new(4));
+    }
+
+    #[test]
+    fn rolling_hash() {
+        let mut kmer = Kmer::new(70);
+        assert_eq!(kmer.rolling_hash(), None);
+        kmer.push(Base::G);
+        assert_eq!(kmer.rolling_hash(), None);
+
+        let mut kmer = kmer.with_rolling_hash();
+        for base in random_bases(200, 0) {
+            kmer.push(base);
+            assert_eq!(kmer.rolling_hash(), Some(nthash::hash(kmer.bases())));
+        }
+        kmer.reverse_complement();
+        assert_eq!(kmer.rolling_hash(), Some(nthash::hash(kmer.bases())));
+        kmer.reverse();
+        assert_eq!(kmer.rolling_hash(), Some(nthash::hash(kmer.bases())));
+
+        let plain: Kmer = kmer.to_string().parse().unwrap();
+        assert_eq!(plain, kmer);
+    }
+
+    #[test]
+    fn fill() {
+        let mut kmer: Kmer = "ACGT".repeat(25).parse().unwrap();
+        kmer = kmer.with_rolling_hash();
+        kmer.push(Base::A);
+        for base in [Base::G, Base::A, Base::T, Base::C] {
+            kmer.fill(base);
+            assert!(kmer.bases().all(|b| b == base));
+            assert_eq!(kmer, Kmer::from_bases(&[base; 100]));
+            assert_eq!(kmer.rolling_hash(), Some(nthash::hash(kmer.bases())));
+        }
+
+        kmer.push(Base::T);
+        kmer.clear();
+        assert_eq!(kmer, Kmer::new(100));
+    }
+
+    #[test]
+    fn debug() {
+        let mut kmer = Kmer::new(3);
+        kmer.push(Base::G);
+        kmer.push(Base::A);
+        assert_eq!(format!("{kmer:?}"), r#"Kmer { k: 3, bases: "CGA" }"#);
+    }
+
     #[test]
     fn from_bytes() {
         let bytes = [0x1B, 0xAA, 0xF0, 0x0F, 0xCC, 0xFF, 0x00, 0x3C, 0xCF];
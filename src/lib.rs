@@ -1,22 +1,134 @@
 //! kmer types
 
-#![cfg_attr(feature = "unstable_nightly", feature(generic_const_exprs))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(
+    feature = "unstable_nightly",
+    feature(generic_const_exprs, try_trait_v2)
+)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![warn(clippy::all, missing_docs, rust_2018_idioms, unreachable_pub)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "bitvec")]
+pub mod alphabet;
 mod base;
+#[cfg(any(feature = "python", feature = "wasm"))]
+mod bindings;
+#[cfg(feature = "bitvec")]
+pub mod correct;
+#[cfg(feature = "alloc")]
+pub mod count;
+#[cfg(feature = "std")]
+pub mod graph;
+pub mod hash;
+#[cfg(feature = "bitvec")]
+pub mod index;
+#[cfg(any(feature = "needletail", feature = "noodles"))]
+pub mod interop;
+#[cfg(feature = "std")]
+pub mod io;
 mod kmer;
+#[cfg(all(feature = "bitvec", feature = "std"))]
+pub mod pipeline;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "rand")]
+pub mod random;
 #[cfg(feature = "bitvec")]
-mod sequence;
+pub mod sequence;
+#[cfg(feature = "alloc")]
+pub mod set;
+#[cfg(feature = "alloc")]
+pub mod sketch;
+pub mod translate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use base::Base;
-pub use kmer::small;
+pub use base::{Base, Iupac, ParseBaseError, Strand};
 #[cfg(feature = "bitvec")]
 pub use kmer::{growable, unbounded};
+pub use kmer::{large, order, small};
 #[cfg(feature = "bitvec")]
-pub use sequence::Sequence;
+pub use sequence::{Sequence, SequenceSlice};
 
 pub(crate) mod utils {
+    #[cfg(feature = "bitvec")]
+    use core::ops::{Bound, Range, RangeBounds};
+
+    /// Resolves `range` against a sequence of length `len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or extends past `len`.
+    #[cfg(feature = "bitvec")]
+    pub(crate) fn range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end, "range start {start} is after end {end}");
+        assert!(
+            end <= len,
+            "range end {end} is out of bounds for length {len}"
+        );
+        start..end
+    }
+
+    /// Merges two sorted slices without duplicates, yielding each value in
+    /// either of them in increasing order, along with whether it is in `a`
+    /// and whether it is in `b`.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn merge_sorted<'a>(
+        a: &'a [u64],
+        b: &'a [u64],
+    ) -> impl Iterator<Item = (u64, bool, bool)> + 'a {
+        let (mut i, mut j) = (0, 0);
+        core::iter::from_fn(move || {
+            let (value, in_a, in_b) = match (a.get(i), b.get(j)) {
+                (Some(&x), Some(&y)) if x == y => (x, true, true),
+                (Some(&x), Some(&y)) if x < y => (x, true, false),
+                (_, Some(&y)) => (y, false, true),
+                (Some(&x), None) => (x, true, false),
+                (None, None) => return None,
+            };
+            i += usize::from(in_a);
+            j += usize::from(in_b);
+            Some((value, in_a, in_b))
+        })
+    }
+
+    /// Random inputs for tests.
+    #[cfg(test)]
+    pub(crate) mod test {
+        use crate::Base;
+
+        /// Returns `len` random bases, the same for every call with the same
+        /// `seed`.
+        pub(crate) fn random_bases(len: usize, seed: u64) -> Vec<Base> {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            (0..len)
+                .map(|_| Base::try_from(rng.u8(..4)).unwrap())
+                .collect()
+        }
+
+        /// Returns `len` random bases as uppercase ASCII.
+        #[cfg(feature = "bitvec")]
+        pub(crate) fn random_ascii(len: usize, seed: u64) -> String {
+            random_bases(len, seed)
+                .into_iter()
+                .map(|base| char::from(base.to_ascii()))
+                .collect()
+        }
+    }
+
     pub(crate) mod const_eval {
         pub(crate) const fn assert_less<const L: usize, const K: usize>() {
             assert!(L < K);
@@ -29,5 +141,154 @@ pub(crate) mod utils {
         pub(crate) const fn assert_sum_leq<const L: usize, const K: usize, const M: usize>() {
             assert!(L + K <= M);
         }
+
+        pub(crate) const fn assert_fits_words<const K: usize, const WORDS: usize>() {
+            assert!(K <= WORDS * 32);
+        }
+    }
+
+    /// Measures of how repetitive a string of bases is.
+    pub(crate) mod complexity {
+        /// Returns the Shannon entropy, in bits, of the distribution of bases
+        /// with the given counts, or 0 if all counts are 0.
+        #[cfg(feature = "std")]
+        pub(crate) fn entropy(counts: [u64; 4]) -> f64 {
+            let total: u64 = counts.iter().sum();
+            counts
+                .iter()
+                .filter(|&&count| count > 0)
+                .map(|&count| {
+                    let p = count as f64 / total as f64;
+                    -p * p.log2()
+                })
+                .sum()
+        }
+
+        /// Returns the number of distinct substrings of all lengths in the
+        /// 2-bit `codes` of a string, divided by the most that a string of
+        /// its length could have, or 0 if it is empty.
+        ///
+        /// `suffixes` must be as long as `codes`, and is overwritten. The
+        /// distinct substrings are counted from the sorted suffixes, which
+        /// takes quadratic time for very repetitive strings.
+        pub(crate) fn linguistic_complexity(codes: &[u8], suffixes: &mut [usize]) -> f64 {
+            if codes.is_empty() {
+                return 0.0;
+            }
+            for (i, suffix) in suffixes.iter_mut().enumerate() {
+                *suffix = i;
+            }
+            suffixes.sort_unstable_by(|&a, &b| codes[a..].cmp(&codes[b..]));
+
+            // each suffix adds the prefixes not shared with the one before it
+            let mut distinct = 0;
+            let mut prev: &[u8] = &[];
+            for &start in suffixes.iter() {
+                let suffix = &codes[start..];
+                let shared = suffix.iter().zip(prev).take_while(|(a, b)| a == b).count();
+                distinct += suffix.len() - shared;
+                prev = suffix;
+            }
+            distinct as f64 / max_substrings(codes.len()) as f64
+        }
+
+        /// Returns the most distinct substrings of all lengths that a string
+        /// of `len` bases can have.
+        fn max_substrings(len: usize) -> usize {
+            // there are at most 4^i substrings of length i, and at most
+            // len - i + 1 of them
+            let mut max = 0;
+            let mut i = 1;
+            while let Some(words) = 4usize.checked_pow(i as u32)
+                && words < len - i + 1
+            {
+                max += words;
+                i += 1;
+            }
+            let n = len - i + 1;
+            max + n * (n + 1) / 2
+        }
+    }
+
+    /// Operations on words of packed 2-bit bases.
+    pub(crate) mod lanes {
+        #[cfg(feature = "bitvec")]
+        const BITS: usize = usize::BITS as usize;
+
+        /// The low bit of every 2-bit lane.
+        #[cfg(feature = "bitvec")]
+        pub(crate) const LOW_BITS: usize = usize::MAX / 3;
+
+        /// Counts the 2-bit lanes that differ between two words.
+        #[cfg(feature = "bitvec")]
+        pub(crate) const fn mismatches(a: usize, b: usize) -> u32 {
+            differences(a, b).count_ones()
+        }
+
+        /// Returns a word with the low bit of each 2-bit lane set if that
+        /// lane differs between two words.
+        #[cfg(feature = "bitvec")]
+        pub(crate) const fn differences(a: usize, b: usize) -> usize {
+            let diff = a ^ b;
+            (diff | diff >> 1) & LOW_BITS
+        }
+
+        /// Returns a word with the low bit of each 2-bit lane set if that
+        /// lane of `word` holds `code`.
+        #[cfg(feature = "bitvec")]
+        pub(crate) const fn matches(word: usize, code: u8) -> usize {
+            // XOR with the code in every lane zeroes exactly the matching lanes.
+            let diff = word ^ (LOW_BITS * code as usize);
+            !(diff | diff >> 1) & LOW_BITS
+        }
+
+        /// Reverses the order of the 2-bit lanes in a word.
+        pub(crate) const fn reverse(word: u64) -> u64 {
+            let word = (word >> 2) & 0x3333_3333_3333_3333 | (word & 0x3333_3333_3333_3333) << 2;
+            let word = (word >> 4) & 0x0F0F_0F0F_0F0F_0F0F | (word & 0x0F0F_0F0F_0F0F_0F0F) << 4;
+            word.swap_bytes()
+        }
+
+        /// Reverses the order of the 2-bit lanes in the first `bits` bits of
+        /// `words`.
+        ///
+        /// `words` must be exactly long enough to hold `bits` bits. Padding
+        /// bits in the last word are cleared.
+        #[cfg(feature = "bitvec")]
+        pub(crate) fn reverse_slice(words: &mut [usize], bits: usize) {
+            debug_assert_eq!(words.len(), bits.div_ceil(BITS));
+            words.reverse();
+            for word in words.iter_mut() {
+                // a narrower word ends up in the high bits
+                *word = (reverse(*word as u64) >> (u64::BITS as usize - BITS)) as usize;
+            }
+
+            // the reversed bases now end at the top of the last word
+            let pad = words.len() * BITS - bits;
+            if pad > 0 {
+                for i in 0..words.len() {
+                    let high = words.get(i + 1).map_or(0, |word| word << (BITS - pad));
+                    words[i] = words[i] >> pad | high;
+                }
+            }
+        }
+
+        /// Complements the 2-bit bases in the first `bits` bits of `words`.
+        ///
+        /// `words` must be exactly long enough to hold `bits` bits. Padding
+        /// bits in the last word are cleared.
+        #[cfg(feature = "bitvec")]
+        pub(crate) fn complement_slice(words: &mut [usize], bits: usize) {
+            debug_assert_eq!(words.len(), bits.div_ceil(BITS));
+            // complementary bases have inverted bits (`C`/`G`, `A`/`T`)
+            for word in words.iter_mut() {
+                *word = !*word;
+            }
+            if let Some(last) = words.last_mut()
+                && !bits.is_multiple_of(BITS)
+            {
+                *last &= (1 << (bits % BITS)) - 1;
+            }
+        }
     }
 }
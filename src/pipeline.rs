@@ -0,0 +1,270 @@
+//! Ready-made pipelines from sequence files to results.
+//!
+//! These functions wire together reading, handling of ambiguous bases and
+//! counting, for when the individual iterators are not needed. Records are
+//! read from FASTA, and k-mers that overlap a character other than `A`, `C`,
+//! `G` or `T` (such as `N`) are skipped. Lowercase bases are counted like
+//! uppercase ones. Files are decompressed as described in
+//! [`io`](crate::io).
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::pipeline;
+//! use helicase::small::Kmer;
+//! use helicase::Base;
+//!
+//! let fasta = b">read1\nGATTACA\n>read2\nTGTNATC\n";
+//! let counts = pipeline::count_fasta_reader::<3>(&fasta[..], true).unwrap();
+//! assert_eq!(counts.get(Kmer::from_bases([Base::G, Base::A, Base::T])), 2);
+//! assert_eq!(counts.total(), 7);
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::count::KmerCounter;
+use crate::sequence::{MaskedSequence, SequenceBuilder};
+
+/// The number of bases read before each batch of records is counted in
+/// parallel.
+#[cfg(feature = "rayon")]
+const BATCH_BASES: usize = 1 << 24;
+
+/// Counts the k-mers of every record in the FASTA file at `path`, which may
+/// be compressed.
+///
+/// If `canonical` is `true`, each k-mer is counted together with its reverse
+/// complement.
+///
+/// # Errors
+///
+/// Returns any error from opening, decompressing or reading the file.
+pub fn count_fasta<const K: usize>(
+    path: impl AsRef<Path>,
+    canonical: bool,
+) -> io::Result<KmerCounter<K>> {
+    count_fasta_reader(crate::io::open(path)?, canonical)
+}
+
+/// Counts the k-mers of every record in uncompressed FASTA read from
+/// `reader`.
+///
+/// See [`count_fasta`], and [`io::decompress`](crate::io::decompress) for
+/// compressed input.
+///
+/// # Errors
+///
+/// Returns any error from `reader`.
+pub fn count_fasta_reader<const K: usize>(
+    reader: impl Read,
+    canonical: bool,
+) -> io::Result<KmerCounter<K>> {
+    let mut counter = counter(canonical);
+    let mut records = Records::new(BufReader::new(reader));
+    while let Some(seq) = records.next_record()? {
+        count_record(&mut counter, &seq);
+    }
+    Ok(counter)
+}
+
+/// Counts the k-mers of every record in the FASTA file at `path`, which may
+/// be compressed, counting records in parallel.
+///
+/// Records are read in batches, so memory use is bounded by the size of a
+/// batch rather than of the file. Each record is counted on a single thread,
+/// so this is fastest for files with many records.
+///
+/// # Errors
+///
+/// Returns any error from opening, decompressing or reading the file.
+#[cfg(feature = "rayon")]
+pub fn par_count_fasta<const K: usize>(
+    path: impl AsRef<Path>,
+    canonical: bool,
+) -> io::Result<KmerCounter<K>> {
+    par_count_fasta_reader(crate::io::open(path)?, canonical)
+}
+
+/// Counts the k-mers of every record in uncompressed FASTA read from
+/// `reader`, counting records in parallel.
+///
+/// See [`par_count_fasta`].
+///
+/// # Errors
+///
+/// Returns any error from `reader`.
+#[cfg(feature = "rayon")]
+pub fn par_count_fasta_reader<const K: usize>(
+    reader: impl Read,
+    canonical: bool,
+) -> io::Result<KmerCounter<K>> {
+    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+    let mut counter = counter(canonical);
+    let mut records = Records::new(BufReader::new(reader));
+    loop {
+        let mut batch = Vec::new();
+        let mut bases = 0;
+        while bases < BATCH_BASES {
+            let Some(seq) = records.next_record()? else {
+                break;
+            };
+            bases += seq.len();
+            batch.push(seq);
+        }
+        if batch.is_empty() {
+            return Ok(counter);
+        }
+
+        let counts = batch
+            .par_iter()
+            .fold(
+                || self::counter(canonical),
+                |mut counter, seq| {
+                    count_record(&mut counter, seq);
+                    counter
+                },
+            )
+            .reduce(
+                || self::counter(canonical),
+                |mut a, b| {
+                    a.merge(&b);
+                    a
+                },
+            );
+        counter.merge(&counts);
+    }
+}
+
+fn counter<const K: usize>(canonical: bool) -> KmerCounter<K> {
+    if canonical {
+        KmerCounter::canonical()
+    } else {
+        KmerCounter::new()
+    }
+}
+
+fn count_record<const K: usize>(counter: &mut KmerCounter<K>, seq: &MaskedSequence) {
+    for (_, kmer) in seq.kmers::<K>() {
+        counter.insert(kmer);
+    }
+}
+
+/// Reads FASTA records one at a time.
+struct Records<R> {
+    reader: R,
+    line: Vec<u8>,
+    /// Set once the header or first line of the current record has been
+    /// read.
+    in_record: bool,
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            in_record: false,
+        }
+    }
+
+    /// Reads the next record, or returns `None` at the end of the input.
+    ///
+    /// Headers are discarded, as are comment lines starting with `;`.
+    fn next_record(&mut self) -> io::Result<Option<MaskedSequence>> {
+        let mut builder = SequenceBuilder::new();
+        loop {
+            self.line.clear();
+            if self.reader.read_until(b'\n', &mut self.line)? == 0 {
+                let in_record = std::mem::take(&mut self.in_record);
+                return Ok(in_record.then(|| builder.build_masked()));
+            }
+            match self.line.first() {
+                Some(b'>') if self.in_record => return Ok(Some(builder.build_masked())),
+                Some(b'>') => self.in_record = true,
+                Some(b';') => {}
+                _ => {
+                    builder.push_ascii(&self.line);
+                    self.in_record |= !builder.is_empty();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sequence;
+
+    const FASTA: &[u8] =
+        b"; comment\n>a\nGATTA\nCAGAT\n\n>b desc\r\nNNacgt\r\nTAN\n>empty\n>c\nGATTACA";
+
+    #[test]
+    fn records() {
+        let mut records = Records::new(FASTA);
+        let mut seqs = Vec::new();
+        while let Some(seq) = records.next_record().unwrap() {
+            seqs.push((seq.len(), seq.ambiguous_count()));
+        }
+        assert_eq!(seqs, [(10, 0), (9, 3), (0, 0), (7, 0)]);
+    }
+
+    #[test]
+    fn count() {
+        let counts = count_fasta_reader::<4>(FASTA, false).unwrap();
+        let mut expected = KmerCounter::<4>::new();
+        for s in ["GATTACAGAT", "ACGTTA", "GATTACA"] {
+            expected.count_sequence(&Sequence::<usize>::try_from(s).unwrap());
+        }
+        assert_eq!(counts.len(), expected.len());
+        for (kmer, count) in expected.iter() {
+            assert_eq!(counts.get(kmer), count);
+        }
+
+        let canonical = count_fasta_reader::<4>(FASTA, true).unwrap();
+        assert!(canonical.is_canonical());
+        assert_eq!(canonical.total(), counts.total());
+    }
+
+    #[test]
+    fn count_file() {
+        let path = std::env::temp_dir().join(format!("helicase-{}.fa", std::process::id()));
+        std::fs::write(&path, FASTA).unwrap();
+        let counts = count_fasta::<4>(&path, true);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(counts.unwrap().total(), 14);
+        assert!(count_fasta::<4>(&path, true).is_err());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn count_gzip_file() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("helicase-{}.fa.gz", std::process::id()));
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(FASTA).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let counts = count_fasta::<4>(&path, true);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(counts.unwrap().total(), 14);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_count() {
+        let fasta: Vec<u8> = (0..500)
+            .flat_map(|i| {
+                format!(">{i}\n{}\n", ["GATTACA", "CCNGT", "acgtacgt"][i % 3]).into_bytes()
+            })
+            .collect();
+        let counts = count_fasta_reader::<3>(&fasta[..], true).unwrap();
+        let par = par_count_fasta_reader::<3>(&fasta[..], true).unwrap();
+        assert_eq!(par.len(), counts.len());
+        for (kmer, count) in counts.iter() {
+            assert_eq!(par.get(kmer), count);
+        }
+    }
+}
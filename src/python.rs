@@ -0,0 +1,563 @@
+//! Python bindings with [PyO3](https://docs.rs/pyo3).
+//!
+//! The exported classes wrap the rest of the crate without const generics:
+//! the k-mer length is an argument checked at runtime, and must be between
+//! 1 and 32. K-mers are exported as their packed `u64` values, with the
+//! first base in the most significant bits, as returned by
+//! [`Kmer::as_masked`](crate::small::Kmer::as_masked).
+//!
+//! Arrays of k-mers, counts and hashes are returned as a `U64Array`, which
+//! owns its values and exposes them through the buffer protocol, so
+//! `numpy.asarray` and `memoryview` read them without copying.
+//!
+//! ```python
+//! import numpy as np
+//! from helicase import KmerCounter, MinHash, Sequence
+//!
+//! seq = Sequence("GATTACAGATTACA")
+//! kmers = np.asarray(seq.kmers(3, canonical=True))  # dtype uint64
+//!
+//! counter = KmerCounter(3, canonical=True)
+//! counter.count_sequence(seq)
+//! counter.get("GAT")  # 2
+//! kmers, counts = map(np.asarray, counter.to_arrays())
+//!
+//! sketch = MinHash(1000, 21)
+//! sketch.add_sequence(seq)
+//! sketch.jaccard(sketch)  # 1.0
+//! ```
+//!
+//! The module is named `helicase`; build it as an extension module with
+//! [maturin](https://www.maturin.rs), which enables the `python` feature
+//! through `pyproject.toml`: `maturin build --release`.
+
+use std::ffi::c_int;
+
+use pyo3::exceptions::{PyBufferError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+use crate::bindings::{self, DynCounter, InvalidK, with_k};
+use crate::{Base, sketch, small};
+
+impl From<InvalidK> for PyErr {
+    fn from(err: InvalidK) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// The Python module.
+#[pymodule]
+fn helicase(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<U64Array>()?;
+    m.add_class::<Sequence>()?;
+    m.add_class::<Kmer>()?;
+    m.add_class::<KmerCounter>()?;
+    m.add_class::<MinHash>()?;
+    m.add_class::<FracMinHash>()?;
+    Ok(())
+}
+
+/// A read-only array of `u64` values, exported through the buffer protocol.
+#[pyclass(frozen, module = "helicase", skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct U64Array {
+    values: Vec<u64>,
+    /// The shape of the exported buffer, which must outlive it.
+    shape: isize,
+}
+
+impl U64Array {
+    fn new(values: Vec<u64>) -> Self {
+        let shape = values.len() as isize;
+        Self { values, shape }
+    }
+}
+
+impl From<Vec<u64>> for U64Array {
+    fn from(values: Vec<u64>) -> Self {
+        Self::new(values)
+    }
+}
+
+#[pymethods]
+impl U64Array {
+    fn __len__(&self) -> usize {
+        self.values.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("U64Array({:?})", self.values)
+    }
+
+    /// Copies the values into a list.
+    fn tolist(&self) -> Vec<u64> {
+        self.values.clone()
+    }
+
+    /// # Safety
+    ///
+    /// `view` must be null or point to a `Py_buffer` to fill.
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("U64Array is read-only"));
+        }
+
+        let array = slf.get();
+        // SAFETY: `view` is not null, and the buffer, shape and format
+        // outlive the view, which holds a reference to `slf` in `obj`
+        unsafe {
+            (*view).buf = array.values.as_ptr().cast_mut().cast();
+            (*view).len = (array.values.len() * size_of::<u64>()) as isize;
+            (*view).readonly = 1;
+            (*view).itemsize = size_of::<u64>() as isize;
+            (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+                c"Q".as_ptr().cast_mut()
+            } else {
+                std::ptr::null_mut()
+            };
+            (*view).ndim = 1;
+            (*view).shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+                (&raw const array.shape).cast_mut()
+            } else {
+                std::ptr::null_mut()
+            };
+            (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+                &raw mut (*view).itemsize
+            } else {
+                std::ptr::null_mut()
+            };
+            (*view).suboffsets = std::ptr::null_mut();
+            (*view).internal = std::ptr::null_mut();
+            (*view).obj = slf.into_any().into_ptr();
+        }
+        Ok(())
+    }
+}
+
+/// A DNA sequence, packed two bits per base.
+#[pyclass(frozen, module = "helicase", skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    inner: crate::Sequence,
+}
+
+#[pymethods]
+impl Sequence {
+    /// Packs a sequence from ASCII, case-insensitively.
+    ///
+    /// Raises `ValueError` if the sequence contains a character other than
+    /// `A`, `C`, `G` or `T`.
+    #[new]
+    fn new(ascii: &str) -> PyResult<Self> {
+        let inner = crate::Sequence::try_from_ascii(ascii.as_bytes())
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Sequence('{}')", self.inner)
+    }
+
+    /// Returns the fraction of bases that are `G` or `C`.
+    fn gc_content(&self) -> f64 {
+        self.inner.gc_content()
+    }
+
+    /// Returns the reverse complement of the sequence.
+    fn reverse_complement(&self) -> Self {
+        Self {
+            inner: self.inner.to_reverse_complement(),
+        }
+    }
+
+    /// Returns the packed values of the `k`-mers of the sequence, in order.
+    ///
+    /// If `canonical` is `True`, each k-mer is replaced by the smaller of it
+    /// and its reverse complement.
+    #[pyo3(signature = (k, canonical = false))]
+    fn kmers(&self, k: usize, canonical: bool) -> PyResult<U64Array> {
+        let kmers: Vec<u64> = with_k!(k, K => if canonical {
+            self.inner.canonical_kmers::<K>().map(|kmer| kmer.as_masked()).collect()
+        } else {
+            self.inner.kmers::<K>().map(|kmer| kmer.as_masked()).collect()
+        })?;
+        Ok(kmers.into())
+    }
+}
+
+/// A k-mer of up to 32 bases.
+#[pyclass(frozen, eq, hash, module = "helicase", skip_from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Kmer {
+    inner: small::DynKmer,
+}
+
+#[pymethods]
+impl Kmer {
+    /// Packs a k-mer from ASCII, case-insensitively.
+    ///
+    /// Raises `ValueError` if the k-mer is not between 1 and 32 bases long,
+    /// or contains a character other than `A`, `C`, `G` or `T`.
+    #[new]
+    fn new(ascii: &str) -> PyResult<Self> {
+        bindings::check_k(ascii.len())?;
+        let bases: Option<Vec<Base>> = ascii.bytes().map(Base::from_ascii).collect();
+        let bases = bases
+            .ok_or_else(|| PyValueError::new_err(format!("{ascii:?} is not a valid k-mer")))?;
+        Ok(Self {
+            inner: small::DynKmer::from_bases(&bases),
+        })
+    }
+
+    /// Unpacks a `k`-mer from its packed value.
+    ///
+    /// Bits above the `k` bases are ignored.
+    #[staticmethod]
+    fn from_int(value: u64, k: usize) -> PyResult<Self> {
+        Ok(Self {
+            inner: small::DynKmer::from_value(value, bindings::check_k(k)?),
+        })
+    }
+
+    /// The number of bases in the k-mer.
+    #[getter]
+    fn k(&self) -> usize {
+        self.inner.k()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.k()
+    }
+
+    fn __int__(&self) -> u64 {
+        self.inner.as_masked()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Kmer('{}')", self.inner)
+    }
+
+    /// Returns the reverse complement of the k-mer.
+    fn reverse_complement(&self) -> Self {
+        Self {
+            inner: self.inner.reverse_complement(),
+        }
+    }
+
+    /// Returns the smaller of the k-mer and its reverse complement.
+    fn canonical(&self) -> Self {
+        Self {
+            inner: self.inner.canonical(),
+        }
+    }
+
+    /// Returns `True` if the k-mer is not greater than its reverse
+    /// complement.
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+}
+
+/// Counts of the k-mers of one or more sequences.
+#[pyclass(module = "helicase")]
+pub struct KmerCounter {
+    inner: Box<dyn DynCounter>,
+    k: usize,
+}
+
+#[pymethods]
+impl KmerCounter {
+    /// Creates an empty counter of `k`-mers.
+    ///
+    /// If `canonical` is `True`, each k-mer is counted together with its
+    /// reverse complement. Raises `ValueError` if `k` is not between 1 and
+    /// 32.
+    #[new]
+    #[pyo3(signature = (k, canonical = false))]
+    fn new(k: usize, canonical: bool) -> PyResult<Self> {
+        let inner = bindings::new_counter(k, canonical)?;
+        Ok(Self { inner, k })
+    }
+
+    /// The length of the counted k-mers.
+    #[getter]
+    fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Counts every k-mer in a sequence.
+    fn count_sequence(&mut self, seq: &Sequence) {
+        self.inner.count_sequence(&seq.inner);
+    }
+
+    /// Returns the number of times a k-mer, given as ASCII, was counted.
+    ///
+    /// Raises `ValueError` if `kmer` is not `k` bases long.
+    fn get(&self, kmer: &str) -> PyResult<u64> {
+        self.inner
+            .get(kmer.as_bytes())
+            .ok_or_else(|| PyValueError::new_err(format!("{kmer:?} is not a {}-mer", self.k)))
+    }
+
+    /// The number of distinct k-mers counted.
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns the total number of occurrences counted.
+    fn total(&self) -> u64 {
+        self.inner.total()
+    }
+
+    /// Returns the `n` most abundant k-mers and their counts, with ties
+    /// sorted by k-mer.
+    fn most_common(&self, n: usize) -> Vec<(Kmer, u64)> {
+        let counts = self.inner.sorted_by_count();
+        counts
+            .into_iter()
+            .take(n)
+            .map(|(value, count)| {
+                let inner = small::DynKmer::from_value(value, self.k);
+                (Kmer { inner }, count)
+            })
+            .collect()
+    }
+
+    /// Returns the packed values of the counted k-mers, in increasing
+    /// order, and their counts.
+    fn to_arrays(&self) -> (U64Array, U64Array) {
+        let (kmers, counts) = self.inner.sorted_by_kmer().into_iter().unzip();
+        (U64Array::new(kmers), U64Array::new(counts))
+    }
+}
+
+/// A bottom-s MinHash sketch of the canonical k-mers of sequences.
+#[pyclass(module = "helicase", skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct MinHash {
+    inner: sketch::MinHash,
+    k: usize,
+}
+
+#[pymethods]
+impl MinHash {
+    /// Creates an empty sketch of `k`-mers that keeps the `size` smallest
+    /// hashes.
+    ///
+    /// Raises `ValueError` if `size` is zero or `k` is not between 1 and 32.
+    #[new]
+    fn new(size: usize, k: usize) -> PyResult<Self> {
+        if size == 0 {
+            return Err(PyValueError::new_err("sketch size must be greater than 0"));
+        }
+        Ok(with_k!(k, K => Self {
+            inner: sketch::MinHash::new(size),
+            k: K,
+        })?)
+    }
+
+    /// The length of the sketched k-mers.
+    #[getter]
+    fn k(&self) -> usize {
+        self.k
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Adds the canonical k-mers of a sequence to the sketch.
+    fn add_sequence(&mut self, seq: &Sequence) {
+        // `k` was checked when the sketch was created
+        let _ = with_k!(self.k, K => self.inner.add_sequence::<K, _>(&seq.inner));
+    }
+
+    /// Returns the hashes in the sketch, in increasing order.
+    fn hashes(&self) -> U64Array {
+        U64Array::new(self.inner.hashes().to_vec())
+    }
+
+    /// Estimates the Jaccard index of the sets of k-mers of two sketches.
+    fn jaccard(&self, other: &Self) -> f64 {
+        self.inner.jaccard(&other.inner)
+    }
+
+    /// Estimates the fraction of the k-mers of this sketch that are in
+    /// `other`.
+    fn containment(&self, other: &Self) -> f64 {
+        self.inner.containment(&other.inner)
+    }
+
+    /// Estimates the Mash distance between the sequences of two sketches.
+    fn mash_distance(&self, other: &Self) -> f64 {
+        self.inner.mash_distance(&other.inner, self.k)
+    }
+}
+
+/// A FracMinHash sketch of the canonical k-mers of sequences, which keeps
+/// the hashes below a fraction of the hash space.
+#[pyclass(module = "helicase", skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct FracMinHash {
+    inner: sketch::FracMinHash,
+    k: usize,
+}
+
+#[pymethods]
+impl FracMinHash {
+    /// Creates an empty sketch of `k`-mers that keeps about one in `scale`
+    /// hashes.
+    ///
+    /// Raises `ValueError` if `scale` is zero or `k` is not between 1 and
+    /// 32.
+    #[new]
+    fn new(scale: u64, k: usize) -> PyResult<Self> {
+        if scale == 0 {
+            return Err(PyValueError::new_err("scale must be greater than 0"));
+        }
+        Ok(with_k!(k, K => Self {
+            inner: sketch::FracMinHash::new(scale),
+            k: K,
+        })?)
+    }
+
+    /// The length of the sketched k-mers.
+    #[getter]
+    fn k(&self) -> usize {
+        self.k
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Adds the canonical k-mers of a sequence to the sketch.
+    fn add_sequence(&mut self, seq: &Sequence) {
+        // `k` was checked when the sketch was created
+        let _ = with_k!(self.k, K => self.inner.add_sequence::<K, _>(&seq.inner));
+    }
+
+    /// Returns the hashes in the sketch, in increasing order.
+    fn hashes(&self) -> U64Array {
+        U64Array::new(self.inner.hashes().collect())
+    }
+
+    /// Estimates the Jaccard index of the sets of k-mers of two sketches.
+    fn jaccard(&self, other: &Self) -> f64 {
+        self.inner.jaccard(&other.inner)
+    }
+
+    /// Estimates the fraction of the k-mers of this sketch that are in
+    /// `other`.
+    fn containment(&self, other: &Self) -> f64 {
+        self.inner.containment(&other.inner)
+    }
+
+    /// Estimates the average nucleotide identity of the sequences of this
+    /// sketch to those of `other`.
+    fn containment_ani(&self, other: &Self) -> f64 {
+        self.inner.containment_ani(&other.inner, self.k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence() {
+        let seq = Sequence::new("gattaca").unwrap();
+        assert_eq!(seq.__len__(), 7);
+        assert_eq!(seq.__str__(), "GATTACA");
+        assert_eq!(seq.__repr__(), "Sequence('GATTACA')");
+        assert_eq!(seq.reverse_complement().__str__(), "TGTAATC");
+
+        let kmers = seq.kmers(5, false).unwrap();
+        let expected: Vec<u64> = seq
+            .inner
+            .kmers::<5>()
+            .map(|kmer| kmer.as_masked())
+            .collect();
+        assert_eq!(kmers.values, expected);
+        let canonical = seq.kmers(5, true).unwrap().values;
+        let mut rc = seq.reverse_complement().kmers(5, true).unwrap().values;
+        rc.reverse();
+        assert_eq!(canonical, rc);
+    }
+
+    #[test]
+    fn kmer() {
+        let kmer = Kmer::new("gatt").unwrap();
+        assert_eq!(kmer.k(), 4);
+        assert_eq!(kmer.__str__(), "GATT");
+        assert_eq!(kmer.reverse_complement().__repr__(), "Kmer('AATC')");
+        assert_eq!(kmer.canonical(), kmer.reverse_complement());
+        assert!(!kmer.is_canonical());
+        assert_eq!(Kmer::from_int(kmer.__int__() | 1 << 63, 4).unwrap(), kmer);
+    }
+
+    #[test]
+    fn counter() {
+        let mut counter = KmerCounter::new(3, true).unwrap();
+        counter.count_sequence(&Sequence::new("GATTACAGATTACA").unwrap());
+        assert_eq!(counter.k(), 3);
+        assert_eq!(counter.total(), 12);
+        assert_eq!(counter.get("GAT").unwrap(), 2);
+        assert_eq!(counter.get("atc").unwrap(), 2);
+        assert_eq!(counter.most_common(1).len(), 1);
+
+        let (kmers, counts) = counter.to_arrays();
+        assert_eq!(kmers.__len__(), counter.__len__());
+        assert!(kmers.values.is_sorted());
+        assert_eq!(counts.values.iter().sum::<u64>(), 12);
+    }
+
+    #[test]
+    fn sketches() {
+        let a = Sequence::new(&"GATTACA".repeat(20)).unwrap();
+        let mut forward = MinHash::new(10, 5).unwrap();
+        forward.add_sequence(&a);
+        let mut reverse = MinHash::new(10, 5).unwrap();
+        reverse.add_sequence(&a.reverse_complement());
+        assert_eq!(forward.hashes().values, reverse.hashes().values);
+        assert_eq!(forward.jaccard(&reverse), 1.0);
+        assert_eq!(forward.mash_distance(&reverse), 0.0);
+
+        let mut frac = FracMinHash::new(1, 5).unwrap();
+        frac.add_sequence(&a);
+        assert_eq!(frac.__len__(), frac.hashes().__len__());
+        assert_eq!(frac.containment(&frac.clone()), 1.0);
+    }
+
+    #[test]
+    fn buffer() {
+        Python::initialize();
+        Python::attach(|py| {
+            let array = Bound::new(py, U64Array::new(vec![1, 2, u64::MAX])).unwrap();
+            let view = pyo3::types::PyMemoryView::from(&array).unwrap();
+            assert_eq!(view.getattr("format").unwrap().to_string(), "Q");
+            let values: Vec<u64> = view.call_method0("tolist").unwrap().extract().unwrap();
+            assert_eq!(values, [1, 2, u64::MAX]);
+        });
+    }
+}
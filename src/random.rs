@@ -0,0 +1,168 @@
+//! Random bases, k-mers and sequences.
+//!
+//! [`StandardUniform`] samples every base with equal probability, while
+//! [`GcBias`] samples `C` and `G` with a given combined probability.
+//!
+//! # Examples
+//!
+//! ```
+//! use helicase::random::GcBias;
+//! use helicase::Base;
+//! use rand::distr::Distribution;
+//!
+//! let mut rng = rand::rng();
+//! let bias = GcBias::new(0.7).unwrap();
+//! let bases: Vec<Base> = bias.sample_iter(&mut rng).take(1000).collect();
+//! let gc = bases.iter().filter(|&&b| b == Base::C || b == Base::G).count();
+//! assert!(gc > 500);
+//! ```
+
+use rand::Rng;
+use rand::distr::{Distribution, StandardUniform};
+
+use crate::Base;
+use crate::small::Kmer;
+
+impl Distribution<Base> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Base {
+        // SAFETY: the value is always in the range `0..4`.
+        unsafe { Base::from_u8_unchecked(rng.random::<u8>() & 0b11) }
+    }
+}
+
+/// A distribution over bases with a fixed GC content.
+///
+/// `C` and `G` are equally likely, as are `A` and `T`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GcBias {
+    gc: f64,
+}
+
+impl GcBias {
+    /// Creates a distribution that samples `C` or `G` with probability
+    /// `gc`.
+    ///
+    /// Returns `None` if `gc` is not between 0 and 1.
+    pub fn new(gc: f64) -> Option<Self> {
+        (0.0..=1.0).contains(&gc).then_some(Self { gc })
+    }
+
+    /// Returns the probability of sampling `C` or `G`.
+    pub fn gc(&self) -> f64 {
+        self.gc
+    }
+}
+
+impl Distribution<Base> for GcBias {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Base {
+        match (rng.random_bool(self.gc), rng.random::<bool>()) {
+            (true, false) => Base::C,
+            (true, true) => Base::G,
+            (false, false) => Base::A,
+            (false, true) => Base::T,
+        }
+    }
+}
+
+impl<const K: usize> Distribution<Kmer<K>> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Kmer<K> {
+        Kmer::from(rng.random::<u64>())
+    }
+}
+
+impl<const K: usize> Kmer<K> {
+    /// Creates a k-mer of uniformly random bases.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        rng.random()
+    }
+}
+
+#[cfg(feature = "bitvec")]
+impl<B: bitvec::store::BitStore> crate::Sequence<B> {
+    /// Creates a sequence of `len` uniformly random bases.
+    ///
+    /// Bases are generated a byte at a time. For a biased sequence, collect
+    /// from a distribution such as [`GcBias`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::random(100, &mut rand::rng());
+    /// assert_eq!(seq.len(), 100);
+    /// ```
+    pub fn random<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Self {
+        let mut bytes = alloc::vec![0; len.div_ceil(4)];
+        rng.fill_bytes(&mut bytes);
+        if let Some(last) = bytes.last_mut()
+            && !len.is_multiple_of(4)
+        {
+            *last &= !(0xFF >> (len % 4 * 2));
+        }
+        Self::from_packed_bytes(len, &bytes).expect("padding bits are cleared")
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn uniform_bases() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = [0; 4];
+        for base in Distribution::<Base>::sample_iter(StandardUniform, &mut rng).take(4000) {
+            counts[base as usize] += 1;
+        }
+        assert!(counts.iter().all(|&count| (800..1200).contains(&count)));
+    }
+
+    #[test]
+    fn gc_bias() {
+        assert!(GcBias::new(1.5).is_none());
+        let mut rng = StdRng::seed_from_u64(7);
+        let bases: Vec<Base> = GcBias::new(0.0)
+            .unwrap()
+            .sample_iter(&mut rng)
+            .take(100)
+            .collect();
+        assert!(bases.iter().all(|&b| b == Base::A || b == Base::T));
+
+        let kmer: Kmer<32> = Kmer::from_bases(
+            GcBias::new(0.25)
+                .unwrap()
+                .sample_iter(&mut rng)
+                .take(32)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        );
+        assert!(kmer.gc_count() < 20);
+    }
+
+    #[test]
+    fn random_kmer() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let a = Kmer::<21>::random(&mut rng);
+        let b = Kmer::<21>::random(&mut rng);
+        assert_ne!(a.as_masked(), b.as_masked());
+        assert!(a.as_masked() < 1 << 42);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn random_sequence() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for len in [0, 1, 3, 4, 5, 100] {
+            let seq = crate::Sequence::<u8>::random(len, &mut rng);
+            assert_eq!(seq.len(), len);
+        }
+        let seq = crate::Sequence::<u64>::random(10_000, &mut rng);
+        let composition = seq.composition();
+        assert!(composition.iter().all(|&count| count > 2000));
+    }
+}
@@ -1,65 +1,1458 @@
-use std::iter::FusedIterator;
+//! Packed sequences of bases and iterators over their k-mers.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::{RangeBounds, RangeInclusive};
+#[cfg(feature = "std")]
+use std::io;
 
 use bitvec::field::BitField as _;
 use bitvec::order::Lsb0;
-use bitvec::slice::ChunksExact;
+use bitvec::slice::{BitSlice, ChunksExact};
 use bitvec::store::BitStore;
 use bitvec::vec::BitVec;
+use bitvec::view::BitView;
+
+use crate::alphabet::{Alphabet, Dna, ParseSymbolError};
+use crate::base::{Base, ParseBaseError, Strand};
+use crate::hash::nthash;
+use crate::sketch::MinimizerSketch;
+use crate::unbounded::FromPackedError;
+use crate::utils::{self, lanes};
 
-use crate::Base;
+#[cfg(feature = "rkyv")]
+mod archive;
+mod builder;
+mod complexity;
+mod distance;
+mod dust;
+mod encode;
+mod hpc;
+mod iupac;
+mod masked;
+mod nthash2;
+#[cfg(feature = "rayon")]
+mod par;
+mod search;
+mod sketch;
+mod slice;
+mod spaced;
+mod stats;
+mod step;
+mod strobe;
 
-pub struct Sequence<B: BitStore> {
+#[cfg(feature = "rkyv")]
+pub use archive::ArchivedSequence;
+pub use builder::SequenceBuilder;
+#[cfg(feature = "std")]
+pub use complexity::EntropyWindows;
+pub use distance::Mismatches;
+pub use hpc::HpcKmerIter;
+pub use iupac::{AmbiguityPolicy, Expansions, IupacIter, IupacSequence};
+pub use masked::{MaskedKmerIter, MaskedMinimizerIter, MaskedSequence, Runs};
+pub use nthash2::{MultiHashIter, NtHash2Iter, SpacedHashIter};
+#[cfg(feature = "rayon")]
+pub use par::ParKmerIter;
+pub use search::{KmerPositions, MatchIndices};
+pub use sketch::{MinimizerIter, SyncmerIter, SyncmerKind};
+pub use slice::{SequenceSlice, Windows};
+pub use spaced::SpacedSeedIter;
+pub use step::StepKmerIter;
+pub use strobe::{StrobeKind, Strobemer, StrobemerIter};
+
+/// A sequence of bases, packed two bits per base.
+///
+/// Bases are stored in order in a bit vector backed by words of type `B`,
+/// with each base occupying two bits. `B` defaults to `usize`, which is the
+/// fastest choice for most uses, but the default only applies where a type
+/// is written, so write `<Sequence>::new()` or annotate the binding.
+///
+/// Sequences of symbols from another [`Alphabet`] than [`Dna`], such as
+/// [`Protein`](crate::alphabet::Protein), are packed in
+/// [`A::BITS`](Alphabet::BITS) bits per symbol. Only the methods that do not
+/// depend on the alphabet, such as [`push`](Self::push) and
+/// [`get`](Self::get), are available for them.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::{Base, Sequence};
+///
+/// let mut seq = <Sequence>::new();
+/// seq.push(Base::G);
+///
+/// let other: Sequence = "GATTACA".try_into().unwrap();
+/// assert_eq!(other.get(0), seq.get(0));
+/// ```
+pub struct Sequence<B: BitStore = usize, A: Alphabet = Dna> {
     store: BitVec<B, Lsb0>,
+    alphabet: PhantomData<A>,
 }
 
-impl<B: BitStore> Sequence<B> {
+impl<B: BitStore, A: Alphabet> fmt::Debug for Sequence<B, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ascii = self.to_ascii();
+        f.debug_struct("Sequence")
+            .field("len", &self.len())
+            .field("bases", &ascii.escape_ascii().to_string())
+            .finish()
+    }
+}
+
+impl<B: BitStore, A: Alphabet> Clone for Sequence<B, A> {
+    fn clone(&self) -> Self {
+        Self::from_store(self.store.clone())
+    }
+}
+
+impl<B: BitStore, A: Alphabet> Default for Sequence<B, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: BitStore, C: BitStore, A: Alphabet> PartialEq<Sequence<C, A>> for Sequence<B, A> {
+    /// Compares the symbols in two sequences, regardless of their storage
+    /// types.
+    fn eq(&self, other: &Sequence<C, A>) -> bool {
+        self.store == other.store
+    }
+}
+
+impl<B: BitStore, C: BitStore> PartialEq<SequenceSlice<'_, C>> for Sequence<B> {
+    fn eq(&self, other: &SequenceSlice<'_, C>) -> bool {
+        self.store == other.bits
+    }
+}
+
+impl<B: BitStore, A: Alphabet> Eq for Sequence<B, A> {}
+
+impl<B: BitStore> Hash for Sequence<B> {
+    /// Hashes the length and then the stored words, with the bits past the
+    /// end of the last word cleared.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for word in self.store.domain() {
+            word.hash(state);
+        }
+    }
+}
+
+impl<B: BitStore> fmt::Display for Sequence<B> {
+    /// Formats the sequence as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_ascii_chunks(&self.store, f.alternate(), |chunk| {
+            // SAFETY: decoded bases are always ASCII.
+            f.write_str(unsafe { core::str::from_utf8_unchecked(chunk) })
+        })
+    }
+}
+
+impl<'a, B: BitStore> IntoIterator for &'a Sequence<B> {
+    type Item = Base;
+    type IntoIter = Bases<'a, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bases()
+    }
+}
+
+impl<B: BitStore> TryFrom<&str> for Sequence<B> {
+    type Error = ParseBaseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_from_ascii(value.as_bytes())
+    }
+}
+
+impl<B: BitStore, A: Alphabet> Extend<A::Symbol> for Sequence<B, A> {
+    fn extend<I: IntoIterator<Item = A::Symbol>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for symbol in iter {
+            self.push(symbol);
+        }
+    }
+}
+
+impl<B: BitStore, A: Alphabet> FromIterator<A::Symbol> for Sequence<B, A> {
+    fn from_iter<I: IntoIterator<Item = A::Symbol>>(iter: I) -> Self {
+        let mut seq = Self::new();
+        seq.extend(iter);
+        seq
+    }
+}
+
+impl<B: BitStore, A: Alphabet> Sequence<B, A> {
+    /// Creates a new, empty sequence.
     pub fn new() -> Self {
+        Self::from_store(BitVec::new())
+    }
+
+    /// Fails to compile if the symbols of `A` do not fit in a byte.
+    const VALID_BITS: () = assert!(
+        A::BITS > 0 && A::BITS <= 8,
+        "symbols must be between 1 and 8 bits"
+    );
+
+    fn from_store(store: BitVec<B, Lsb0>) -> Self {
+        let () = Self::VALID_BITS;
         Self {
-            store: BitVec::new(),
+            store,
+            alphabet: PhantomData,
         }
     }
 
-    pub fn push(&mut self, base: Base) {
-        let bits = match base {
-            Base::C => (false, false),
-            Base::A => (false, true),
-            Base::T => (true, false),
-            Base::G => (true, true),
-        };
-        self.store.push(bits.1);
-        self.store.push(bits.0);
+    /// Creates a new, empty sequence with space for at least `bases` bases.
+    pub fn with_capacity(bases: usize) -> Self {
+        Self::from_store(BitVec::with_capacity(bases * A::BITS))
+    }
+
+    /// Returns the number of bases the sequence can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.store.capacity() / A::BITS
+    }
+
+    /// Reserves space for at least `additional` more bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let mut seq = Sequence::<usize>::with_capacity(150);
+    /// assert!(seq.capacity() >= 150);
+    /// seq.extend_from_sequence(&Sequence::<u8>::try_from("GATTACA").unwrap());
+    /// seq.reserve(300);
+    /// assert!(seq.capacity() >= 307);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.store.reserve(additional * A::BITS);
+    }
+
+    /// Shrinks the capacity of the sequence as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.store.shrink_to_fit();
+    }
+
+    /// Returns the memory elements holding the packed bases, without
+    /// copying them.
+    ///
+    /// Base `i` is held in bits `2 * i` and `2 * i + 1`, counting from the
+    /// least significant bit of the first element, or in the
+    /// [`A::BITS`](Alphabet::BITS) bits from `A::BITS * i` for other
+    /// alphabets. The bits after the last base are unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+    /// assert_eq!(seq.as_raw_slice(), [0b10_10_01_11, 0b01_00_01]);
+    /// ```
+    pub fn as_raw_slice(&self) -> &[B] {
+        self.store.as_raw_slice()
+    }
+
+    /// Appends a base to the end of the sequence.
+    pub fn push(&mut self, base: A::Symbol) {
+        let code = A::encode(base);
+        self.store
+            .extend_from_bitslice(&code.view_bits::<Lsb0>()[..A::BITS]);
+    }
+
+    /// Removes the last base from the sequence and returns it, or `None` if
+    /// the sequence is empty.
+    pub fn pop(&mut self) -> Option<A::Symbol> {
+        let base = self.get(self.len().checked_sub(1)?);
+        self.store.truncate(self.store.len() - A::BITS);
+        base
+    }
+
+    /// Shortens the sequence to its first `len` bases.
+    ///
+    /// Has no effect if the sequence is already shorter than `len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let mut seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// assert_eq!(seq.pop(), Some(Base::A));
+    /// seq.truncate(4);
+    /// assert_eq!(seq.to_string(), "GATT");
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        self.store.truncate(len * A::BITS);
+    }
+
+    /// Returns the number of bases in the sequence.
+    pub fn len(&self) -> usize {
+        self.store.len() / A::BITS
+    }
+
+    /// Returns `true` if the sequence contains no bases.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Returns the base at index `i`, or `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let mut seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// assert_eq!(seq.get(1), Some(Base::A));
+    /// assert_eq!(seq.get(7), None);
+    ///
+    /// seq.set(1, Base::C);
+    /// assert_eq!(seq.get(1), Some(Base::C));
+    /// ```
+    pub fn get(&self, i: usize) -> Option<A::Symbol> {
+        let chunk = self.store.get(i * A::BITS..(i + 1) * A::BITS)?;
+        Some(A::decode(chunk.load::<u8>()))
+    }
+
+    /// Replaces the base at index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set(&mut self, i: usize, base: A::Symbol) {
+        let len = self.len();
+        self.store
+            .get_mut(i * A::BITS..(i + 1) * A::BITS)
+            .unwrap_or_else(|| panic!("index {i} is out of bounds for length {len}"))
+            .store(A::encode(base));
+    }
+
+    /// Returns an iterator over the symbols in the sequence.
+    ///
+    /// For DNA, [`bases`](Self::bases) is faster.
+    pub fn symbols(&self) -> Symbols<'_, B, A> {
+        Symbols::new(&self.store)
+    }
+
+    /// Creates a sequence from the ASCII characters of symbols.
+    ///
+    /// For DNA, [`try_from_ascii`](Self::try_from_ascii) is faster.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the byte offset and value of the first character
+    /// that is not a symbol of the alphabet.
+    pub fn parse_ascii(ascii: &[u8]) -> Result<Self, ParseSymbolError> {
+        let mut seq = Self::with_capacity(ascii.len());
+        for (position, &byte) in ascii.iter().enumerate() {
+            let symbol = A::from_ascii(byte).ok_or(ParseSymbolError::new(position, byte))?;
+            seq.push(symbol);
+        }
+        Ok(seq)
+    }
+
+    /// Returns the symbols in the sequence as ASCII characters.
+    ///
+    /// For DNA, this is the same as `to_string().into_bytes()`.
+    pub fn to_ascii(&self) -> Vec<u8> {
+        self.symbols().map(A::to_ascii).collect()
+    }
+}
+
+impl<B: BitStore> Sequence<B> {
+    /// Creates a sequence from ASCII bases, in either case.
+    ///
+    /// Use [`try_from_ascii_soft_masked`](Self::try_from_ascii_soft_masked)
+    /// to keep track of the lowercase bases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the byte offset and value of the first character
+    /// that is not a valid base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from_ascii(b"GATTACA").unwrap();
+    /// assert_eq!(seq.to_lowercase_string(), "gattaca");
+    ///
+    /// let err = Sequence::<usize>::try_from_ascii(b"GATTNCA").err().unwrap();
+    /// assert_eq!((err.position(), err.byte()), (4, b'N'));
+    /// ```
+    pub fn try_from_ascii(ascii: &[u8]) -> Result<Self, ParseBaseError> {
+        let mut seq = Self::new();
+        seq.extend_from_ascii(ascii)?;
+        Ok(seq)
+    }
+
+    /// Creates a sequence of `len` bases from bytes in the layout produced by
+    /// [`to_packed_bytes`](Self::to_packed_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not exactly `len.div_ceil(4)` bytes
+    /// long, or if the padding bits after the last base are not zero.
+    pub fn from_packed_bytes(len: usize, bytes: &[u8]) -> Result<Self, FromPackedError> {
+        Self::from_packed_with(len, bytes, |byte| byte)
+    }
+
+    /// Creates a sequence of `len` bases from data packed as in the UCSC
+    /// `.2bit` format, without decoding it to ASCII.
+    ///
+    /// Each byte holds four bases, with the first in the most significant
+    /// bits, encoded as `T = 0b00`, `C = 0b01`, `A = 0b10` and `G = 0b11`.
+    /// Masking and `N` blocks are stored separately in `.2bit` files and
+    /// are not applied here.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not exactly `len.div_ceil(4)` bytes
+    /// long, or if the padding bits after the last base are not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::from_packed_2bit(7, &[0b11_10_00_00, 0b10_01_10_00]).unwrap();
+    /// assert_eq!(seq.to_string(), "GATTACA");
+    /// ```
+    pub fn from_packed_2bit(len: usize, bytes: &[u8]) -> Result<Self, FromPackedError> {
+        Self::from_packed_with(len, bytes, |byte| {
+            // `T C A G` to `C A T G`: the new low bit is the old high bit,
+            // and the new high bit is set when both old bits are equal
+            let high = byte >> 1 & 0x55;
+            let low = byte & 0x55;
+            (!(high ^ low) & 0x55) << 1 | high
+        })
+    }
+
+    /// Creates a sequence from bytes packed with the first base in the most
+    /// significant bits, converting the codes in each byte with `codes`.
+    fn from_packed_with(
+        len: usize,
+        bytes: &[u8],
+        codes: impl Fn(u8) -> u8,
+    ) -> Result<Self, FromPackedError> {
+        check_packed(len, bytes)?;
+        let mut store = BitVec::repeat(false, len * 2);
+        for (chunk, &byte) in store.chunks_mut(8).zip(bytes) {
+            chunk.store_le(reverse_byte_lanes(codes(byte)));
+        }
+        Ok(Self::from_store(store))
+    }
+
+    /// Packs the sequence into bytes, four bases per byte.
+    ///
+    /// The layout is the same as [`unbounded::Kmer::to_packed_bytes`]: the
+    /// first base occupies the two most significant bits of the first byte,
+    /// and the unused low bits of the last byte are zero. It does not depend
+    /// on `B` or on the endianness of the host.
+    ///
+    /// [`unbounded::Kmer::to_packed_bytes`]: crate::unbounded::Kmer::to_packed_bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<u64>::try_from("GATTACA").unwrap();
+    /// let bytes = seq.to_packed_bytes();
+    /// assert_eq!(bytes, [0b11_01_10_10, 0b01_00_01_00]);
+    ///
+    /// let unpacked = Sequence::<u8>::from_packed_bytes(7, &bytes).unwrap();
+    /// assert_eq!(unpacked.to_lowercase_string(), "gattaca");
+    /// ```
+    pub fn to_packed_bytes(&self) -> Vec<u8> {
+        self.store
+            .chunks(8)
+            .map(|chunk| reverse_byte_lanes(chunk.load_le::<u8>()))
+            .collect()
+    }
+
+    /// Returns the packed bases as a bit slice, with base `i` in bits `2 * i`
+    /// and `2 * i + 1`.
+    pub fn as_bitslice(&self) -> &BitSlice<B, Lsb0> {
+        &self.store
+    }
+
+    /// Decomposes the sequence into the memory elements holding its packed
+    /// bases and its length in bases.
+    ///
+    /// The layout is that of [`as_raw_slice`](Self::as_raw_slice), with the
+    /// bits after the last base cleared.
+    pub fn into_raw_parts(mut self) -> (Vec<B>, usize) {
+        let len = self.len();
+        self.store.set_uninitialized(false);
+        (self.store.into_vec(), len)
+    }
+
+    /// Creates a sequence of `len` bases from memory elements in the layout
+    /// produced by [`into_raw_parts`](Self::into_raw_parts), without copying
+    /// them.
+    ///
+    /// Any bits after the last base are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `words` is too short to hold `len` bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<u16>::try_from("GATTACA").unwrap();
+    /// let (words, len) = seq.clone().into_raw_parts();
+    /// assert_eq!(Sequence::from_raw_parts(words, len), seq);
+    /// ```
+    pub fn from_raw_parts(words: Vec<B>, len: usize) -> Self {
+        let mut store = BitVec::from_vec(words);
+        assert!(
+            len * 2 <= store.len(),
+            "{} bits cannot hold {len} bases",
+            store.len()
+        );
+        store.truncate(len * 2);
+        Self::from_store(store)
+    }
+
+    /// Returns an iterator over the bases in the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let mut bases = seq.bases();
+    /// assert_eq!(bases.len(), 7);
+    /// assert_eq!(bases.nth(3), Some(Base::T));
+    /// assert_eq!(bases.next_back(), Some(Base::A));
+    /// assert!(bases.eq([Base::A, Base::C]));
+    /// ```
+    pub fn bases(&self) -> Bases<'_, B> {
+        Bases::new(&self.store)
+    }
+
+    /// Returns an iterator over the bases in the sequence from the 3' end,
+    /// without first collecting them.
+    ///
+    /// This is equivalent to `self.bases().rev()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let mut seq = Sequence::<usize>::try_from("GATTACAAAA").unwrap();
+    /// let tail = seq.bases_rev().take_while(|&base| base == Base::A).count();
+    /// seq.truncate(seq.len() - tail);
+    /// assert_eq!(seq.to_string(), "GATTAC");
+    /// ```
+    pub fn bases_rev(&self) -> core::iter::Rev<Bases<'_, B>> {
+        self.bases().rev()
+    }
+
+    /// Moves all the bases of `other` onto the end of the sequence, leaving
+    /// `other` empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let mut seq = Sequence::<u8>::try_from("GATT").unwrap();
+    /// let mut other = Sequence::<u8>::try_from("ACA").unwrap();
+    /// seq.append(&mut other);
+    /// assert_eq!(seq.to_lowercase_string(), "gattaca");
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        self.extend_from_bits(&other.store);
+        other.store.clear();
+    }
+
+    /// Copies all the bases of `other` onto the end of the sequence.
+    pub fn extend_from_sequence<C: BitStore>(&mut self, other: &Sequence<C>) {
+        self.extend_from_bits(&other.store);
+    }
+
+    /// Encodes ASCII bases onto the end of the sequence.
+    ///
+    /// Characters are encoded many at a time with SIMD instructions where
+    /// available, which is much faster than [`push`](Self::push)ing each
+    /// base.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the byte offset and value of the first character
+    /// that is not a valid base. The bases before it are still appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let mut seq = Sequence::<usize>::try_from_ascii(b"GATT").unwrap();
+    /// seq.extend_from_ascii(b"acagattaca").unwrap();
+    /// assert_eq!(seq.to_string(), "GATTACAGATTACA");
+    ///
+    /// let err = seq.extend_from_ascii(b"GANTC").err().unwrap();
+    /// assert_eq!((err.position(), err.byte()), (2, b'N'));
+    /// assert_eq!(seq.len(), 16);
+    /// ```
+    pub fn extend_from_ascii(&mut self, ascii: &[u8]) -> Result<(), ParseBaseError> {
+        self.reserve(ascii.len());
+        encode::encode(ascii, |word, bases| {
+            if bases == 0 {
+                return;
+            }
+            let start = self.store.len();
+            self.store.resize(start + bases * 2, false);
+            self.store[start..].store_le(word);
+        })
+        .map_err(|position| ParseBaseError::new(position, ascii[position]))
+    }
+
+    /// Copies all the bases in `slice` onto the end of the sequence.
+    pub fn extend_from_slice<C: BitStore>(&mut self, slice: SequenceSlice<'_, C>) {
+        self.extend_from_bits(slice.bits);
+    }
+
+    /// Copies bases a word at a time, shifting them into place when the end
+    /// of the sequence is not word-aligned.
+    fn extend_from_bits<C: BitStore>(&mut self, bits: &BitSlice<C, Lsb0>) {
+        let start = self.store.len();
+        self.store.resize(start + bits.len(), false);
+        let dst = self.store[start..].chunks_mut(usize::BITS as usize);
+        for (dst, src) in dst.zip(bits.chunks(usize::BITS as usize)) {
+            dst.store_le(src.load_le::<usize>());
+        }
+    }
+
+    /// Reverse complements the sequence in place.
+    ///
+    /// Operates on whole words of bases rather than on individual bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let mut seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+    /// seq.reverse_complement();
+    /// assert_eq!(seq.to_lowercase_string(), "tgtaatc");
+    /// ```
+    pub fn reverse_complement(&mut self) {
+        let words = self.reverse_complement_words();
+        for (chunk, word) in self.store.chunks_mut(usize::BITS as usize).zip(words) {
+            chunk.store_le(word);
+        }
+    }
+
+    /// Returns the reverse complement of the sequence.
+    pub fn to_reverse_complement(&self) -> Self {
+        let mut store = BitVec::repeat(false, self.store.len());
+        let words = self.reverse_complement_words();
+        for (chunk, word) in store.chunks_mut(usize::BITS as usize).zip(words) {
+            chunk.store_le(word);
+        }
+        Self::from_store(store)
+    }
+
+    /// Loads the bases a word at a time, independent of the word size of
+    /// `B`.
+    fn words(&self) -> Vec<usize> {
+        self.store
+            .chunks(usize::BITS as usize)
+            .map(|chunk| chunk.load_le())
+            .collect()
+    }
+
+    /// Loads the bases into words and reverse complements them.
+    fn reverse_complement_words(&self) -> Vec<usize> {
+        let mut words = self.words();
+        lanes::reverse_slice(&mut words, self.store.len());
+        lanes::complement_slice(&mut words, self.store.len());
+        words
+    }
+
+    /// Returns the sequence as a lowercase string.
+    ///
+    /// Equivalent to `format!("{seq:#}")`.
+    pub fn to_lowercase_string(&self) -> String {
+        alloc::format!("{self:#}")
+    }
+
+    /// Returns the sequence as an RNA string, using `U` in place of `T`.
+    pub fn to_rna_string(&self) -> String {
+        self.decode(Base::to_rna_ascii)
+    }
+
+    /// Writes the sequence to `writer` as uppercase ASCII.
+    ///
+    /// Bases are decoded a word at a time into a buffer, which is written in
+    /// chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing to `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("gattaca").unwrap();
+    /// let mut fasta = b">seq\n".to_vec();
+    /// seq.write_ascii(&mut fasta).unwrap();
+    /// assert_eq!(fasta, b">seq\nGATTACA");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_ascii(&self, mut writer: impl io::Write) -> io::Result<()> {
+        write_ascii_chunks(&self.store, false, |chunk| writer.write_all(chunk))
+    }
+
+    fn decode(&self, to_ascii: impl Fn(Base) -> u8) -> String {
+        self.store
+            .chunks_exact(2)
+            // SAFETY: 2 bit bases are always valid.
+            .map(|chunk| to_ascii(unsafe { Base::from_u8_unchecked(chunk.load::<u8>()) }) as char)
+            .collect()
     }
 
+    /// Returns an iterator over the k-mers in the sequence.
+    ///
+    /// Yields nothing if the sequence has fewer than `K` bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let kmers: Vec<String> = seq.kmers::<5>().map(|k| k.to_string()).collect();
+    /// assert_eq!(kmers, ["GATTA", "ATTAC", "TTACA"]);
+    /// assert_eq!(seq.kmers::<8>().len(), 0);
+    /// ```
     pub fn kmers<const K: usize>(&self) -> SmallKmerIter<'_, K, B> {
-        let mut kmer = crate::small::Kmer::<K>::new();
-        let mut bases = self.store.chunks_exact(2);
-        for _ in 0..K - 1 {
-            if let Some(chunk) = bases.next() {
-                // SAFETY: 2 bit bases are always valid.
-                kmer.push(unsafe { Base::from_u8_unchecked(chunk.load::<u8>()) });
+        SmallKmerIter::new(&self.store)
+    }
+
+    /// Returns an iterator over every `step`-th k-mer in the sequence, along
+    /// with the index of its first base.
+    ///
+    /// Only the bases of the yielded k-mers are read, so a large step skips
+    /// most of the work of [`kmers`](Self::kmers).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGATTACA").unwrap();
+    /// let kmers: Vec<(usize, String)> = seq.kmers_step::<4>(5).map(|(i, k)| (i, k.to_string())).collect();
+    /// assert_eq!(kmers, [(0, "GATT".to_string()), (5, "CAGA".to_string()), (10, "TACA".to_string())]);
+    /// ```
+    pub fn kmers_step<const K: usize>(&self, step: usize) -> StepKmerIter<'_, K, B> {
+        StepKmerIter::new(&self.store, step)
+    }
+
+    /// Returns an iterator over the k-mers in the sequence, or `None` if the
+    /// sequence has fewer than `K` bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// assert_eq!(seq.try_kmers::<7>().map(|kmers| kmers.len()), Some(1));
+    /// assert!(seq.try_kmers::<8>().is_none());
+    /// ```
+    pub fn try_kmers<const K: usize>(&self) -> Option<SmallKmerIter<'_, K, B>> {
+        (self.len() >= K).then(|| self.kmers())
+    }
+
+    /// Returns a parallel iterator over the k-mers in the sequence.
+    ///
+    /// The sequence is split into chunks that overlap by `K - 1` bases, so
+    /// the same k-mers are produced as by [`kmers`](Self::kmers), in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    /// use rayon::prelude::*;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA".repeat(1000).as_str()).unwrap();
+    /// let gc: u32 = seq.par_kmers::<21>().map(|k| k.gc_count()).sum();
+    /// assert_eq!(gc, seq.kmers::<21>().map(|k| k.gc_count()).sum::<u32>());
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_kmers<const K: usize>(&self) -> ParKmerIter<'_, K, B> {
+        ParKmerIter::new(&self.store)
+    }
+
+    /// Returns an iterator over the k-mers in the sequence, for any `k`.
+    ///
+    /// Unlike [`kmers`](Self::kmers), `k` is not limited to 32. The iterator
+    /// slides a single k-mer along the sequence, which can be borrowed
+    /// without allocating through [`LargeKmerIter::next_kmer`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let kmers: Vec<String> = seq.large_kmers(5).map(|k| k.to_string()).collect();
+    /// assert_eq!(kmers, ["GATTA", "ATTAC", "TTACA"]);
+    ///
+    /// let mut iter = seq.large_kmers(6);
+    /// while let Some(kmer) = iter.next_kmer() {
+    ///     assert_eq!(kmer.size(), 6);
+    /// }
+    /// ```
+    pub fn large_kmers(&self, k: usize) -> LargeKmerIter<'_, B> {
+        LargeKmerIter::new(&self.store, k)
+    }
+
+    /// Returns an iterator over the overlapping windows of `k` bases in the
+    /// sequence, as borrowed views.
+    ///
+    /// Each window can be converted to an [`unbounded::Kmer`] with
+    /// [`SequenceSlice::to_kmer`], or see [`large_kmers`](Self::large_kmers)
+    /// to slide a single k-mer instead.
+    ///
+    /// [`unbounded::Kmer`]: crate::unbounded::Kmer
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let k = "5".parse().unwrap();
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let windows: Vec<String> = seq.windows(k).map(|w| w.to_string()).collect();
+    /// assert_eq!(windows, ["GATTA", "ATTAC", "TTACA"]);
+    /// ```
+    pub fn windows(&self, k: usize) -> Windows<'_, B> {
+        Windows::new(&self.store, k)
+    }
+
+    /// Returns an iterator over the canonical k-mers in the sequence.
+    ///
+    /// Each k-mer is the smaller of the forward k-mer and its reverse
+    /// complement, both of which are updated in constant time per base. Use
+    /// [`CanonicalKmerIter::with_strand`] to also learn which strand each
+    /// canonical k-mer came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Sequence, Strand};
+    ///
+    /// let seq = Sequence::<usize>::try_from("GGATC").unwrap();
+    /// let kmers: Vec<String> = seq.canonical_kmers::<3>().map(|k| k.to_string()).collect();
+    /// assert_eq!(kmers, ["TCC", "ATC", "ATC"]);
+    ///
+    /// let strands: Vec<Strand> = seq.canonical_kmers::<3>().with_strand().map(|(_, s)| s).collect();
+    /// assert_eq!(strands, [Strand::Reverse, Strand::Reverse, Strand::Forward]);
+    /// ```
+    pub fn canonical_kmers<const K: usize>(&self) -> CanonicalKmerIter<'_, K, B> {
+        CanonicalKmerIter::new(&self.store)
+    }
+
+    /// Returns an iterator over the forward ntHash of every k-mer in the
+    /// sequence.
+    ///
+    /// The k-mers themselves are never materialized, so `K` is not limited to
+    /// 32.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::hash::nthash;
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let hashes: Vec<u64> = seq.kmer_hashes::<6>().collect();
+    /// assert_eq!(hashes[1], nthash::hash([Base::A, Base::T, Base::T, Base::A, Base::C, Base::A]));
+    /// ```
+    pub fn kmer_hashes<const K: usize>(&self) -> KmerHashIter<'_, K, B> {
+        KmerHashIter::new(&self.store, false)
+    }
+
+    /// Returns an iterator over the canonical ntHash of every k-mer in the
+    /// sequence, which is the same for a k-mer and its reverse complement.
+    ///
+    /// See [`nthash::canonical`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let rc = seq.to_reverse_complement();
+    /// let mut hashes: Vec<u64> = seq.canonical_kmer_hashes::<4>().collect();
+    /// hashes.reverse();
+    /// assert!(rc.canonical_kmer_hashes::<4>().eq(hashes));
+    /// ```
+    pub fn canonical_kmer_hashes<const K: usize>(&self) -> KmerHashIter<'_, K, B> {
+        KmerHashIter::new(&self.store, true)
+    }
+
+    /// Returns an iterator over the canonical [ntHash2](crate::hash::nthash2)
+    /// of every k-mer in the sequence.
+    ///
+    /// Unlike [`canonical_kmer_hashes`](Self::canonical_kmer_hashes), the
+    /// strands are combined by addition, so a k-mer and its reverse
+    /// complement are not distinguished by which hash was kept. Use
+    /// [`NtHash2Iter::multi`] for several hashes per k-mer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let rc = seq.to_reverse_complement();
+    /// let mut hashes: Vec<u64> = seq.nthash2::<4>().collect();
+    /// hashes.reverse();
+    /// assert!(rc.nthash2::<4>().eq(hashes));
+    /// ```
+    pub fn nthash2<const K: usize>(&self) -> NtHash2Iter<'_, K, B> {
+        NtHash2Iter::new(&self.store)
+    }
+
+    /// Returns an iterator over the `(W, K)`-minimizers of the sequence.
+    ///
+    /// Each window of `W` consecutive k-mers is represented by the k-mer
+    /// with the smallest [ntHash](crate::hash::nthash), taking the leftmost
+    /// on ties. Yields `(position, kmer, hash)` each time the minimizer
+    /// changes, where `position` is the index of the k-mer's first base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::hash::nthash;
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGATTACA").unwrap();
+    /// for (position, kmer, hash) in seq.minimizers::<5, 4>() {
+    ///     assert_eq!(kmer.to_string(), seq.to_lowercase_string()[position..position + 5].to_uppercase());
+    ///     assert_eq!(hash, nthash::hash(kmer.bases()));
+    /// }
+    /// ```
+    pub fn minimizers<const K: usize, const W: usize>(&self) -> MinimizerIter<'_, K, W, B> {
+        MinimizerIter::new(&self.store)
+    }
+
+    /// Returns the winnowed fingerprint of the sequence: the distinct hashes
+    /// of its `(W, K)`-minimizers, as a sketch that supports set operations.
+    ///
+    /// See [`minimizers`](Self::minimizers) for how minimizers are chosen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let a = Sequence::<usize>::try_from("GATTACAGATTACACCGT").unwrap();
+    /// let b = Sequence::<usize>::try_from("GATTACAGATTACA").unwrap();
+    /// let (a, b) = (a.sketch_minimizers::<5, 4>(), b.sketch_minimizers::<5, 4>());
+    /// assert_eq!(b.containment(&a), 1.0);
+    /// assert!(a.len() > b.len());
+    /// ```
+    pub fn sketch_minimizers<const K: usize, const W: usize>(&self) -> MinimizerSketch {
+        self.minimizers::<K, W>().map(|(_, _, hash)| hash).collect()
+    }
+
+    /// Returns an iterator over the syncmers of the sequence.
+    ///
+    /// A k-mer is selected if its smallest s-mer, of size `S`, is at the
+    /// offsets given by `kind`. Yields `(position, kmer)` for each selected
+    /// k-mer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an open syncmer offset is greater than `K - S`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sequence::SyncmerKind;
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGATTACA").unwrap();
+    /// let closed = seq.syncmers::<5, 2>(SyncmerKind::Closed).count();
+    /// let open = seq.syncmers::<5, 2>(SyncmerKind::Open(0)).count();
+    /// assert!(open <= closed);
+    /// ```
+    pub fn syncmers<const K: usize, const S: usize>(
+        &self,
+        kind: SyncmerKind,
+    ) -> SyncmerIter<'_, K, S, B> {
+        SyncmerIter::new(&self.store, kind)
+    }
+
+    /// Returns an iterator over the spaced seeds of the sequence.
+    ///
+    /// Bit `i` of `shape` marks position `i` of each window as a care
+    /// position, and the window spans up to the highest set bit. Yields the
+    /// bases at the `K` care positions of every window, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shape` does not have exactly `K` bits set, or if it spans
+    /// more than 32 bases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let seeds: Vec<String> = seq.spaced_seeds::<3>(0b1011).map(|s| s.to_string()).collect();
+    /// assert_eq!(seeds, ["GAT", "ATA", "TTC", "TAA"]);
+    /// ```
+    pub fn spaced_seeds<const K: usize>(&self, shape: u64) -> SpacedSeedIter<'_, K, B> {
+        SpacedSeedIter::new(&self.store, shape)
+    }
+
+    /// Returns an iterator over the canonical
+    /// [ntHash2](crate::hash::nthash2) of the spaced seed in every window of
+    /// the sequence, as computed by
+    /// [`nthash2::spaced_hashes`](crate::hash::nthash2::spaced_hashes).
+    ///
+    /// The hashes are rolled over the whole window, so `shape` may span up to
+    /// 64 bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shape` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGAGTACA").unwrap();
+    /// let hashes: Vec<u64> = seq.spaced_seed_hashes(0b1011).collect();
+    /// assert_eq!(hashes.len(), 11);
+    /// // `GATT` and `GAGT` differ only at a don't-care position
+    /// assert_eq!(hashes[0], hashes[7]);
+    /// ```
+    pub fn spaced_seed_hashes(&self, shape: u64) -> SpacedHashIter<'_, B> {
+        SpacedHashIter::new(&self.store, shape)
+    }
+
+    /// Returns an iterator over the k-mers of the homopolymer-compressed
+    /// sequence, in which each run of a repeated base counts as one base.
+    ///
+    /// Each k-mer is yielded with the index of its first base in the
+    /// original sequence. The compressed sequence is never materialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GAATTTACCA").unwrap();
+    /// let kmers: Vec<(usize, String)> = seq.hpc_kmers::<4>().map(|(i, k)| (i, k.to_string())).collect();
+    /// assert_eq!(kmers, [(0, "GATA".to_string()), (1, "ATAC".to_string()), (3, "TACA".to_string())]);
+    /// ```
+    pub fn hpc_kmers<const K: usize>(&self) -> HpcKmerIter<'_, K, B> {
+        HpcKmerIter::new(&self.store)
+    }
+
+    /// Returns an iterator over the strobemers of the sequence: seeds of `N`
+    /// strobes of `L` bases, which tolerate insertions and deletions between
+    /// the strobes.
+    ///
+    /// The first strobe of each strobemer starts at each position in turn.
+    /// Strobe `j` (counting from 0) is chosen from those starting between
+    /// `(j - 1) * w_max + w_min` and `j * w_max` bases after the first, where
+    /// `window` is `w_min..=w_max`. Strobemers whose last window extends
+    /// past the end of the sequence are not yielded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is less than 2, or if `window` is empty or starts at
+    /// 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sequence::StrobeKind;
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGATTACAGATTACA").unwrap();
+    /// let strobemers: Vec<_> = seq.strobemers::<4, 2>(StrobeKind::Randstrobe, 2..=6).collect();
+    /// assert_eq!(strobemers.len(), 12);
+    /// let [first, second] = strobemers[0].positions();
+    /// assert!(first == 0 && (2..=6).contains(&second));
+    /// ```
+    pub fn strobemers<const L: usize, const N: usize>(
+        &self,
+        kind: StrobeKind,
+        window: RangeInclusive<usize>,
+    ) -> StrobemerIter<'_, L, N, B> {
+        StrobemerIter::new(&self.store, kind, window)
+    }
+
+    /// Returns a view of the bases in `range`, without copying them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let slice = seq.slice(2..6);
+    /// assert_eq!(slice.len(), 4);
+    /// assert_eq!(slice.kmers::<3>().count(), 2);
+    /// ```
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> SequenceSlice<'_, B> {
+        let range = utils::range(range, self.len());
+        SequenceSlice::new(&self.store[range.start * 2..range.end * 2])
+    }
+
+    /// Returns a view of the whole sequence.
+    pub fn as_slice(&self) -> SequenceSlice<'_, B> {
+        SequenceSlice::new(&self.store)
+    }
+}
+
+/// Decodes bases into a buffer of ASCII a word at a time, passing each full
+/// buffer to `write`.
+fn write_ascii_chunks<B: BitStore, E>(
+    bits: &BitSlice<B, Lsb0>,
+    lowercase: bool,
+    mut write: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), E> {
+    const WORD: usize = usize::BITS as usize;
+    let ascii = if lowercase { b"catg" } else { b"CATG" };
+
+    let mut buf = [0; 1024];
+    for chunk in bits.chunks(buf.len() * 2) {
+        let len = chunk.len() / 2;
+        for (i, word) in chunk.chunks(WORD).enumerate() {
+            let mut word: usize = word.load_le();
+            for byte in buf[i * WORD / 2..len.min((i + 1) * WORD / 2)].iter_mut() {
+                *byte = ascii[word & 0b11];
+                word >>= 2;
             }
         }
+        write(&buf[..len])?;
+    }
+    Ok(())
+}
 
-        SmallKmerIter { bases, kmer }
+/// Checks that `bytes` holds exactly `len` bases packed four per byte, with
+/// zero padding after the last base.
+fn check_packed(len: usize, bytes: &[u8]) -> Result<(), FromPackedError> {
+    let expected = len.div_ceil(4);
+    if bytes.len() != expected {
+        return Err(FromPackedError::Length {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    if let Some(last) = bytes.last()
+        && !len.is_multiple_of(4)
+        && last & (0xFF >> (len % 4 * 2)) != 0
+    {
+        return Err(FromPackedError::Padding);
     }
+    Ok(())
 }
 
+/// Reverses the order of the four 2-bit lanes in a byte, converting between
+/// the packed layout, with the first base in the most significant bits, and
+/// the layout of a sequence.
+const fn reverse_byte_lanes(byte: u8) -> u8 {
+    let byte = byte.rotate_left(4);
+    (byte >> 2) & 0x33 | (byte & 0x33) << 2
+}
+
+/// An iterator over the symbols in a sequence of any [`Alphabet`].
+///
+/// Created with [`Sequence::symbols`].
+#[derive(Debug)]
+pub struct Symbols<'a, B: BitStore, A: Alphabet> {
+    chunks: ChunksExact<'a, B, Lsb0>,
+    alphabet: PhantomData<A>,
+}
+
+impl<'a, B: BitStore, A: Alphabet> Symbols<'a, B, A> {
+    pub(crate) fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self {
+            chunks: bits.chunks_exact(A::BITS),
+            alphabet: PhantomData,
+        }
+    }
+}
+
+impl<'a, B: BitStore, A: Alphabet> Iterator for Symbols<'a, B, A> {
+    type Item = A::Symbol;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(|chunk| A::decode(chunk.load()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a, B: BitStore, A: Alphabet> DoubleEndedIterator for Symbols<'a, B, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(|chunk| A::decode(chunk.load()))
+    }
+}
+
+impl<'a, B: BitStore, A: Alphabet> FusedIterator for Symbols<'a, B, A> {}
+
+impl<'a, B: BitStore, A: Alphabet> ExactSizeIterator for Symbols<'a, B, A> {}
+
+/// An iterator over the bases in a sequence.
+#[derive(Debug)]
+pub struct Bases<'a, B: BitStore> {
+    chunks: ChunksExact<'a, B, Lsb0>,
+}
+
+impl<'a, B: BitStore> Bases<'a, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self {
+            chunks: bits.chunks_exact(2),
+        }
+    }
+}
+
+impl<'a, B: BitStore> Iterator for Bases<'a, B> {
+    type Item = Base;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: 2 bit bases are always valid.
+        Some(unsafe { Base::from_u8_unchecked(self.chunks.next()?.load::<u8>()) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // SAFETY: 2 bit bases are always valid.
+        Some(unsafe { Base::from_u8_unchecked(self.chunks.nth(n)?.load::<u8>()) })
+    }
+}
+
+impl<'a, B: BitStore> DoubleEndedIterator for Bases<'a, B> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // SAFETY: 2 bit bases are always valid.
+        Some(unsafe { Base::from_u8_unchecked(self.chunks.next_back()?.load::<u8>()) })
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        // SAFETY: 2 bit bases are always valid.
+        Some(unsafe { Base::from_u8_unchecked(self.chunks.nth_back(n)?.load::<u8>()) })
+    }
+}
+
+impl<'a, B: BitStore> FusedIterator for Bases<'a, B> {}
+
+impl<'a, B: BitStore> ExactSizeIterator for Bases<'a, B> {}
+
+/// An iterator over the 2-bit codes of the bases in a bit slice.
+///
+/// Codes are loaded a word at a time from either end into a register, and
+/// read from it by shifting, rather than loading each base separately.
+#[derive(Debug, Clone)]
+struct Codes<'a, B: BitStore> {
+    /// Bits that have not been loaded from either end.
+    bits: &'a BitSlice<B, Lsb0>,
+    /// Codes loaded from the front, with the next one in the low bits.
+    front: usize,
+    /// Number of codes remaining in `front`.
+    front_len: usize,
+    /// Codes loaded from the back, with the next one in the high bits.
+    back: usize,
+    /// Number of codes remaining in `back`.
+    back_len: usize,
+}
+
+impl<'a, B: BitStore> Codes<'a, B> {
+    const BITS: usize = usize::BITS as usize;
+
+    fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self {
+            bits,
+            front: 0,
+            front_len: 0,
+            back: 0,
+            back_len: 0,
+        }
+    }
+}
+
+impl<'a, B: BitStore> Iterator for Codes<'a, B> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front_len == 0 {
+            if self.bits.is_empty() {
+                // only the codes loaded from the back remain, lowest first
+                self.back_len = self.back_len.checked_sub(1)?;
+                return Some((self.back >> (Self::BITS - 2 - self.back_len * 2)) as u8 & 0b11);
+            }
+            let (word, rest) = self.bits.split_at(self.bits.len().min(Self::BITS));
+            self.front = word.load_le();
+            self.front_len = word.len() / 2;
+            self.bits = rest;
+        }
+
+        let code = self.front as u8 & 0b11;
+        self.front >>= 2;
+        self.front_len -= 1;
+        Some(code)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front_len + self.bits.len() / 2 + self.back_len;
+        (len, Some(len))
+    }
+}
+
+impl<'a, B: BitStore> DoubleEndedIterator for Codes<'a, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back_len == 0 {
+            if self.bits.is_empty() {
+                // only the codes loaded from the front remain, highest first
+                self.front_len = self.front_len.checked_sub(1)?;
+                return Some((self.front >> (self.front_len * 2)) as u8 & 0b11);
+            }
+            let (rest, word) = self
+                .bits
+                .split_at(self.bits.len() - self.bits.len().min(Self::BITS));
+            self.back = word.load_le::<usize>() << (Self::BITS - word.len());
+            self.back_len = word.len() / 2;
+            self.bits = rest;
+        }
+
+        let code = (self.back >> (Self::BITS - 2)) as u8;
+        self.back <<= 2;
+        self.back_len -= 1;
+        Some(code)
+    }
+}
+
+impl<'a, B: BitStore> ExactSizeIterator for Codes<'a, B> {}
+
+/// An iterator over the k-mers in a sequence.
+#[derive(Debug)]
 pub struct SmallKmerIter<'a, const K: usize, B: BitStore> {
-    bases: ChunksExact<'a, B, Lsb0>,
+    /// The last base of each k-mer, read from the front.
+    bases: Codes<'a, B>,
+    /// The first base of each k-mer, read from the back.
+    starts: Codes<'a, B>,
+    /// The number of k-mers that have not been yielded from either end.
+    len: usize,
     kmer: crate::small::Kmer<K>,
+    /// The last `K - 1` bases of the k-mer after the last remaining one,
+    /// starting at its second lane.
+    back: crate::small::Kmer<K>,
+}
+
+impl<'a, const K: usize, B: BitStore> SmallKmerIter<'a, K, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        let mut kmer = crate::small::Kmer::<K>::new();
+        let mut back = crate::small::Kmer::<K>::new();
+        // a sequence shorter than `K` has no k-mers, rather than a partial one
+        let bits = if bits.len() < K * 2 { &bits[..0] } else { bits };
+        let overlap = bits.len().min((K - 1) * 2);
+        let (head, _) = bits.split_at(overlap);
+        let (starts, tail) = bits.split_at(bits.len() - overlap);
+
+        for code in Codes::new(head) {
+            // SAFETY: 2 bit bases are always valid.
+            kmer.push(unsafe { Base::from_u8_unchecked(code) });
+        }
+        for code in Codes::new(tail).rev() {
+            // SAFETY: 2 bit bases are always valid.
+            back.push_front(unsafe { Base::from_u8_unchecked(code) });
+        }
+
+        Self {
+            bases: Codes::new(&bits[overlap..]),
+            starts: Codes::new(starts),
+            len: starts.len() / 2,
+            kmer,
+            back,
+        }
+    }
 }
 
 impl<'a, const K: usize, B: BitStore> Iterator for SmallKmerIter<'a, K, B> {
     type Item = crate::small::Kmer<K>;
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.kmer
-            .push(unsafe { Base::from_u8_unchecked(self.bases.next()?.load::<u8>()) });
+        // `bases` and `starts` are only read from one end each, so the
+        // remaining k-mers are counted separately.
+        self.len = self.len.checked_sub(1)?;
+        let code = self.bases.next()?;
+        // SAFETY: 2 bit bases are always valid.
+        self.kmer.push(unsafe { Base::from_u8_unchecked(code) });
         Some(self.kmer)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.bases.size_hint()
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> DoubleEndedIterator for SmallKmerIter<'a, K, B> {
+    /// Yields k-mers from the end of the sequence, pushing each new first base
+    /// onto the front of the k-mer.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.len = self.len.checked_sub(1)?;
+        let code = self.starts.next_back()?;
+        // SAFETY: 2 bit bases are always valid.
+        self.back
+            .push_front(unsafe { Base::from_u8_unchecked(code) });
+        Some(self.back)
     }
 }
 
@@ -67,9 +1460,272 @@ impl<'a, const K: usize, B: BitStore> FusedIterator for SmallKmerIter<'a, K, B>
 
 impl<'a, const K: usize, B: BitStore> ExactSizeIterator for SmallKmerIter<'a, K, B> {}
 
+/// An iterator over the ntHash values of the k-mers in a sequence.
+///
+/// Created with [`Sequence::kmer_hashes`] or
+/// [`Sequence::canonical_kmer_hashes`].
+#[derive(Debug)]
+pub struct KmerHashIter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    /// Trails `bases` by `K`, yielding the base leaving the k-mer.
+    out: Bases<'a, B>,
+    forward: u64,
+    /// The reverse complement hash, if canonical hashes were requested.
+    reverse: Option<u64>,
+    primed: bool,
+}
+
+impl<'a, const K: usize, B: BitStore> KmerHashIter<'a, K, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>, canonical: bool) -> Self {
+        utils::const_eval::assert_less::<0, K>();
+        Self {
+            bases: Bases::new(bits),
+            out: Bases::new(bits),
+            forward: 0,
+            reverse: canonical.then_some(0),
+            primed: false,
+        }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for KmerHashIter<'a, K, B> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.primed {
+            self.primed = true;
+            for i in 0..K {
+                // the same folds as `nthash::hash` and `nthash::reverse_hash`
+                let base = self.bases.next()?;
+                self.forward = self.forward.rotate_left(1) ^ nthash::seed(base);
+                self.reverse = self.reverse.map(|reverse| {
+                    reverse ^ nthash::seed(base.complement()).rotate_left((i % 64) as u32)
+                });
+            }
+        } else {
+            let base = self.bases.next()?;
+            // `out` trails `bases` by `K`, so it cannot run out first.
+            let out = self.out.next()?;
+            self.forward = nthash::roll(self.forward, K, out, base);
+            self.reverse = self
+                .reverse
+                .map(|reverse| nthash::roll_reverse(reverse, K, out, base));
+        }
+        Some(match self.reverse {
+            Some(reverse) => nthash::canonical(self.forward, reverse),
+            None => self.forward,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.primed {
+            self.bases.len()
+        } else {
+            (self.bases.len() + 1).saturating_sub(K)
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for KmerHashIter<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for KmerHashIter<'a, K, B> {}
+
+/// An iterator over the k-mers in a sequence and their ntHash values.
+#[derive(Debug)]
+struct HashedKmerIter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    /// Trails `bases` by `K`, yielding the base leaving the k-mer.
+    out: Bases<'a, B>,
+    kmer: crate::small::Kmer<K>,
+    hash: u64,
+    primed: bool,
+}
+
+impl<'a, const K: usize, B: BitStore> HashedKmerIter<'a, K, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self {
+            bases: Bases::new(bits),
+            out: Bases::new(bits),
+            kmer: crate::small::Kmer::new(),
+            hash: 0,
+            primed: false,
+        }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for HashedKmerIter<'a, K, B> {
+    type Item = (crate::small::Kmer<K>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.primed {
+            self.primed = true;
+            for _ in 0..K {
+                self.kmer.push(self.bases.next()?);
+            }
+            self.hash = nthash::hash(self.kmer.bases());
+        } else {
+            let base = self.bases.next()?;
+            // `out` trails `bases` by `K`, so it cannot run out first.
+            let out = self.out.next()?;
+            self.kmer.push(base);
+            self.hash = nthash::roll(self.hash, K, out, base);
+        }
+        Some((self.kmer, self.hash))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.primed {
+            self.bases.len()
+        } else {
+            (self.bases.len() + 1).saturating_sub(K)
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for HashedKmerIter<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for HashedKmerIter<'a, K, B> {}
+
+/// An iterator over the k-mers in a sequence, for any `k`.
+///
+/// Created with [`Sequence::large_kmers`].
+#[derive(Debug)]
+pub struct LargeKmerIter<'a, B: BitStore> {
+    bases: Bases<'a, B>,
+    kmer: crate::unbounded::Kmer,
+}
+
+impl<'a, B: BitStore> LargeKmerIter<'a, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>, k: usize) -> Self {
+        assert!(k > 0, "k must be greater than 0");
+        let mut iter = Self {
+            bases: Bases::new(bits),
+            kmer: crate::unbounded::Kmer::new(k),
+        };
+        for base in iter.bases.by_ref().take(k - 1) {
+            iter.kmer.push(base);
+        }
+        iter
+    }
+
+    /// Advances the iterator, returning a reference to the next k-mer.
+    ///
+    /// The k-mer is updated in place, so this does not allocate.
+    pub fn next_kmer(&mut self) -> Option<&crate::unbounded::Kmer> {
+        self.kmer.push(self.bases.next()?);
+        Some(&self.kmer)
+    }
+}
+
+impl<'a, B: BitStore> Iterator for LargeKmerIter<'a, B> {
+    type Item = crate::unbounded::Kmer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_kmer().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bases.size_hint()
+    }
+}
+
+impl<'a, B: BitStore> FusedIterator for LargeKmerIter<'a, B> {}
+
+impl<'a, B: BitStore> ExactSizeIterator for LargeKmerIter<'a, B> {}
+
+/// An iterator over the canonical k-mers in a sequence.
+#[derive(Debug)]
+pub struct CanonicalKmerIter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    forward: crate::small::Kmer<K>,
+    reverse: crate::small::Kmer<K>,
+}
+
+impl<'a, const K: usize, B: BitStore> CanonicalKmerIter<'a, K, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        let mut iter = Self {
+            bases: Bases::new(bits),
+            forward: crate::small::Kmer::new(),
+            reverse: crate::small::Kmer::new(),
+        };
+        for _ in 0..K - 1 {
+            if iter.advance().is_none() {
+                break;
+            }
+        }
+        iter
+    }
+
+    /// Returns an iterator that also yields the strand of each k-mer.
+    pub fn with_strand(self) -> WithStrand<'a, K, B> {
+        WithStrand { inner: self }
+    }
+
+    /// Slides both strands forward by one base.
+    fn advance(&mut self) -> Option<()> {
+        let base = self.bases.next()?;
+        self.forward.push(base);
+        self.reverse.push_front(base.complement());
+        Some(())
+    }
+
+    fn next_with_strand(&mut self) -> Option<(crate::small::Kmer<K>, Strand)> {
+        self.advance()?;
+        if self.forward.as_masked() <= self.reverse.as_masked() {
+            Some((self.forward, Strand::Forward))
+        } else {
+            Some((self.reverse, Strand::Reverse))
+        }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for CanonicalKmerIter<'a, K, B> {
+    type Item = crate::small::Kmer<K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_strand().map(|(kmer, _)| kmer)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bases.size_hint()
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for CanonicalKmerIter<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for CanonicalKmerIter<'a, K, B> {}
+
+/// An iterator over the canonical k-mers in a sequence and the strands they
+/// were read from.
+///
+/// Created with [`CanonicalKmerIter::with_strand`].
+#[derive(Debug)]
+pub struct WithStrand<'a, const K: usize, B: BitStore> {
+    inner: CanonicalKmerIter<'a, K, B>,
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for WithStrand<'a, K, B> {
+    type Item = (crate::small::Kmer<K>, Strand);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_with_strand()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for WithStrand<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for WithStrand<'a, K, B> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::test::random_ascii;
 
     #[test]
     fn one_kmer() {
@@ -93,4 +1749,528 @@ mod tests {
         let iter_bases: Vec<Base> = kmers[0].bases().collect();
         assert_eq!(iter_bases, bases);
     }
+
+    #[test]
+    fn try_from_ascii() {
+        let seq = Sequence::<u16>::try_from("ACGTTGCA").unwrap();
+        assert_eq!(seq.to_rna_string(), "ACGUUGCA");
+
+        let Err(err) = Sequence::<u8>::try_from("ACGT\nACGT") else {
+            panic!("invalid base was accepted");
+        };
+        assert_eq!(err.position(), 4);
+        assert_eq!(err.byte(), b'\n');
+        assert_eq!(err.to_string(), "invalid base '\\n' at byte 4");
+    }
+
+    #[test]
+    fn try_from_ascii_invalid_at_chunk_start() {
+        for i in [0, 32, 64] {
+            let mut ascii = b"ACGT".repeat(20);
+            ascii[i] = b'N';
+            let err = Sequence::<usize>::try_from_ascii(&ascii).unwrap_err();
+            assert_eq!((err.position(), err.byte()), (i, b'N'));
+
+            let mut seq = Sequence::<u8>::new();
+            assert!(seq.extend_from_ascii(&ascii).is_err());
+            assert_eq!(seq.len(), i);
+        }
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut seq = Sequence::<u8>::new();
+        assert!(seq.is_empty());
+        assert_eq!(seq.get(0), None);
+
+        let bases = [Base::G, Base::A, Base::T, Base::C, Base::A];
+        for base in bases {
+            seq.push(base);
+        }
+        assert_eq!(seq.len(), 5);
+        assert!(!seq.is_empty());
+        for (i, base) in bases.iter().enumerate() {
+            assert_eq!(seq.get(i), Some(*base));
+        }
+
+        for (i, base) in bases.iter().rev().enumerate() {
+            seq.set(i, *base);
+        }
+        assert_eq!(seq.to_lowercase_string(), "actag");
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_out_of_bounds() {
+        Sequence::<u8>::try_from("ACGT")
+            .unwrap_or_else(|_| unreachable!())
+            .set(4, Base::A);
+    }
+
+    #[test]
+    fn reverse_complement() {
+        fn check<B: BitStore>(s: &str, expected: &str) {
+            let seq = Sequence::<B>::try_from(s).unwrap();
+            assert_eq!(seq.to_reverse_complement().to_lowercase_string(), expected);
+            let mut seq = seq;
+            seq.reverse_complement();
+            assert_eq!(seq.to_lowercase_string(), expected);
+        }
+
+        let s = random_ascii(150, 0).to_lowercase();
+        for n in [0, 1, 3, 4, 31, 32, 33, 64, 65, 150] {
+            let expected: String = s[..n]
+                .bytes()
+                .rev()
+                .map(|c| match c {
+                    b'a' => 't',
+                    b'c' => 'g',
+                    b'g' => 'c',
+                    _ => 'a',
+                })
+                .collect();
+            check::<u8>(&s[..n], &expected);
+            check::<u16>(&s[..n], &expected);
+            check::<u64>(&s[..n], &expected);
+            check::<usize>(&s[..n], &expected);
+        }
+    }
+
+    #[test]
+    fn alternate_strings() {
+        let mut seq = Sequence::<u8>::new();
+        for base in [Base::G, Base::A, Base::T, Base::T, Base::C] {
+            seq.push(base);
+        }
+        assert_eq!(seq.to_lowercase_string(), "gattc");
+        assert_eq!(seq.to_rna_string(), "GAUUC");
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        let mut rc: Sequence<u8> = seq.bases().rev().map(Base::complement).collect();
+        assert_eq!(rc.to_lowercase_string(), "tgtaatc");
+
+        rc.extend([Base::G, Base::G]);
+        assert_eq!(rc.to_lowercase_string(), "tgtaatcgg");
+    }
+
+    #[test]
+    fn canonical_kmers() {
+        let s = random_ascii(100, 0);
+        let seq = Sequence::<u8>::try_from(s.as_str()).unwrap();
+
+        let kmers: Vec<_> = seq.canonical_kmers::<21>().with_strand().collect();
+        assert_eq!(kmers.len(), 80);
+        for (kmer, (canonical, strand)) in seq.kmers::<21>().zip(kmers) {
+            assert_eq!(canonical.as_masked(), kmer.canonical().as_masked());
+            let expected = if kmer.is_canonical() {
+                Strand::Forward
+            } else {
+                Strand::Reverse
+            };
+            assert_eq!(strand, expected);
+        }
+
+        assert_eq!(seq.slice(..20).canonical_kmers::<21>().count(), 0);
+    }
+
+    #[test]
+    fn hashed_kmers() {
+        let seq = Sequence::<u8>::try_from("GATTACAGATTACAGATTACA").unwrap();
+        let iter = HashedKmerIter::<'_, 7, _>::new(&seq.store);
+        assert_eq!(iter.len(), 15);
+        for ((kmer, hash), expected) in iter.zip(seq.kmers::<7>()) {
+            assert_eq!(kmer.as_masked(), expected.as_masked());
+            assert_eq!(hash, nthash::hash(expected.bases()));
+        }
+
+        let short = Sequence::<u8>::try_from("GATTAC").unwrap();
+        let mut iter = HashedKmerIter::<'_, 7, _>::new(&short.store);
+        assert_eq!(iter.len(), 0);
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn large_kmers() {
+        let s = random_ascii(300, 0);
+        let seq = Sequence::<u16>::try_from(s.as_str()).unwrap();
+        for k in [1, 31, 32, 33, 64, 65, 100, 300] {
+            let kmers: Vec<String> = seq.large_kmers(k).map(|k| k.to_string()).collect();
+            assert_eq!(kmers.len(), 301 - k);
+            for (i, kmer) in kmers.iter().enumerate() {
+                assert_eq!(*kmer, s[i..i + k]);
+            }
+        }
+        assert_eq!(seq.large_kmers(301).count(), 0);
+
+        for (small, large) in seq.kmers::<21>().zip(seq.large_kmers(21)) {
+            assert_eq!(crate::unbounded::Kmer::from(small), large);
+        }
+    }
+
+    #[test]
+    fn kmer_hashes() {
+        let s = random_ascii(300, 0);
+        let seq = Sequence::<u8>::try_from(s.as_str()).unwrap();
+        let rc = seq.to_reverse_complement();
+
+        let forward: Vec<u64> = seq.kmer_hashes::<100>().collect();
+        let expected: Vec<u64> = seq
+            .large_kmers(100)
+            .map(|kmer| nthash::hash(kmer.bases()))
+            .collect();
+        assert_eq!(forward, expected);
+
+        let canonical: Vec<u64> = seq.canonical_kmer_hashes::<100>().collect();
+        let mut rc_canonical: Vec<u64> = rc.canonical_kmer_hashes::<100>().collect();
+        rc_canonical.reverse();
+        assert_eq!(canonical, rc_canonical);
+        assert!(
+            canonical
+                .iter()
+                .zip(&forward)
+                .all(|(canonical, forward)| canonical <= forward)
+        );
+
+        assert_eq!(seq.kmer_hashes::<301>().len(), 0);
+        assert_eq!(seq.kmer_hashes::<301>().count(), 0);
+        assert_eq!(seq.kmer_hashes::<300>().count(), 1);
+    }
+
+    #[test]
+    fn append() {
+        let s = random_ascii(300, 0);
+        for (i, j) in [
+            (0, 0),
+            (0, 5),
+            (3, 0),
+            (3, 100),
+            (32, 64),
+            (61, 300),
+            (300, 300),
+        ] {
+            let mut seq = Sequence::<u8>::try_from(&s[..i]).unwrap();
+            let mut other = Sequence::<u8>::try_from(&s[..j]).unwrap();
+            seq.append(&mut other);
+            assert_eq!(
+                seq.to_lowercase_string(),
+                (s[..i].to_string() + &s[..j]).to_lowercase()
+            );
+            assert!(other.is_empty());
+
+            let mut seq = Sequence::<u32>::try_from(&s[..i]).unwrap();
+            let other = Sequence::<u16>::try_from(s.as_str()).unwrap();
+            seq.extend_from_slice(other.slice(i..j.max(i)));
+            seq.extend_from_sequence(&other);
+            assert_eq!(
+                seq.to_lowercase_string(),
+                (s[..i].to_string() + &s[i..j.max(i)] + &s).to_lowercase()
+            );
+        }
+    }
+
+    #[test]
+    fn packed_bytes() {
+        let s = random_ascii(150, 0);
+        for n in [0, 1, 3, 4, 5, 31, 32, 33, 150] {
+            let seq = Sequence::<u32>::try_from(&s[..n]).unwrap();
+            let bytes = seq.to_packed_bytes();
+            let kmer: crate::unbounded::Kmer = s[..n].parse().unwrap();
+            assert_eq!(bytes, kmer.to_packed_bytes());
+
+            let Ok(unpacked) = Sequence::<u8>::from_packed_bytes(n, &bytes) else {
+                panic!("failed to unpack {n} bases");
+            };
+            assert_eq!(unpacked.to_lowercase_string(), s[..n].to_lowercase());
+        }
+
+        assert_eq!(
+            Sequence::<u8>::from_packed_bytes(5, &[0]).err(),
+            Some(FromPackedError::Length {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            Sequence::<u8>::from_packed_bytes(5, &[0, 0b00_000100]).err(),
+            Some(FromPackedError::Padding)
+        );
+    }
+
+    #[test]
+    fn packed_2bit() {
+        // every base in each position of a byte
+        let seq = Sequence::<u16>::from_packed_2bit(8, &[0b00_01_10_11, 0b11_10_01_00]).unwrap();
+        assert_eq!(seq.to_string(), "TCAGGACT");
+
+        let seq = Sequence::<u8>::from_packed_2bit(0, &[]).unwrap();
+        assert!(seq.is_empty());
+        assert_eq!(
+            Sequence::<u8>::from_packed_2bit(5, &[0; 1]),
+            Err(FromPackedError::Length {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            Sequence::<u8>::from_packed_2bit(5, &[0, 0b0001_0000]),
+            Err(FromPackedError::Padding)
+        );
+    }
+
+    #[test]
+    fn display() {
+        let s = random_ascii(3000, 0);
+        for n in [0, 1, 31, 32, 33, 1023, 1024, 1025, 3000] {
+            let seq = Sequence::<u8>::try_from(&s[..n]).unwrap();
+            assert_eq!(seq.to_string(), s[..n]);
+            assert_eq!(format!("{seq:#}"), s[..n].to_lowercase());
+
+            #[cfg(feature = "std")]
+            {
+                let mut ascii = Vec::new();
+                seq.write_ascii(&mut ascii).unwrap();
+                assert_eq!(ascii, &s.as_bytes()[..n]);
+            }
+        }
+    }
+
+    #[test]
+    fn traits() {
+        use std::collections::HashSet;
+
+        let s = random_ascii(300, 0);
+        let seq = Sequence::<u8>::try_from(s.as_str()).unwrap();
+        let other = Sequence::<u64>::try_from(s.as_str()).unwrap();
+        assert_eq!(seq, other);
+        assert_eq!(seq, seq.clone());
+        assert_eq!(seq, other.as_slice());
+        assert_ne!(seq, other.slice(1..));
+        assert_eq!(Sequence::<u8>::default(), Sequence::<u16>::new());
+
+        let mut set = HashSet::new();
+        set.insert(seq.clone());
+        set.insert(seq.to_reverse_complement());
+        set.insert(seq);
+        assert_eq!(set.len(), 2);
+
+        // truncating leaves stale bits in the last word, which are not hashed
+        let mut truncated = Sequence::<u8>::try_from("GATTACA").unwrap();
+        truncated.truncate(3);
+        let seq = Sequence::<u8>::try_from("GAT").unwrap();
+        assert!(HashSet::from([truncated]).contains(&seq));
+
+        assert_eq!(format!("{seq:?}"), r#"Sequence { len: 3, bases: "GAT" }"#);
+    }
+
+    #[test]
+    fn pop_and_truncate() {
+        let mut seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        assert_eq!(seq.pop(), Some(Base::A));
+        assert_eq!(seq.pop(), Some(Base::C));
+        assert_eq!(seq.len(), 5);
+
+        seq.truncate(10);
+        assert_eq!(seq.to_string(), "GATTA");
+        seq.truncate(2);
+        assert_eq!(seq.to_string(), "GA");
+        seq.push(Base::G);
+        assert_eq!(seq.to_string(), "GAG");
+
+        seq.truncate(0);
+        assert_eq!(seq.pop(), None);
+        assert!(seq.is_empty());
+    }
+
+    #[test]
+    fn bases() {
+        let s = random_ascii(100, 0);
+        let seq = Sequence::<u16>::try_from(s.as_str()).unwrap();
+        let expected: Vec<Base> = s.bytes().map(|b| Base::from_ascii(b).unwrap()).collect();
+
+        assert!(seq.bases().eq(expected.iter().copied()));
+        assert!(seq.bases().rev().eq(expected.iter().rev().copied()));
+        assert!(seq.bases_rev().eq(expected.iter().rev().copied()));
+        assert!(
+            seq.slice(5..20)
+                .bases_rev()
+                .eq(expected[5..20].iter().rev().copied())
+        );
+        assert!((&seq).into_iter().eq(expected.iter().copied()));
+
+        let mut bases = seq.bases();
+        assert_eq!(bases.nth(10), Some(expected[10]));
+        assert_eq!(bases.nth_back(10), Some(expected[89]));
+        assert_eq!(bases.len(), 78);
+        assert_eq!(bases.nth(78), None);
+        assert_eq!(bases.next(), None);
+    }
+
+    #[test]
+    fn raw_parts() {
+        let mut seq = Sequence::<u32>::try_from("GATTACAGATTACAGATTACA").unwrap();
+        seq.truncate(17);
+        assert_eq!(seq.as_bitslice().len(), 34);
+        assert_eq!(seq.as_raw_slice().len(), 2);
+
+        let (words, len) = seq.clone().into_raw_parts();
+        assert_eq!(len, 17);
+        assert_eq!(words[1], 0b10);
+        let mut unpacked = Sequence::from_raw_parts(words, len);
+        assert_eq!(unpacked, seq);
+        unpacked.push(Base::G);
+        assert_eq!(unpacked.to_string(), "GATTACAGATTACAGATG");
+
+        let words = vec![u8::MAX; 2];
+        assert_eq!(Sequence::from_raw_parts(words, 5).to_string(), "GGGGG");
+    }
+
+    #[test]
+    #[should_panic]
+    fn raw_parts_too_short() {
+        Sequence::from_raw_parts(vec![0u8], 5);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut seq = Sequence::<u64>::with_capacity(100);
+        assert!(seq.capacity() >= 100);
+        assert!(seq.is_empty());
+
+        seq.extend([Base::G; 10]);
+        seq.reserve(500);
+        assert!(seq.capacity() >= 510);
+
+        seq.shrink_to_fit();
+        assert!(seq.capacity() >= 10);
+        assert!(seq.capacity() < 510);
+        assert_eq!(seq.to_string(), "G".repeat(10));
+    }
+
+    #[test]
+    fn short_kmers() {
+        for n in 0..8 {
+            let seq = Sequence::<u8>::try_from(&"GATTACA"[..n]).unwrap();
+            let kmers = seq.kmers::<5>();
+            let expected = (n + 1).saturating_sub(5);
+            assert_eq!(kmers.len(), expected, "n = {n}");
+            assert_eq!(kmers.count(), expected, "n = {n}");
+            assert_eq!(seq.try_kmers::<5>().is_some(), n >= 5);
+        }
+    }
+
+    #[test]
+    fn kmers_rev() {
+        let s = random_ascii(100, 0);
+        let seq = Sequence::<u8>::try_from(s.as_str()).unwrap();
+
+        let forward: Vec<u64> = seq.kmers::<13>().map(|k| k.as_masked()).collect();
+        let mut backward: Vec<u64> = seq.kmers::<13>().rev().map(|k| k.as_masked()).collect();
+        backward.reverse();
+        assert_eq!(backward, forward);
+
+        // meeting in the middle yields every k-mer once
+        let mut kmers = seq.kmers::<13>();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(kmer) = kmers.next() {
+            front.push(kmer.as_masked());
+            if let Some(kmer) = kmers.next_back() {
+                back.push(kmer.as_masked());
+            }
+            assert_eq!(kmers.len(), forward.len() - front.len() - back.len());
+        }
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, forward);
+
+        let short = Sequence::<u8>::try_from("GATTAC").unwrap();
+        assert!(short.kmers::<7>().next_back().is_none());
+        let exact = Sequence::<u8>::try_from("GATTACA").unwrap();
+        assert_eq!(
+            exact.kmers::<7>().next_back().map(|k| k.to_string()),
+            Some("GATTACA".to_string())
+        );
+    }
+
+    #[test]
+    fn codes() {
+        let s = random_ascii(300, 0);
+        let seq = Sequence::<u16>::try_from(s.as_str()).unwrap();
+        let expected: Vec<u8> = seq.bases().map(|base| base as u8).collect();
+
+        for (start, end) in [(0, 300), (3, 300), (5, 41), (7, 290), (33, 33)] {
+            let bits = &seq.store[start * 2..end * 2];
+            let expected = &expected[start..end];
+            assert!(Codes::new(bits).eq(expected.iter().copied()));
+            assert!(Codes::new(bits).rev().eq(expected.iter().rev().copied()));
+
+            // alternating ends meet in the middle
+            let mut codes = Codes::new(bits);
+            let (mut front, mut back) = (Vec::new(), Vec::new());
+            for i in 0.. {
+                let code = if i % 3 == 0 {
+                    codes.next_back().inspect(|&code| back.push(code))
+                } else {
+                    codes.next().inspect(|&code| front.push(code))
+                };
+                if code.is_none() {
+                    break;
+                }
+                assert_eq!(codes.len(), expected.len() - front.len() - back.len());
+            }
+            back.reverse();
+            front.extend(back);
+            assert_eq!(front, expected);
+        }
+    }
+
+    #[test]
+    fn kmers_unaligned() {
+        let s = random_ascii(300, 0);
+        let seq = Sequence::<u32>::try_from(s.as_str()).unwrap();
+        for start in [0, 1, 15, 17] {
+            let slice = seq.slice(start..);
+            let expected: Vec<String> = (start..=300 - 31)
+                .map(|i| s[i..i + 31].to_string())
+                .collect();
+            let kmers: Vec<String> = slice.kmers::<31>().map(|k| k.to_string()).collect();
+            assert_eq!(kmers, expected);
+            let mut rev: Vec<String> = slice.kmers::<31>().rev().map(|k| k.to_string()).collect();
+            rev.reverse();
+            assert_eq!(rev, expected);
+        }
+    }
+
+    #[test]
+    fn protein() {
+        use crate::alphabet::Protein;
+        use crate::translate::AminoAcid;
+
+        let ascii: Vec<u8> = (0..100)
+            .map(|i| b"ACDEFGHIKLMNPQRSTVWY*"[i * 7 % 21])
+            .collect();
+        let mut seq = Sequence::<u16, Protein>::parse_ascii(&ascii).unwrap();
+        assert_eq!(seq.len(), 100);
+        assert_eq!(seq.to_ascii(), ascii);
+        assert!(
+            seq.symbols()
+                .rev()
+                .map(|amino| amino.to_ascii())
+                .eq(ascii.iter().rev().copied())
+        );
+
+        seq.set(3, AminoAcid::Trp);
+        assert_eq!(seq.get(3), Some(AminoAcid::Trp));
+        assert_eq!(seq.pop(), Some(AminoAcid::from_ascii(ascii[99]).unwrap()));
+        seq.truncate(2);
+        assert_eq!(seq.to_ascii(), &ascii[..2]);
+        assert_eq!(format!("{seq:?}"), r#"Sequence { len: 2, bases: "AI" }"#);
+
+        let err = Sequence::<usize, Protein>::parse_ascii(b"MKXV").unwrap_err();
+        assert_eq!((err.position(), err.byte()), (2, b'X'));
+    }
 }
@@ -0,0 +1,185 @@
+use core::fmt;
+
+use bitvec::store::BitStore;
+use rkyv::bytecheck::{CheckBytes, Verify};
+use rkyv::munge::munge;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Archived, Deserialize, Place, Portable, Serialize};
+
+use super::{Sequence, check_packed};
+use crate::Base;
+
+/// An archived [`Sequence`], which can be read in place without
+/// deserializing it.
+///
+/// Bases are stored four per byte in the layout of
+/// [`Sequence::to_packed_bytes`], so an archive does not depend on the word
+/// type of the sequence that wrote it, or on the endianness of the host.
+/// Accessing an archive with [`rkyv::access`] checks that the packed bytes
+/// hold exactly the archived number of bases.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::sequence::ArchivedSequence;
+/// use helicase::{Base, Sequence};
+/// use rkyv::rancor::Error;
+///
+/// let seq = Sequence::<u64>::try_from("GATTACA").unwrap();
+/// let bytes = rkyv::to_bytes::<Error>(&seq).unwrap();
+///
+/// let archived = rkyv::access::<ArchivedSequence, Error>(&bytes).unwrap();
+/// assert_eq!(archived.get(3), Some(Base::T));
+/// assert_eq!(archived.to_string(), "GATTACA");
+///
+/// let seq: Sequence<u8> = rkyv::deserialize::<_, Error>(archived).unwrap();
+/// assert_eq!(seq.len(), 7);
+/// ```
+#[derive(Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[repr(C)]
+pub struct ArchivedSequence {
+    len: Archived<u64>,
+    /// The bases in the layout of [`Sequence::to_packed_bytes`].
+    bytes: ArchivedVec<u8>,
+}
+
+impl ArchivedSequence {
+    /// Returns the number of bases in the sequence.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns `true` if the sequence has no bases.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the base at position `i`, or `None` if `i` is out of bounds.
+    pub fn get(&self, i: usize) -> Option<Base> {
+        if i >= self.len() {
+            return None;
+        }
+        let code = self.bytes[i / 4] >> (6 - i % 4 * 2) & 0b11;
+        // SAFETY: `code` is masked to two bits
+        Some(unsafe { Base::from_u8_unchecked(code) })
+    }
+
+    /// Returns an iterator over the bases of the sequence.
+    pub fn bases(&self) -> impl DoubleEndedIterator<Item = Base> + ExactSizeIterator + '_ {
+        (0..self.len()).map(|i| self.get(i).unwrap())
+    }
+
+    /// Returns the packed bases, in the layout of
+    /// [`Sequence::to_packed_bytes`].
+    pub fn as_packed_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Copies the archived bases into a new sequence.
+    pub fn to_sequence<B: BitStore>(&self) -> Sequence<B> {
+        // checked when the archive was accessed
+        Sequence::from_packed_bytes(self.len(), &self.bytes)
+            .expect("archived sequence is packed correctly")
+    }
+}
+
+impl fmt::Debug for ArchivedSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchivedSequence")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl fmt::Display for ArchivedSequence {
+    /// Formats the sequence as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::base::write_bases(f, self.bases())
+    }
+}
+
+// SAFETY: `verify` only succeeds if the packed bytes hold exactly `len`
+// bases, which is what `get` relies on.
+unsafe impl<C: Fallible + ?Sized> Verify<C> for ArchivedSequence
+where
+    C::Error: Source,
+{
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        let len = usize::try_from(self.len.to_native()).map_err(Source::new)?;
+        check_packed(len, &self.bytes).map_err(Source::new)
+    }
+}
+
+impl<B: BitStore> Archive for Sequence<B> {
+    type Archived = ArchivedSequence;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedSequence { len, bytes } = out);
+        (self.len() as u64).resolve((), len);
+        ArchivedVec::<u8>::resolve_from_len(self.len().div_ceil(4), resolver, bytes);
+    }
+}
+
+impl<B: BitStore, S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for Sequence<B> {
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(&self.to_packed_bytes(), serializer)
+    }
+}
+
+impl<B: BitStore, D: Fallible + ?Sized> Deserialize<Sequence<B>, D> for ArchivedSequence
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Sequence<B>, D::Error> {
+        Sequence::from_packed_bytes(self.len(), &self.bytes).map_err(Source::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::rancor::Error;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for s in ["", "G", "GATT", "GATTACAGATTACAGATTACAGATTACAGATTACA"] {
+            let seq = Sequence::<usize>::try_from(s).unwrap();
+            let bytes = rkyv::to_bytes::<Error>(&seq).unwrap();
+            let archived = rkyv::access::<ArchivedSequence, Error>(&bytes).unwrap();
+            assert_eq!(archived.len(), s.len());
+            assert_eq!(archived.to_string(), s);
+            assert_eq!(archived.bases().rev().count(), s.len());
+            assert_eq!(archived.get(s.len()), None);
+            assert_eq!(archived.to_sequence::<u16>(), seq);
+
+            let deserialized: Sequence<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+            assert_eq!(deserialized, seq);
+        }
+    }
+
+    #[test]
+    fn in_struct() {
+        let seqs: Vec<Sequence<u8>> = ["GATTACA", "ACGT"]
+            .map(|s| Sequence::try_from(s).unwrap())
+            .into();
+        let bytes = rkyv::to_bytes::<Error>(&seqs).unwrap();
+        let archived = rkyv::access::<ArchivedVec<ArchivedSequence>, Error>(&bytes).unwrap();
+        assert_eq!(archived[1].as_packed_bytes(), seqs[1].to_packed_bytes());
+        assert_eq!(format!("{:#}", archived[0]), "gattaca");
+    }
+
+    #[test]
+    fn invalid() {
+        let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        let mut bytes = rkyv::to_bytes::<Error>(&seq).unwrap();
+        // the padding bits of the last packed byte
+        let last = bytes.iter().position(|&b| b == 0b01_00_01_00).unwrap();
+        bytes[last] |= 1;
+        assert!(rkyv::access::<ArchivedSequence, Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use bitvec::store::BitStore;
+
+use super::{MaskedSequence, Sequence};
+use crate::base::{Base, ParseBaseError};
+
+/// The default number of bytes read at a time.
+#[cfg(feature = "std")]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Builds a sequence from ASCII read incrementally, such as from a file.
+///
+/// Input is encoded as it arrives, so the ASCII is never held in memory as a
+/// whole. ASCII whitespace, such as the line breaks in a FASTA record, is
+/// skipped. Other characters that are not bases are recorded as ambiguous
+/// positions, and lowercase characters as soft-masked.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::sequence::SequenceBuilder;
+///
+/// let mut builder = <SequenceBuilder>::new();
+/// builder.read_from(&b"GATT\nACA\n"[..]).unwrap();
+/// let seq = builder.build().unwrap();
+/// assert_eq!(seq.to_string(), "GATTACA");
+///
+/// let mut builder = SequenceBuilder::<usize>::new();
+/// builder.push_ascii(b"GATTNCA");
+/// assert_eq!(builder.ambiguous_count(), 1);
+/// let err = builder.build().err().unwrap();
+/// assert_eq!((err.position(), err.byte()), (4, b'N'));
+/// ```
+#[derive(Debug)]
+pub struct SequenceBuilder<B: BitStore = usize> {
+    seq: MaskedSequence<B>,
+    /// The number of bytes of input consumed.
+    consumed: usize,
+    /// The first character that was not a base or whitespace.
+    first_invalid: Option<ParseBaseError>,
+    #[cfg(feature = "std")]
+    chunk_size: usize,
+}
+
+impl<B: BitStore> Default for SequenceBuilder<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: BitStore> SequenceBuilder<B> {
+    /// Creates a new, empty builder.
+    pub fn new() -> Self {
+        Self {
+            seq: MaskedSequence::from(Sequence::new()),
+            consumed: 0,
+            first_invalid: None,
+            #[cfg(feature = "std")]
+            chunk_size: CHUNK_SIZE,
+        }
+    }
+
+    /// Sets the number of bytes read at a time by
+    /// [`read_from`](Self::read_from). Defaults to 64 KiB.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    #[cfg(feature = "std")]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be greater than 0");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Encodes a chunk of ASCII onto the end of the sequence.
+    pub fn push_ascii(&mut self, ascii: &[u8]) {
+        for (i, &byte) in ascii.iter().enumerate() {
+            match Base::from_ascii(byte) {
+                Some(_) => {}
+                None if byte.is_ascii_whitespace() => continue,
+                None => {
+                    self.first_invalid
+                        .get_or_insert(ParseBaseError::new(self.consumed + i, byte));
+                }
+            }
+            self.seq.push_ascii(byte);
+        }
+        self.consumed += ascii.len();
+    }
+
+    /// Reads `reader` to the end, encoding each chunk as it is read.
+    ///
+    /// Returns the number of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `reader` other than
+    /// [`ErrorKind::Interrupted`](io::ErrorKind::Interrupted), which is
+    /// retried. Bases read before the error are kept.
+    #[cfg(feature = "std")]
+    pub fn read_from(&mut self, mut reader: impl Read) -> io::Result<usize> {
+        let mut buf = alloc::vec![0; self.chunk_size];
+        let mut total = 0;
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return Ok(total),
+                Ok(n) => {
+                    self.push_ascii(&buf[..n]);
+                    total += n;
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the number of positions encoded so far, including ambiguous
+    /// ones.
+    pub fn len(&self) -> usize {
+        self.seq.len()
+    }
+
+    /// Returns `true` if no positions have been encoded.
+    pub fn is_empty(&self) -> bool {
+        self.seq.is_empty()
+    }
+
+    /// Returns the number of characters encoded as ambiguous positions.
+    pub fn ambiguous_count(&self) -> usize {
+        self.seq.ambiguous_count()
+    }
+
+    /// Finishes building, keeping track of ambiguous positions.
+    pub fn build_masked(self) -> MaskedSequence<B> {
+        self.seq
+    }
+
+    /// Finishes building a sequence of unambiguous bases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the byte offset in the input and value of the
+    /// first character that was neither a base nor whitespace.
+    pub fn build(self) -> Result<Sequence<B>, ParseBaseError> {
+        match self.first_invalid {
+            Some(err) => Err(err),
+            None => Ok(self.seq.into_sequence()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read_from() {
+        let s: String = (0..10_000)
+            .map(|i| ["A", "c", "G", "t", "\n"][i * 7 % 11 % 5])
+            .collect();
+        let expected: String = s.split_ascii_whitespace().collect();
+
+        for chunk_size in [1, 7, 4096] {
+            let mut builder = SequenceBuilder::<u32>::new().with_chunk_size(chunk_size);
+            assert_eq!(builder.read_from(s.as_bytes()).unwrap(), s.len());
+            assert_eq!(builder.len(), expected.len());
+            let seq = builder.build().unwrap();
+            assert_eq!(seq.to_string(), expected.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn ambiguous() {
+        let mut builder = SequenceBuilder::<u8>::new();
+        builder.push_ascii(b"GAT\n");
+        builder.push_ascii(b"NNA\nRCA");
+        assert_eq!(builder.ambiguous_count(), 3);
+
+        let seq = builder.build_masked();
+        assert_eq!(seq.len(), 9);
+        assert!(seq.valid_runs().eq([0..3, 5..6, 7..9]));
+        assert!(seq.masked_regions().next().is_none());
+
+        let mut builder = SequenceBuilder::<u8>::new();
+        builder.push_ascii(b"GAT\n");
+        builder.push_ascii(b"NNA\nRCA");
+        let err = builder.build().err().unwrap();
+        assert_eq!((err.position(), err.byte()), (4, b'N'));
+    }
+}
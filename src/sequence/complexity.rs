@@ -0,0 +1,311 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::iter::FusedIterator;
+
+#[cfg(feature = "std")]
+use bitvec::order::Lsb0;
+#[cfg(feature = "std")]
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+#[cfg(feature = "std")]
+use super::Bases;
+use super::{Sequence, SequenceSlice};
+use crate::utils::complexity;
+
+impl<B: BitStore> SequenceSlice<'_, B> {
+    /// Returns the length of the longest run of identical bases in the view,
+    /// or 0 if it is empty.
+    pub fn longest_homopolymer(&self) -> usize {
+        let mut bases = self.bases();
+        let Some(mut prev) = bases.next() else {
+            return 0;
+        };
+        let (mut run, mut longest) = (1, 1);
+        for base in bases {
+            run = if base == prev { run + 1 } else { 1 };
+            longest = longest.max(run);
+            prev = base;
+        }
+        longest
+    }
+
+    /// Returns the Shannon entropy of the bases in the view, in bits, or 0
+    /// if it is empty.
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        let mut counts = [0; 4];
+        for base in self.bases() {
+            counts[base as usize] += 1;
+        }
+        complexity::entropy(counts)
+    }
+
+    /// Returns the linguistic complexity of the view, or 0 if it is empty.
+    ///
+    /// See [`Sequence::linguistic_complexity`].
+    pub fn linguistic_complexity(&self) -> f64 {
+        let codes: Vec<u8> = self.bases().map(|base| base as u8).collect();
+        complexity::linguistic_complexity(&codes, &mut alloc::vec![0; codes.len()])
+    }
+}
+
+impl<B: BitStore> Sequence<B> {
+    /// Returns the length of the longest run of identical bases in the
+    /// sequence, or 0 if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTTACCA").unwrap();
+    /// assert_eq!(seq.longest_homopolymer(), 3);
+    /// ```
+    pub fn longest_homopolymer(&self) -> usize {
+        self.slice(..).longest_homopolymer()
+    }
+
+    /// Returns the Shannon entropy of the bases in the sequence, in bits, or
+    /// 0 if it is empty.
+    ///
+    /// The entropy ranges from 0 for a homopolymer to 2 for a sequence with
+    /// equal numbers of each base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// assert_eq!(Sequence::<usize>::try_from("GATC").unwrap().entropy(), 2.0);
+    /// assert_eq!(Sequence::<usize>::try_from("GAGA").unwrap().entropy(), 1.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        complexity::entropy(self.composition())
+    }
+
+    /// Returns the linguistic complexity of the sequence, or 0 if it is
+    /// empty.
+    ///
+    /// This is the number of distinct substrings of the sequence, of every
+    /// length, divided by the most that any sequence of its length could
+    /// have. It is 1 when no substring is repeated that could have been
+    /// avoided, and low for sequences made of few short repeats.
+    ///
+    /// Substrings are counted by sorting the suffixes of the sequence, which
+    /// takes quadratic time for long repeats, so this is meant for reads and
+    /// windows rather than whole genomes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// assert_eq!(seq.linguistic_complexity(), 1.0);
+    ///
+    /// // 15 distinct substrings, of the 32 that 8 bases could have
+    /// let seq = Sequence::<usize>::try_from("GAGAGAGA").unwrap();
+    /// assert_eq!(seq.linguistic_complexity(), 15.0 / 32.0);
+    /// ```
+    pub fn linguistic_complexity(&self) -> f64 {
+        self.slice(..).linguistic_complexity()
+    }
+
+    /// Returns an iterator over the Shannon entropy of each window of
+    /// `width` bases in the sequence.
+    ///
+    /// The composition of the window is updated as it slides, so each
+    /// window takes constant time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("AAAAGATC").unwrap();
+    /// let entropy: Vec<f64> = seq.entropy_windows(4).collect();
+    /// assert_eq!(entropy.len(), 5);
+    /// assert_eq!((entropy[0], entropy[4]), (0.0, 2.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn entropy_windows(&self, width: usize) -> EntropyWindows<'_, B> {
+        EntropyWindows::new(&self.store, width)
+    }
+
+    /// Returns an iterator over the linguistic complexity of each window of
+    /// `width` bases in the sequence.
+    ///
+    /// See [`linguistic_complexity`](Self::linguistic_complexity). Other
+    /// measures of each window can be computed from
+    /// [`windows`](Self::windows), such as
+    /// `seq.windows(width).map(|w| w.longest_homopolymer())`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is 0.
+    pub fn linguistic_complexity_windows(&self, width: usize) -> impl Iterator<Item = f64> + '_ {
+        let mut codes = Vec::with_capacity(width);
+        let mut suffixes = alloc::vec![0; width];
+        self.windows(width).map(move |window| {
+            codes.clear();
+            codes.extend(window.bases().map(|base| base as u8));
+            complexity::linguistic_complexity(&codes, &mut suffixes)
+        })
+    }
+}
+
+/// An iterator over the Shannon entropy of each window of a sequence.
+///
+/// Created with [`Sequence::entropy_windows`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct EntropyWindows<'a, B: BitStore> {
+    /// The bases entering the window.
+    front: Bases<'a, B>,
+    /// The bases leaving the window.
+    back: Bases<'a, B>,
+    /// The number of each base in the window, once it is full.
+    counts: Option<[u64; 4]>,
+    width: usize,
+}
+
+#[cfg(feature = "std")]
+impl<'a, B: BitStore> EntropyWindows<'a, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>, width: usize) -> Self {
+        assert!(width > 0, "width must be greater than 0");
+        Self {
+            front: Bases::new(bits),
+            back: Bases::new(bits),
+            counts: None,
+            width,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, B: BitStore> Iterator for EntropyWindows<'a, B> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.counts {
+            Some(counts) => {
+                let base = self.front.next()?;
+                counts[base as usize] += 1;
+                let old = self.back.next().expect("back is behind front");
+                counts[old as usize] -= 1;
+            }
+            None => {
+                if self.front.len() < self.width {
+                    return None;
+                }
+                let mut counts = [0; 4];
+                for base in self.front.by_ref().take(self.width) {
+                    counts[base as usize] += 1;
+                }
+                self.counts = Some(counts);
+            }
+        }
+        self.counts.map(complexity::entropy)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.counts {
+            Some(_) => self.front.len(),
+            None => (self.front.len() + 1).saturating_sub(self.width),
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, B: BitStore> FusedIterator for EntropyWindows<'a, B> {}
+
+#[cfg(feature = "std")]
+impl<'a, B: BitStore> ExactSizeIterator for EntropyWindows<'a, B> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use crate::Sequence;
+    use crate::small::Kmer;
+    use crate::utils::test::random_ascii;
+
+    /// Computes the linguistic complexity of a string by listing its
+    /// substrings.
+    fn linguistic_complexity(s: &str) -> f64 {
+        let (mut distinct, mut max) = (0, 0);
+        for len in 1..=s.len() {
+            let substrings: BTreeSet<&str> = (0..=s.len() - len).map(|i| &s[i..i + len]).collect();
+            distinct += substrings.len();
+            max += (s.len() - len + 1).min(4usize.saturating_pow(len as u32));
+        }
+        distinct as f64 / max as f64
+    }
+
+    #[test]
+    fn complexity() {
+        let s = random_ascii(150, 0);
+        for n in [1, 2, 5, 16, 40, 150] {
+            let s = &s[..n];
+            let seq = Sequence::<u8>::try_from(s).unwrap();
+            assert_eq!(seq.linguistic_complexity(), linguistic_complexity(s));
+
+            let longest = (0..n)
+                .map(|i| {
+                    s[i..]
+                        .chars()
+                        .take_while(|&c| c == s.as_bytes()[i] as char)
+                        .count()
+                })
+                .max()
+                .unwrap();
+            assert_eq!(seq.longest_homopolymer(), longest);
+        }
+
+        let s = "ACGTTTTTACGTTTTTACGT";
+        let seq = Sequence::<u64>::try_from(s).unwrap();
+        let kmer: Kmer<20> = seq.kmers().next().unwrap();
+        assert_eq!(kmer.linguistic_complexity(), linguistic_complexity(s));
+        assert_eq!(kmer.longest_homopolymer(), 5);
+        #[cfg(feature = "std")]
+        assert_eq!(kmer.entropy(), seq.entropy());
+        assert_eq!(Kmer::<32>::from(u64::MAX).longest_homopolymer(), 32);
+        assert_eq!(Kmer::<1>::new().longest_homopolymer(), 1);
+
+        let empty = Sequence::<u8>::new();
+        assert_eq!(empty.longest_homopolymer(), 0);
+        #[cfg(feature = "std")]
+        assert_eq!(empty.entropy(), 0.0);
+        assert_eq!(empty.linguistic_complexity(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn windows() {
+        let s = random_ascii(100, 0);
+        let seq = Sequence::<u16>::try_from(s.as_str()).unwrap();
+        for width in [1, 7, 64, 100, 101] {
+            let entropy = seq.entropy_windows(width);
+            assert_eq!(entropy.len(), (101usize).saturating_sub(width));
+            let expected: Vec<f64> = seq.windows(width).map(|w| w.entropy()).collect();
+            let entropy: Vec<f64> = entropy.collect();
+            assert_eq!(entropy.len(), expected.len());
+            for (a, b) in entropy.iter().zip(&expected) {
+                assert!((a - b).abs() < 1e-12);
+            }
+
+            let expected: Vec<f64> = seq
+                .windows(width)
+                .map(|w| w.linguistic_complexity())
+                .collect();
+            assert!(seq.linguistic_complexity_windows(width).eq(expected));
+        }
+    }
+}
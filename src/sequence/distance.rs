@@ -0,0 +1,385 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use core::iter::{Enumerate, FusedIterator, Zip};
+
+use bitvec::field::BitField as _;
+use bitvec::order::Lsb0;
+use bitvec::slice::Chunks;
+use bitvec::store::BitStore;
+
+use super::Sequence;
+use crate::utils::lanes;
+
+/// The number of bits loaded at a time.
+const WORD: usize = usize::BITS as usize;
+
+impl<B: BitStore> Sequence<B> {
+    /// Returns the Hamming distance between two sequences of equal length:
+    /// the number of positions at which their bases differ.
+    ///
+    /// The bases are compared a word at a time, rather than base by base.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequences have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let a = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let b = Sequence::<u8>::try_from("GACTATA").unwrap();
+    /// assert_eq!(a.hamming_distance(&b), 2);
+    /// ```
+    pub fn hamming_distance<C: BitStore>(&self, other: &Sequence<C>) -> usize {
+        self.mismatch_words(other)
+            .map(|(a, b)| lanes::mismatches(a.load_le(), b.load_le()) as usize)
+            .sum()
+    }
+
+    /// Returns an iterator over the positions at which the bases of two
+    /// sequences of equal length differ, in increasing order.
+    ///
+    /// See [`hamming_distance`](Self::hamming_distance).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequences have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let a = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let b = Sequence::<u8>::try_from("GACTATA").unwrap();
+    /// assert!(a.mismatches(&b).eq([2, 5]));
+    /// ```
+    pub fn mismatches<'a, C: BitStore>(&'a self, other: &'a Sequence<C>) -> Mismatches<'a, B, C> {
+        Mismatches {
+            words: self.mismatch_words(other).enumerate(),
+            differences: 0,
+            start: 0,
+        }
+    }
+
+    /// Returns the words of two sequences of equal length side by side.
+    fn mismatch_words<'a, C: BitStore>(
+        &'a self,
+        other: &'a Sequence<C>,
+    ) -> Zip<Chunks<'a, B, Lsb0>, Chunks<'a, C, Lsb0>> {
+        assert_eq!(self.len(), other.len(), "sequences have different lengths");
+        self.store.chunks(WORD).zip(other.store.chunks(WORD))
+    }
+
+    /// Returns the edit (Levenshtein) distance between two sequences: the
+    /// minimum number of substitutions, insertions and deletions that turn
+    /// one into the other.
+    ///
+    /// Uses Myers' bit-vector algorithm, reading `other` base by base from
+    /// its packed representation and taking `self.len() / 64` word
+    /// operations per base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let a = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let b = Sequence::<u8>::try_from("GCATGCA").unwrap();
+    /// assert_eq!(a.edit_distance(&b), 3);
+    /// assert_eq!(b.edit_distance(&a), 3);
+    /// ```
+    pub fn edit_distance<C: BitStore>(&self, other: &Sequence<C>) -> usize {
+        Myers::new(self).distance(other, None).unwrap()
+    }
+
+    /// Returns the edit distance between two sequences if it is at most
+    /// `max`, or `None` otherwise.
+    ///
+    /// Only the band of `max` diagonals either side of the main diagonal is
+    /// computed, so this is much faster than
+    /// [`edit_distance`](Self::edit_distance) when `max` is small, and stops
+    /// early once the distance is known to exceed `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let a = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let b = Sequence::<u8>::try_from("GCATGCA").unwrap();
+    /// assert_eq!(a.edit_distance_within(&b, 3), Some(3));
+    /// assert_eq!(a.edit_distance_within(&b, 2), None);
+    /// ```
+    pub fn edit_distance_within<C: BitStore>(
+        &self,
+        other: &Sequence<C>,
+        max: usize,
+    ) -> Option<usize> {
+        Myers::new(self).distance(other, Some(max))
+    }
+}
+
+/// An iterator over the positions at which two sequences differ.
+///
+/// Created with [`Sequence::mismatches`].
+#[derive(Debug)]
+pub struct Mismatches<'a, B: BitStore, C: BitStore> {
+    words: Enumerate<Zip<Chunks<'a, B, Lsb0>, Chunks<'a, C, Lsb0>>>,
+    /// The low bit of each lane that differs in the current word, and has
+    /// not been returned yet.
+    differences: usize,
+    /// The position of the first base in the current word.
+    start: usize,
+}
+
+impl<B: BitStore, C: BitStore> Iterator for Mismatches<'_, B, C> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.differences == 0 {
+            let (i, (a, b)) = self.words.next()?;
+            self.start = i * WORD / 2;
+            self.differences = lanes::differences(a.load_le(), b.load_le());
+        }
+        let lane = self.differences.trailing_zeros() as usize / 2;
+        self.differences &= self.differences - 1;
+        Some(self.start + lane)
+    }
+}
+
+impl<B: BitStore, C: BitStore> FusedIterator for Mismatches<'_, B, C> {}
+
+/// The state of Myers' algorithm for a pattern, in blocks of 64 rows.
+struct Myers {
+    /// For each base, the rows of the pattern that hold it.
+    masks: [Box<[u64]>; 4],
+    /// The positive vertical deltas in each block.
+    pv: Box<[u64]>,
+    /// The negative vertical deltas in each block.
+    mv: Box<[u64]>,
+    /// The value in the last row of each block.
+    scores: Box<[usize]>,
+    len: usize,
+}
+
+impl Myers {
+    fn new<B: BitStore>(pattern: &Sequence<B>) -> Self {
+        let words = pattern.len().div_ceil(64);
+        let mut masks = [(); 4].map(|_| vec![0; words].into_boxed_slice());
+        for (i, base) in pattern.bases().enumerate() {
+            masks[base as usize][i / 64] |= 1 << (i % 64);
+        }
+        Self {
+            masks,
+            pv: vec![u64::MAX; words].into_boxed_slice(),
+            mv: vec![0; words].into_boxed_slice(),
+            scores: vec![0; words].into_boxed_slice(),
+            len: pattern.len(),
+        }
+    }
+
+    /// Returns the number of rows in block `b`.
+    fn rows(&self, b: usize) -> usize {
+        (self.len - b * 64).min(64)
+    }
+
+    /// Resets block `b` so that each value is one more than the one above.
+    fn reset(&mut self, b: usize) {
+        let above = if b == 0 { 0 } else { self.scores[b - 1] };
+        self.pv[b] = u64::MAX;
+        self.mv[b] = 0;
+        self.scores[b] = above + self.rows(b);
+    }
+
+    /// Advances block `b` by one column for `base`, given the horizontal
+    /// delta entering its top row, and returns the delta leaving its last
+    /// row.
+    fn advance(&mut self, b: usize, base: usize, h_in: i8) -> i8 {
+        let (pv, mv) = (self.pv[b], self.mv[b]);
+        let mut eq = self.masks[base][b];
+        let xv = eq | mv;
+        if h_in < 0 {
+            eq |= 1;
+        }
+        let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+        let mut ph = mv | !(xh | pv);
+        let mut mh = pv & xh;
+
+        let last = 1 << (self.rows(b) - 1);
+        let h_out = if ph & last != 0 {
+            1
+        } else if mh & last != 0 {
+            -1
+        } else {
+            0
+        };
+
+        ph <<= 1;
+        mh <<= 1;
+        match h_in {
+            ..0 => mh |= 1,
+            1.. => ph |= 1,
+            0 => {}
+        }
+        self.pv[b] = mh | !(xv | ph);
+        self.mv[b] = ph & xv;
+        self.scores[b] = self.scores[b].wrapping_add_signed(h_out as isize);
+        h_out
+    }
+
+    /// Computes the distance between the pattern and `text`, giving up
+    /// once it must exceed `max`.
+    fn distance<C: BitStore>(mut self, text: &Sequence<C>, max: Option<usize>) -> Option<usize> {
+        let (m, n) = (self.len, text.len());
+        if max.is_some_and(|max| m.abs_diff(n) > max) {
+            return None;
+        }
+        if m == 0 {
+            return Some(n);
+        }
+
+        let blocks = self.scores.len();
+        // the number of blocks to compute after `j` columns: values in rows
+        // more than `max` below the diagonal are all greater than `max`
+        let needed = |j: usize| max.map_or(blocks, |max| (j + max).div_ceil(64).min(blocks));
+        let mut active = needed(0);
+        for b in 0..active {
+            self.reset(b);
+        }
+
+        for (j, base) in text.bases().enumerate() {
+            let j = j + 1;
+            while active < needed(j) {
+                self.reset(active);
+                active += 1;
+            }
+            // the top row holds the column index, so always increases
+            let mut h = 1;
+            for b in 0..active {
+                h = self.advance(b, base as usize, h);
+            }
+            if let Some(max) = max
+                && active == blocks
+                && self.scores[blocks - 1] > max + (n - j)
+            {
+                return None;
+            }
+        }
+
+        let score = self.scores[blocks - 1];
+        max.is_none_or(|max| score <= max).then_some(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test::random_ascii;
+
+    /// The textbook dynamic programming edit distance.
+    fn naive(a: &[u8], b: &[u8]) -> usize {
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, &x) in a.iter().enumerate() {
+            let mut diag = row[0];
+            row[0] = i + 1;
+            for (j, &y) in b.iter().enumerate() {
+                let next = (diag + usize::from(x != y))
+                    .min(row[j] + 1)
+                    .min(row[j + 1] + 1);
+                diag = row[j + 1];
+                row[j + 1] = next;
+            }
+        }
+        row[b.len()]
+    }
+
+    fn mutate(seed: usize, ascii: &[u8], edits: usize) -> Vec<u8> {
+        let mut out = ascii.to_vec();
+        for e in 0..edits {
+            let r = (seed + 1) * 2_654_435_761 + e * 40_503;
+            let pos = r % (out.len() + 1);
+            match r / 7 % 3 {
+                0 if pos < out.len() => out[pos] = b"ACGT"[r / 13 % 4],
+                1 if pos < out.len() => {
+                    out.remove(pos);
+                }
+                _ => out.insert(pos, b"ACGT"[r / 13 % 4]),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn hamming_distance() {
+        let empty = Sequence::<u8>::new();
+        assert_eq!(empty.hamming_distance(&empty), 0);
+        assert_eq!(empty.mismatches(&empty).next(), None);
+
+        for (seed, len) in [(0, 1), (1, 31), (2, 32), (3, 33), (4, 200), (5, 1000)] {
+            let a = random_ascii(len, seed as u64).into_bytes();
+            let b = random_ascii(len, seed as u64 + 100).into_bytes();
+            let expected: Vec<usize> = (0..len).filter(|&i| a[i] != b[i]).collect();
+            let a = Sequence::<u16>::try_from_ascii(&a).unwrap();
+            let b = Sequence::<u64>::try_from_ascii(&b).unwrap();
+            assert_eq!(a.hamming_distance(&b), expected.len());
+            assert_eq!(b.hamming_distance(&a), expected.len());
+            assert_eq!(a.hamming_distance(&a), 0);
+            assert!(a.mismatches(&b).eq(expected.iter().copied()));
+            assert!(b.mismatches(&a).eq(expected));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn hamming_distance_length_mismatch() {
+        let a = Sequence::<u8>::try_from("GATTACA").unwrap();
+        let b = Sequence::<u8>::try_from("GATTAC").unwrap();
+        a.hamming_distance(&b);
+    }
+
+    #[test]
+    fn edit_distance() {
+        let empty = Sequence::<u8>::new();
+        let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        assert_eq!(empty.edit_distance(&empty), 0);
+        assert_eq!(empty.edit_distance(&seq), 7);
+        assert_eq!(seq.edit_distance(&empty), 7);
+        assert_eq!(seq.edit_distance(&seq), 0);
+
+        for (seed, len) in [(0, 10), (1, 63), (2, 64), (3, 65), (4, 200), (5, 300)] {
+            let a = random_ascii(len, seed as u64).into_bytes();
+            let b = mutate(seed, &a, len / 5);
+            let expected = naive(&a, &b);
+            let a = Sequence::<u16>::try_from_ascii(&a).unwrap();
+            let b = Sequence::<u64>::try_from_ascii(&b).unwrap();
+            assert_eq!(a.edit_distance(&b), expected);
+            assert_eq!(b.edit_distance(&a), expected);
+        }
+    }
+
+    #[test]
+    fn edit_distance_within() {
+        for (seed, len) in [(0, 10), (1, 70), (2, 130), (3, 300), (4, 500)] {
+            let a = random_ascii(len, seed as u64).into_bytes();
+            let b = mutate(seed, &a, len / 8 + 1);
+            let expected = naive(&a, &b);
+            let a = Sequence::<u32>::try_from_ascii(&a).unwrap();
+            let b = Sequence::<u8>::try_from_ascii(&b).unwrap();
+            for max in [
+                0,
+                1,
+                expected.saturating_sub(1),
+                expected,
+                expected + 1,
+                100,
+            ] {
+                let within = (expected <= max).then_some(expected);
+                assert_eq!(a.edit_distance_within(&b, max), within);
+                assert_eq!(b.edit_distance_within(&a, max), within);
+            }
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Finding low-complexity regions with the symmetric DUST algorithm.
+//!
+//! SDUST (Morgulis et al., 2006) scores each substring by how often its
+//! triplets repeat: a substring with `c_t` copies of each triplet `t` and `l`
+//! triplets in total scores `sum(c_t * (c_t - 1) / 2) / (l - 1)`. Substrings
+//! that score above a threshold, and contain no higher-scoring prefix or
+//! suffix, are *perfect intervals*. Every window of a fixed length is
+//! searched for perfect intervals, and their union is reported.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::{MaskedSequence, Sequence, SmallKmerIter};
+
+/// The number of distinct triplets.
+const TRIPLETS: usize = 64;
+
+/// A perfect interval within the current window.
+#[derive(Debug, Clone)]
+struct Perfect {
+    range: Range<usize>,
+    /// The sum of `c_t * (c_t - 1) / 2` over the triplets in the interval.
+    repeats: usize,
+    /// One less than the number of triplets in the interval.
+    len: usize,
+}
+
+/// The state of SDUST over one run of bases.
+#[derive(Debug)]
+struct Sdust {
+    window: usize,
+    /// The score threshold, multiplied by 10.
+    threshold: usize,
+    /// The triplets in the current window.
+    triplets: VecDeque<u8>,
+    /// The number of copies of each triplet in the window.
+    window_counts: [usize; TRIPLETS],
+    window_repeats: usize,
+    /// The number of copies of each triplet in the longest suffix of the
+    /// window in which no triplet is repeated too often to be skipped.
+    suffix_counts: [usize; TRIPLETS],
+    suffix_repeats: usize,
+    suffix_len: usize,
+    /// Perfect intervals that may still be extended, in decreasing order of
+    /// start.
+    perfect: Vec<Perfect>,
+}
+
+impl Sdust {
+    fn new(window: usize, threshold: u32) -> Self {
+        Self {
+            window,
+            threshold: threshold as usize,
+            triplets: VecDeque::with_capacity(window),
+            window_counts: [0; TRIPLETS],
+            window_repeats: 0,
+            suffix_counts: [0; TRIPLETS],
+            suffix_repeats: 0,
+            suffix_len: 0,
+            perfect: Vec::new(),
+        }
+    }
+
+    /// Finds the low-complexity regions of a run of bases, adding `offset`
+    /// to their positions.
+    fn run<B: BitStore>(
+        mut self,
+        bits: &BitSlice<B, Lsb0>,
+        offset: usize,
+        regions: &mut Vec<Range<usize>>,
+    ) {
+        for (i, triplet) in SmallKmerIter::<3, B>::new(bits).enumerate() {
+            let start = offset + (i + 3).saturating_sub(self.window);
+            self.save(start, regions);
+            self.shift(triplet.as_masked() as u8);
+            if self.window_repeats * 10 > self.suffix_len * self.threshold {
+                self.find_perfect(start);
+            }
+        }
+
+        let mut start = offset + (bits.len() / 2 + 1).saturating_sub(self.window);
+        while !self.perfect.is_empty() {
+            self.save(start, regions);
+            start += 1;
+        }
+    }
+
+    /// Slides the window over the next triplet.
+    fn shift(&mut self, triplet: u8) {
+        if self.triplets.len() > self.window - 3 {
+            let old = self.triplets.pop_front().expect("window is not empty") as usize;
+            self.window_counts[old] -= 1;
+            self.window_repeats -= self.window_counts[old];
+            if self.suffix_len > self.triplets.len() {
+                self.suffix_len -= 1;
+                self.suffix_counts[old] -= 1;
+                self.suffix_repeats -= self.suffix_counts[old];
+            }
+        }
+
+        let t = triplet as usize;
+        self.triplets.push_back(triplet);
+        self.suffix_len += 1;
+        self.window_repeats += self.window_counts[t];
+        self.window_counts[t] += 1;
+        self.suffix_repeats += self.suffix_counts[t];
+        self.suffix_counts[t] += 1;
+
+        // shrink the suffix until it has few enough copies of the new triplet
+        if self.suffix_counts[t] * 10 > self.threshold * 2 {
+            loop {
+                let s = self.triplets[self.triplets.len() - self.suffix_len] as usize;
+                self.suffix_counts[s] -= 1;
+                self.suffix_repeats -= self.suffix_counts[s];
+                self.suffix_len -= 1;
+                if s == t {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Records the perfect intervals that end at the last triplet of the
+    /// window, which starts at base `start`.
+    fn find_perfect(&mut self, start: usize) {
+        let mut counts = self.suffix_counts;
+        let mut repeats = self.suffix_repeats;
+        let (mut max_repeats, mut max_len) = (0, 0);
+        let end = start + self.triplets.len() + 2;
+
+        for i in (0..self.triplets.len() - self.suffix_len).rev() {
+            let t = self.triplets[i] as usize;
+            repeats += counts[t];
+            counts[t] += 1;
+            let len = self.triplets.len() - i - 1;
+            if repeats * 10 <= self.threshold * len {
+                continue;
+            }
+
+            // the best score of the intervals that start no earlier
+            let mut j = 0;
+            while let Some(p) = self.perfect.get(j)
+                && p.range.start >= start + i
+            {
+                if max_repeats == 0 || p.repeats * max_len > max_repeats * p.len {
+                    (max_repeats, max_len) = (p.repeats, p.len);
+                }
+                j += 1;
+            }
+            if max_repeats == 0 || repeats * max_len >= max_repeats * len {
+                (max_repeats, max_len) = (repeats, len);
+                let range = start + i..end;
+                self.perfect.insert(
+                    j,
+                    Perfect {
+                        range,
+                        repeats,
+                        len,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Reports the perfect interval that starts first, if it starts before
+    /// the window at `start`, and forgets every interval that does.
+    fn save(&mut self, start: usize, regions: &mut Vec<Range<usize>>) {
+        let Some(p) = self.perfect.last() else {
+            return;
+        };
+        if p.range.start >= start {
+            return;
+        }
+        match regions.last_mut() {
+            Some(last) if p.range.start <= last.end => last.end = last.end.max(p.range.end),
+            _ => regions.push(p.range.clone()),
+        }
+        while self.perfect.last().is_some_and(|p| p.range.start < start) {
+            self.perfect.pop();
+        }
+    }
+}
+
+/// Checks the arguments to SDUST.
+fn check_window(window: usize) {
+    assert!(
+        window >= 3,
+        "SDUST window must be at least 3 bases, not {window}"
+    );
+}
+
+impl<B: BitStore> Sequence<B> {
+    /// Finds the low-complexity regions of the sequence with the SDUST
+    /// algorithm, returning them as sorted, disjoint ranges of positions.
+    ///
+    /// `window` is the length of the windows searched, and `threshold` is the
+    /// score above which a region is masked, multiplied by 10. The usual
+    /// values, also used by `dustmasker` and minimap2, are 64 and 20.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is less than 3.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from(
+    ///     "GATTACAGCTTGACCGTA\
+    ///      CACACACACACACACACACACACACACACA\
+    ///      TGCGGATCAAGTCCTAGC",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(seq.low_complexity(64, 20), [17..48]);
+    /// ```
+    pub fn low_complexity(&self, window: usize, threshold: u32) -> Vec<Range<usize>> {
+        check_window(window);
+        let mut regions = Vec::new();
+        Sdust::new(window, threshold).run(&self.store, 0, &mut regions);
+        regions
+    }
+}
+
+impl<B: BitStore> MaskedSequence<B> {
+    /// Finds the low-complexity regions of the sequence with the SDUST
+    /// algorithm.
+    ///
+    /// See [`Sequence::low_complexity`]. Each run of unambiguous bases is
+    /// searched independently, so regions never span an ambiguous position.
+    /// Soft-masked bases are searched like any other.
+    pub fn low_complexity(&self, window: usize, threshold: u32) -> Vec<Range<usize>> {
+        check_window(window);
+        let mut regions = Vec::new();
+        for run in self.valid_runs() {
+            let bits = &self.sequence().store[run.start * 2..run.end * 2];
+            Sdust::new(window, threshold).run(bits, run.start, &mut regions);
+        }
+        regions
+    }
+
+    /// Soft-masks the low-complexity regions found by
+    /// [`low_complexity`](Self::low_complexity), and returns them.
+    ///
+    /// K-mer iterators skip the masked regions if enabled with
+    /// [`MaskedKmerIter::skip_soft_masked`](super::MaskedKmerIter::skip_soft_masked).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sequence::MaskedSequence;
+    ///
+    /// let mut seq = MaskedSequence::<usize>::from_ascii(b"GATTACAAAAAAAAAAAAAAAAAAAAGATTACA");
+    /// assert_eq!(seq.soft_mask_low_complexity(64, 20), [6..26]);
+    ///
+    /// let kmers = seq.kmers::<5>().skip_soft_masked(true);
+    /// assert!(kmers.map(|(i, _)| i).eq([0, 1, 26, 27, 28]));
+    /// ```
+    pub fn soft_mask_low_complexity(&mut self, window: usize, threshold: u32) -> Vec<Range<usize>> {
+        let regions = self.low_complexity(window, threshold);
+        for region in &regions {
+            self.soft_mask(region.clone());
+        }
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::*;
+
+    fn random(len: usize, seed: u64) -> String {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                ["A", "C", "G", "T"][(state >> 62) as usize]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn low_complexity() {
+        let seq = Sequence::<usize>::try_from(random(1000, 1).as_str()).unwrap();
+        assert!(seq.low_complexity(64, 20).is_empty());
+        assert!(Sequence::<usize>::new().low_complexity(64, 20).is_empty());
+
+        let seq = Sequence::<u8>::try_from("A".repeat(200).as_str()).unwrap();
+        let regions = seq.low_complexity(64, 20);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0], 0..200);
+
+        // repeats of different periods, separated by random sequence
+        let s = [
+            random(100, 2),
+            "GATC".repeat(15),
+            random(100, 3),
+            "ACGTTGCA".repeat(20),
+            random(100, 4),
+        ]
+        .concat();
+        let seq = Sequence::<u16>::try_from(s.as_str()).unwrap();
+        let regions = seq.low_complexity(64, 20);
+        assert_eq!(regions.len(), 2);
+        assert!(regions[0].start <= 100 && regions[0].end >= 160);
+        assert!(regions[0].end - regions[0].start < 70);
+        assert!(regions[1].start <= 260 && regions[1].end >= 420);
+        assert!(regions[1].end - regions[1].start < 170);
+        assert!(regions.windows(2).all(|w| w[0].end < w[1].start));
+
+        // a higher threshold masks less
+        let strict = seq.low_complexity(64, 40);
+        let masked = |regions: &[Range<usize>]| regions.iter().map(|r| r.len()).sum::<usize>();
+        assert!(masked(&strict) <= masked(&regions));
+    }
+
+    #[test]
+    fn ambiguous() {
+        let ascii = [
+            random(50, 5),
+            "T".repeat(30),
+            "N".into(),
+            "T".repeat(30),
+            random(50, 6),
+        ]
+        .concat();
+        let mut seq = MaskedSequence::<usize>::from_ascii(ascii.as_bytes());
+        let regions = seq.soft_mask_low_complexity(64, 20);
+        assert_eq!(regions, [50..80, 81..111]);
+        assert!(seq.masked_regions().eq(regions));
+
+        let mut kmers = seq.kmers::<11>().skip_soft_masked(true);
+        assert!(kmers.all(|(i, _)| i + 11 <= 50 || i >= 111));
+    }
+}
@@ -0,0 +1,328 @@
+//! Bulk encoding of ASCII bases into packed 2-bit words.
+//!
+//! Input is encoded 32 characters at a time with the widest instructions
+//! available: AVX2 or SSSE3 on x86-64 (detected at runtime) and NEON on
+//! AArch64, falling back to a lookup table elsewhere. Base `i` of each chunk
+//! is packed into bits `2i` and `2i + 1` of a `u64`, matching the layout of
+//! [`Sequence`](super::Sequence).
+
+use crate::base::Base;
+
+/// The number of characters encoded at a time.
+pub(super) const CHUNK: usize = 32;
+
+/// Marks characters that are not bases in [`CODES`].
+const INVALID: u8 = 0xff;
+
+/// The 2-bit code of every ASCII character, or [`INVALID`].
+const CODES: [u8; 256] = {
+    let mut codes = [INVALID; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        if let Some(base) = Base::from_ascii(byte as u8) {
+            codes[byte] = base as u8;
+        }
+        byte += 1;
+    }
+    codes
+};
+
+/// The 2-bit code of each base, indexed by the low nibble of its ASCII
+/// character, which is the same in both cases.
+const NIBBLE_CODES: [u8; 16] = {
+    let mut codes = [0; 16];
+    codes[(b'A' & 0xf) as usize] = Base::A as u8;
+    codes[(b'C' & 0xf) as usize] = Base::C as u8;
+    codes[(b'G' & 0xf) as usize] = Base::G as u8;
+    codes[(b'T' & 0xf) as usize] = Base::T as u8;
+    codes
+};
+
+/// Encodes a full chunk, or returns the offset of its first invalid
+/// character.
+type Kernel = fn(&[u8; CHUNK]) -> Result<u64, usize>;
+
+/// Encodes `ascii`, passing each word of packed bases and the number of bases
+/// in it to `emit`.
+///
+/// Stops at the first character that is not a base, after emitting every
+/// base before it, and returns its offset.
+pub(super) fn encode(ascii: &[u8], mut emit: impl FnMut(u64, usize)) -> Result<(), usize> {
+    let kernel = kernel();
+    let mut chunks = ascii.chunks_exact(CHUNK);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let chunk = chunk.try_into().expect("chunk has CHUNK characters");
+        match kernel(chunk) {
+            Ok(word) => emit(word, CHUNK),
+            Err(offset) => {
+                // the kernel has checked that the prefix is valid
+                if offset > 0 {
+                    emit(pack_scalar(&chunk[..offset]).unwrap_or_default(), offset);
+                }
+                return Err(i * CHUNK + offset);
+            }
+        }
+    }
+
+    let rest = chunks.remainder();
+    let start = ascii.len() - rest.len();
+    match pack_scalar(rest) {
+        Ok(word) => {
+            if !rest.is_empty() {
+                emit(word, rest.len());
+            }
+            Ok(())
+        }
+        Err(offset) => {
+            if offset > 0 {
+                emit(pack_scalar(&rest[..offset]).unwrap_or_default(), offset);
+            }
+            Err(start + offset)
+        }
+    }
+}
+
+/// Detects an x86 CPU feature at runtime, or without `std`, from the target
+/// features enabled at compile time.
+#[cfg(target_arch = "x86_64")]
+macro_rules! has_x86_feature {
+    ($feature:tt) => {{
+        #[cfg(feature = "std")]
+        let detected = std::is_x86_feature_detected!($feature);
+        #[cfg(not(feature = "std"))]
+        let detected = cfg!(target_feature = $feature);
+        detected
+    }};
+}
+
+/// Selects the fastest kernel supported by the running CPU.
+#[cfg(target_arch = "x86_64")]
+fn kernel() -> Kernel {
+    if has_x86_feature!("avx2") {
+        // SAFETY: AVX2 is available.
+        return |chunk| unsafe { x86::pack_avx2(chunk) };
+    }
+    if has_x86_feature!("ssse3") {
+        // SAFETY: SSSE3 is available.
+        return |chunk| unsafe { x86::pack_ssse3(chunk) };
+    }
+    pack_scalar_chunk
+}
+
+/// Selects the NEON kernel, which is part of the AArch64 baseline.
+#[cfg(target_arch = "aarch64")]
+fn kernel() -> Kernel {
+    // SAFETY: NEON is always available on AArch64.
+    |chunk| unsafe { neon::pack(chunk) }
+}
+
+/// Selects the lookup table kernel, on targets without a SIMD kernel.
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn kernel() -> Kernel {
+    pack_scalar_chunk
+}
+
+#[cfg(any(test, not(target_arch = "aarch64")))]
+fn pack_scalar_chunk(chunk: &[u8; CHUNK]) -> Result<u64, usize> {
+    pack_scalar(chunk)
+}
+
+/// Encodes up to [`CHUNK`] characters with a lookup table.
+fn pack_scalar(ascii: &[u8]) -> Result<u64, usize> {
+    debug_assert!(ascii.len() <= CHUNK);
+    let mut word = 0;
+    for (i, &byte) in ascii.iter().enumerate() {
+        let code = CODES[byte as usize];
+        if code == INVALID {
+            return Err(i);
+        }
+        word |= (code as u64) << (2 * i);
+    }
+    Ok(word)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    use super::{CHUNK, NIBBLE_CODES};
+
+    /// Returns a mask with a set byte for each `A`, `C`, `G` or `T` in
+    /// either case.
+    #[target_feature(enable = "ssse3")]
+    fn valid_128(chars: __m128i) -> __m128i {
+        let upper = _mm_and_si128(chars, _mm_set1_epi8(!0x20));
+        let a = _mm_cmpeq_epi8(upper, _mm_set1_epi8(b'A' as i8));
+        let c = _mm_cmpeq_epi8(upper, _mm_set1_epi8(b'C' as i8));
+        let g = _mm_cmpeq_epi8(upper, _mm_set1_epi8(b'G' as i8));
+        let t = _mm_cmpeq_epi8(upper, _mm_set1_epi8(b'T' as i8));
+        _mm_or_si128(_mm_or_si128(a, c), _mm_or_si128(g, t))
+    }
+
+    /// Packs the 2-bit codes of 16 valid characters into 32 bits.
+    #[target_feature(enable = "ssse3")]
+    fn pack_128(chars: __m128i) -> u32 {
+        // SAFETY: the table is 16 bytes.
+        let table = unsafe { _mm_loadu_si128(NIBBLE_CODES.as_ptr().cast()) };
+        let codes = _mm_shuffle_epi8(table, _mm_and_si128(chars, _mm_set1_epi8(0xf)));
+        // `c0 + 4 * c1` in each 16-bit lane, then `+ 16 * (c2 + 4 * c3)` in
+        // each 32-bit lane
+        let pairs = _mm_maddubs_epi16(codes, _mm_set1_epi16(0x0401));
+        let quads = _mm_madd_epi16(pairs, _mm_set1_epi32(0x0010_0001));
+        let bytes = _mm_shuffle_epi8(
+            quads,
+            _mm_setr_epi8(0, 4, 8, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1),
+        );
+        _mm_cvtsi128_si32(bytes) as u32
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub(super) fn pack_ssse3(chunk: &[u8; CHUNK]) -> Result<u64, usize> {
+        // SAFETY: the chunk is 32 bytes, and unaligned loads are allowed.
+        let (lo, hi) = unsafe {
+            let ptr = chunk.as_ptr().cast::<__m128i>();
+            (_mm_loadu_si128(ptr), _mm_loadu_si128(ptr.add(1)))
+        };
+        let valid = (_mm_movemask_epi8(valid_128(lo)) as u32 & 0xffff)
+            | ((_mm_movemask_epi8(valid_128(hi)) as u32) << 16);
+        if valid != u32::MAX {
+            return Err((!valid).trailing_zeros() as usize);
+        }
+        Ok(pack_128(lo) as u64 | (pack_128(hi) as u64) << 32)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) fn pack_avx2(chunk: &[u8; CHUNK]) -> Result<u64, usize> {
+        // SAFETY: the chunk is 32 bytes, and unaligned loads are allowed.
+        let chars = unsafe { _mm256_loadu_si256(chunk.as_ptr().cast()) };
+        let upper = _mm256_and_si256(chars, _mm256_set1_epi8(!0x20));
+        let a = _mm256_cmpeq_epi8(upper, _mm256_set1_epi8(b'A' as i8));
+        let c = _mm256_cmpeq_epi8(upper, _mm256_set1_epi8(b'C' as i8));
+        let g = _mm256_cmpeq_epi8(upper, _mm256_set1_epi8(b'G' as i8));
+        let t = _mm256_cmpeq_epi8(upper, _mm256_set1_epi8(b'T' as i8));
+        let valid = _mm256_or_si256(_mm256_or_si256(a, c), _mm256_or_si256(g, t));
+        let valid = _mm256_movemask_epi8(valid) as u32;
+        if valid != u32::MAX {
+            return Err((!valid).trailing_zeros() as usize);
+        }
+
+        // SAFETY: the table is 16 bytes.
+        let table =
+            _mm256_broadcastsi128_si256(unsafe { _mm_loadu_si128(NIBBLE_CODES.as_ptr().cast()) });
+        let codes = _mm256_shuffle_epi8(table, _mm256_and_si256(chars, _mm256_set1_epi8(0xf)));
+        let pairs = _mm256_maddubs_epi16(codes, _mm256_set1_epi16(0x0401));
+        let quads = _mm256_madd_epi16(pairs, _mm256_set1_epi32(0x0010_0001));
+        // gather the low byte of each 32-bit lane into the low 4 bytes of
+        // each 128-bit half
+        let bytes = _mm256_shuffle_epi8(
+            quads,
+            _mm256_setr_epi8(
+                0, 4, 8, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, //
+                0, 4, 8, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+            ),
+        );
+        let lo = _mm256_extract_epi32::<0>(bytes) as u32;
+        let hi = _mm256_extract_epi32::<4>(bytes) as u32;
+        Ok(lo as u64 | (hi as u64) << 32)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use core::arch::aarch64::*;
+
+    use super::{CHUNK, NIBBLE_CODES, pack_scalar};
+
+    /// Returns a mask with a set byte for each `A`, `C`, `G` or `T` in
+    /// either case.
+    #[target_feature(enable = "neon")]
+    fn valid_128(chars: uint8x16_t) -> uint8x16_t {
+        let upper = vandq_u8(chars, vdupq_n_u8(!0x20));
+        let a = vceqq_u8(upper, vdupq_n_u8(b'A'));
+        let c = vceqq_u8(upper, vdupq_n_u8(b'C'));
+        let g = vceqq_u8(upper, vdupq_n_u8(b'G'));
+        let t = vceqq_u8(upper, vdupq_n_u8(b'T'));
+        vorrq_u8(vorrq_u8(a, c), vorrq_u8(g, t))
+    }
+
+    /// Packs the 2-bit codes of 16 valid characters into 32 bits.
+    #[target_feature(enable = "neon")]
+    fn pack_128(chars: uint8x16_t) -> u32 {
+        // SAFETY: the table is 16 bytes.
+        let table = unsafe { vld1q_u8(NIBBLE_CODES.as_ptr()) };
+        let codes = vqtbl1q_u8(table, vandq_u8(chars, vdupq_n_u8(0xf)));
+        // `c0 | c1 << 2` in each 16-bit lane, then `| (c2 | c3 << 2) << 4`
+        // in each 32-bit lane
+        let pairs = vreinterpretq_u16_u8(codes);
+        let pairs = vandq_u16(vsraq_n_u16::<6>(pairs, pairs), vdupq_n_u16(0xf));
+        let quads = vreinterpretq_u32_u16(pairs);
+        let quads = vandq_u32(vsraq_n_u32::<12>(quads, quads), vdupq_n_u32(0xff));
+        let bytes = vmovn_u16(vcombine_u16(vmovn_u32(quads), vdup_n_u16(0)));
+        vget_lane_u32::<0>(vreinterpret_u32_u8(bytes))
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) fn pack(chunk: &[u8; CHUNK]) -> Result<u64, usize> {
+        // SAFETY: the chunk is 32 bytes.
+        let (lo, hi) = unsafe { (vld1q_u8(chunk.as_ptr()), vld1q_u8(chunk.as_ptr().add(16))) };
+        if vminvq_u8(vandq_u8(valid_128(lo), valid_128(hi))) != u8::MAX {
+            // there is no cheap movemask, so find the offset with the table
+            return pack_scalar(chunk);
+        }
+        Ok(pack_128(lo) as u64 | (pack_128(hi) as u64) << 32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_all(ascii: &[u8]) -> (Vec<Base>, Result<(), usize>) {
+        let mut bases = Vec::new();
+        let result = encode(ascii, |word, n| {
+            bases.extend((0..n).map(|i| Base::try_from((word >> (2 * i)) as u8 & 0b11).unwrap()));
+        });
+        (bases, result)
+    }
+
+    #[test]
+    fn kernels() {
+        let mut kernels: Vec<Kernel> = vec![kernel(), pack_scalar_chunk];
+        #[cfg(target_arch = "x86_64")]
+        if std::is_x86_feature_detected!("ssse3") {
+            kernels.push(|chunk| unsafe { x86::pack_ssse3(chunk) });
+        }
+
+        let chunk: [u8; CHUNK] = *b"GATTACAgattacaCCGGTTAAcgtaACGTtg";
+        let expected = pack_scalar(&chunk).unwrap();
+        assert_eq!(expected & 0xff, 0b10_10_01_11);
+        for kernel in kernels {
+            assert_eq!(kernel(&chunk), Ok(expected));
+            for i in [0, 5, 15, 16, 31] {
+                let mut invalid = chunk;
+                invalid[i] = [b'N', b'\n', b'a' + 1, 0xc1][i % 4];
+                assert_eq!(kernel(&invalid), Err(i));
+            }
+        }
+    }
+
+    #[test]
+    fn encode_ascii() {
+        let ascii: Vec<u8> = (0..1000).map(|i| b"ACGTacgt"[i * 7 % 11 % 8]).collect();
+        let (bases, result) = encode_all(&ascii);
+        assert_eq!(result, Ok(()));
+        assert!(
+            bases
+                .into_iter()
+                .eq(ascii.iter().map(|&b| Base::from_ascii(b).unwrap()))
+        );
+
+        for i in [0, 31, 32, 500, 990, 999] {
+            let mut invalid = ascii.clone();
+            invalid[i] = b'N';
+            let (bases, result) = encode_all(&invalid);
+            assert_eq!(result, Err(i));
+            assert_eq!(bases.len(), i);
+        }
+    }
+}
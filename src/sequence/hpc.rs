@@ -0,0 +1,119 @@
+use core::iter::FusedIterator;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::Bases;
+use crate::Base;
+use crate::small::Kmer;
+
+/// An iterator over the k-mers of the homopolymer-compressed form of a
+/// sequence.
+///
+/// Created with
+/// [`Sequence::hpc_kmers`](super::Sequence::hpc_kmers).
+#[derive(Debug)]
+pub struct HpcKmerIter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    /// The index of the next base.
+    pos: usize,
+    /// The last base read, which any repeats of are skipped.
+    last: Option<Base>,
+    kmer: Kmer<K>,
+    /// The original index of each base in the k-mer, indexed by its
+    /// compressed index modulo `K`.
+    starts: [usize; K],
+    /// The number of compressed bases read.
+    len: usize,
+}
+
+impl<'a, const K: usize, B: BitStore> HpcKmerIter<'a, K, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self {
+            bases: Bases::new(bits),
+            pos: 0,
+            last: None,
+            kmer: Kmer::new(),
+            starts: [0; K],
+            len: 0,
+        }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for HpcKmerIter<'a, K, B> {
+    type Item = (usize, Kmer<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let base = self.bases.next()?;
+            self.pos += 1;
+            if self.last == Some(base) {
+                continue;
+            }
+            self.last = Some(base);
+            self.kmer.push(base);
+            self.starts[self.len % K] = self.pos - 1;
+            self.len += 1;
+            if self.len >= K {
+                return Some((self.starts[self.len % K], self.kmer));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every remaining base may start a new run
+        let remaining = self.bases.len() + self.len.min(K - 1);
+        (0, Some(remaining.saturating_sub(K - 1)))
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for HpcKmerIter<'a, K, B> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sequence;
+    use crate::utils::test::random_ascii;
+
+    /// Compresses `ascii`, along with the original index of each base.
+    fn compress(ascii: &[u8]) -> (Vec<u8>, Vec<usize>) {
+        let mut bases = Vec::new();
+        let mut starts = Vec::new();
+        for (i, &b) in ascii.iter().enumerate() {
+            if bases.last() != Some(&b) {
+                bases.push(b);
+                starts.push(i);
+            }
+        }
+        (bases, starts)
+    }
+
+    #[test]
+    fn hpc_kmers() {
+        let ascii = random_ascii(300, 0).into_bytes();
+        let seq = Sequence::<u16>::try_from_ascii(&ascii).unwrap();
+        let (compressed, starts) = compress(&ascii);
+
+        let expected: Vec<(usize, String)> = compressed
+            .windows(5)
+            .zip(starts)
+            .map(|(window, start)| (start, String::from_utf8(window.to_vec()).unwrap()))
+            .collect();
+        let kmers: Vec<(usize, String)> = seq
+            .hpc_kmers::<5>()
+            .map(|(i, kmer)| (i, kmer.to_string()))
+            .collect();
+        assert!(kmers.len() < 296);
+        assert_eq!(kmers, expected);
+    }
+
+    #[test]
+    fn short() {
+        let seq = Sequence::<u8>::try_from("AAAACCCCGGGG").unwrap();
+        assert_eq!(seq.hpc_kmers::<4>().count(), 0);
+        let kmers: Vec<_> = seq.hpc_kmers::<3>().collect();
+        assert_eq!(kmers.len(), 1);
+        assert_eq!(kmers[0].0, 0);
+        assert!(seq.hpc_kmers::<3>().size_hint().1 >= Some(1));
+    }
+}
@@ -0,0 +1,380 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use bitvec::field::BitField as _;
+use bitvec::order::Lsb0;
+use bitvec::slice::ChunksExact;
+use bitvec::store::BitStore;
+use bitvec::vec::BitVec;
+
+use super::{MaskedSequence, Sequence};
+use crate::base::{Base, Iupac, ParseBaseError};
+
+/// The number of bits used to store each code.
+const CODE_BITS: usize = 4;
+
+/// How [`IupacSequence::to_sequence`] resolves codes that are not a single
+/// base.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AmbiguityPolicy {
+    /// Fail at the first ambiguous code or gap.
+    Reject,
+    /// Replace every ambiguous code and gap with the given base.
+    Replace(Base),
+    /// Use the first base each ambiguous code allows, in the order `A`, `C`,
+    /// `G`, `T`. Gaps are still rejected.
+    First,
+}
+
+/// A nucleotide sequence of [IUPAC codes](Iupac), stored in 4 bits per
+/// position.
+///
+/// Unlike [`Sequence`], every position can hold an ambiguity code or a gap.
+/// A sequence of bases converts to an `IupacSequence` losslessly, and back
+/// with an [`AmbiguityPolicy`] or by [expanding](Self::expansions) each
+/// ambiguous position.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::sequence::{AmbiguityPolicy, IupacSequence};
+/// use helicase::Base;
+///
+/// let seq = IupacSequence::try_from_ascii(b"GATRYN").unwrap();
+/// assert_eq!(seq.ambiguous_count(), 3);
+/// assert_eq!(seq.reverse_complement().to_string(), "NRYATC");
+///
+/// assert!(seq.to_sequence::<usize>(AmbiguityPolicy::Reject).is_err());
+/// let replaced = seq.to_sequence::<usize>(AmbiguityPolicy::Replace(Base::A)).unwrap();
+/// assert_eq!(replaced.to_string(), "GATAAA");
+/// let first = seq.to_sequence::<usize>(AmbiguityPolicy::First).unwrap();
+/// assert_eq!(first.to_string(), "GATACA");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IupacSequence {
+    store: BitVec<u8, Lsb0>,
+}
+
+impl fmt::Display for IupacSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|code| fmt::Display::fmt(&code, f))
+    }
+}
+
+impl<B: BitStore> From<&Sequence<B>> for IupacSequence {
+    fn from(seq: &Sequence<B>) -> Self {
+        let mut iupac = Self::with_capacity(seq.len());
+        iupac.extend(seq.bases().map(Iupac::from));
+        iupac
+    }
+}
+
+impl Extend<Iupac> for IupacSequence {
+    fn extend<T: IntoIterator<Item = Iupac>>(&mut self, iter: T) {
+        for code in iter {
+            self.push(code);
+        }
+    }
+}
+
+impl FromIterator<Iupac> for IupacSequence {
+    fn from_iter<T: IntoIterator<Item = Iupac>>(iter: T) -> Self {
+        let mut seq = Self::new();
+        seq.extend(iter);
+        seq
+    }
+}
+
+impl IupacSequence {
+    /// Creates a new, empty sequence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty sequence with room for at least `capacity` codes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            store: BitVec::with_capacity(capacity * CODE_BITS),
+        }
+    }
+
+    /// Creates a sequence from IUPAC characters in either case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the position and value of the first character
+    /// that is not an IUPAC code.
+    pub fn try_from_ascii(ascii: &[u8]) -> Result<Self, ParseBaseError> {
+        let mut seq = Self::with_capacity(ascii.len());
+        for (i, &byte) in ascii.iter().enumerate() {
+            let code = Iupac::from_ascii(byte).ok_or(ParseBaseError::new(i, byte))?;
+            seq.push(code);
+        }
+        Ok(seq)
+    }
+
+    /// Returns the number of codes in the sequence.
+    pub fn len(&self) -> usize {
+        self.store.len() / CODE_BITS
+    }
+
+    /// Returns `true` if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Appends a code to the end of the sequence.
+    pub fn push(&mut self, code: Iupac) {
+        let len = self.store.len();
+        self.store.resize(len + CODE_BITS, false);
+        self.store[len..].store::<u8>(code.mask());
+    }
+
+    /// Returns the code at index `i`, or `None` if it is out of bounds.
+    pub fn get(&self, i: usize) -> Option<Iupac> {
+        let bits = self.store.get(i * CODE_BITS..(i + 1) * CODE_BITS)?;
+        Iupac::from_mask(bits.load::<u8>())
+    }
+
+    /// Returns an iterator over the codes in the sequence.
+    pub fn iter(&self) -> IupacIter<'_> {
+        IupacIter {
+            chunks: self.store.chunks_exact(CODE_BITS),
+        }
+    }
+
+    /// Returns the number of positions that allow more than one base.
+    pub fn ambiguous_count(&self) -> usize {
+        self.iter().filter(|code| code.is_ambiguous()).count()
+    }
+
+    /// Returns the reverse complement of the sequence.
+    pub fn reverse_complement(&self) -> Self {
+        self.iter().rev().map(Iupac::complement).collect()
+    }
+
+    /// Converts the sequence to bases, resolving ambiguous codes and gaps
+    /// with `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the position and character of the first code
+    /// that `policy` cannot resolve.
+    pub fn to_sequence<B: BitStore>(
+        &self,
+        policy: AmbiguityPolicy,
+    ) -> Result<Sequence<B>, ParseBaseError> {
+        let mut seq = Sequence::with_capacity(self.len());
+        for (i, code) in self.iter().enumerate() {
+            let base = match (code.to_base(), policy) {
+                (Some(base), _) => Some(base),
+                (None, AmbiguityPolicy::Replace(base)) => Some(base),
+                (None, AmbiguityPolicy::First) => code.bases().next(),
+                (None, AmbiguityPolicy::Reject) => None,
+            };
+            seq.push(base.ok_or(ParseBaseError::new(i, code.to_ascii()))?);
+        }
+        Ok(seq)
+    }
+
+    /// Converts the sequence to bases, marking every ambiguous code and gap
+    /// as an ambiguous position.
+    pub fn to_masked<B: BitStore>(&self) -> MaskedSequence<B> {
+        let mut seq = MaskedSequence::from(Sequence::with_capacity(self.len()));
+        for code in self.iter() {
+            match code.to_base() {
+                Some(base) => seq.push(base),
+                None => seq.push_ambiguous(),
+            }
+        }
+        seq
+    }
+
+    /// Returns the number of sequences of bases the sequence expands to, or
+    /// `None` if it overflows a `usize`.
+    ///
+    /// The count is 0 if the sequence contains a gap.
+    pub fn expansion_count(&self) -> Option<usize> {
+        self.iter().try_fold(1usize, |n, code| {
+            n.checked_mul(code.mask().count_ones() as usize)
+        })
+    }
+
+    /// Returns an iterator over every sequence of bases the sequence matches,
+    /// with the last position varying fastest.
+    ///
+    /// The number of sequences grows exponentially with the number of
+    /// ambiguous positions; check [`expansion_count`](Self::expansion_count)
+    /// first for untrusted input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sequence::IupacSequence;
+    ///
+    /// let seq = IupacSequence::try_from_ascii(b"GAYW").unwrap();
+    /// assert_eq!(seq.expansion_count(), Some(4));
+    /// let seqs: Vec<String> = seq.expansions::<usize>().map(|s| s.to_string()).collect();
+    /// assert_eq!(seqs, ["GACA", "GACT", "GATA", "GATT"]);
+    /// ```
+    pub fn expansions<B: BitStore>(&self) -> Expansions<'_, B> {
+        let choices: Vec<Vec<Base>> = self.iter().map(|code| code.bases().collect()).collect();
+        let done = choices.iter().any(Vec::is_empty);
+        Expansions {
+            indices: vec![0; choices.len()],
+            choices,
+            done,
+            _seq: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IupacSequence {
+    type Item = Iupac;
+    type IntoIter = IupacIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the codes in an [`IupacSequence`].
+///
+/// This struct is created by [`IupacSequence::iter`].
+#[derive(Debug, Clone)]
+pub struct IupacIter<'a> {
+    chunks: ChunksExact<'a, u8, Lsb0>,
+}
+
+impl Iterator for IupacIter<'_> {
+    type Item = Iupac;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks
+            .next()
+            .and_then(|bits| Iupac::from_mask(bits.load::<u8>()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IupacIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks
+            .next_back()
+            .and_then(|bits| Iupac::from_mask(bits.load::<u8>()))
+    }
+}
+
+impl ExactSizeIterator for IupacIter<'_> {}
+
+impl FusedIterator for IupacIter<'_> {}
+
+/// An iterator over the sequences of bases an [`IupacSequence`] matches.
+///
+/// This struct is created by [`IupacSequence::expansions`].
+#[derive(Debug, Clone)]
+pub struct Expansions<'a, B: BitStore> {
+    /// The bases each position allows.
+    choices: Vec<Vec<Base>>,
+    /// The index of the next base to use at each position.
+    indices: Vec<usize>,
+    done: bool,
+    _seq: core::marker::PhantomData<(&'a IupacSequence, B)>,
+}
+
+impl<B: BitStore> Iterator for Expansions<'_, B> {
+    type Item = Sequence<B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let seq = self
+            .indices
+            .iter()
+            .zip(&self.choices)
+            .map(|(&i, bases)| bases[i])
+            .collect();
+
+        // advance like an odometer, with the last position varying fastest
+        self.done = true;
+        for (i, bases) in self.indices.iter_mut().zip(&self.choices).rev() {
+            *i += 1;
+            if *i < bases.len() {
+                self.done = false;
+                break;
+            }
+            *i = 0;
+        }
+        Some(seq)
+    }
+}
+
+impl<B: BitStore> FusedIterator for Expansions<'_, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let ascii = b"ACGTRYSWKMBDHVN-";
+        let seq = IupacSequence::try_from_ascii(ascii).unwrap();
+        assert_eq!(seq.len(), 16);
+        assert_eq!(seq.to_string().as_bytes(), ascii);
+        assert_eq!(seq.get(4), Iupac::from_ascii(b'R'));
+        assert_eq!(seq.get(16), None);
+        assert_eq!(seq.ambiguous_count(), 11);
+        assert_eq!(seq.reverse_complement().to_string(), "-NBDHVKMWSRYACGT");
+
+        let err = IupacSequence::try_from_ascii(b"GATXA").unwrap_err();
+        assert_eq!((err.position(), err.byte()), (3, b'X'));
+
+        let bases = Sequence::<u16>::try_from_ascii(b"GATTACA").unwrap();
+        let iupac = IupacSequence::from(&bases);
+        assert_eq!(iupac.to_string(), "GATTACA");
+        assert_eq!(iupac.to_sequence::<u16>(AmbiguityPolicy::Reject), Ok(bases));
+    }
+
+    #[test]
+    fn policies() {
+        let seq = IupacSequence::try_from_ascii(b"GAT-KA").unwrap();
+        let err = seq
+            .to_sequence::<usize>(AmbiguityPolicy::Reject)
+            .unwrap_err();
+        assert_eq!((err.position(), err.byte()), (3, b'-'));
+        let err = seq
+            .to_sequence::<usize>(AmbiguityPolicy::First)
+            .unwrap_err();
+        assert_eq!(err.position(), 3);
+        let replaced = seq.to_sequence::<usize>(AmbiguityPolicy::Replace(Base::C));
+        assert_eq!(replaced.unwrap().to_string(), "GATCCA");
+
+        let masked = seq.to_masked::<u8>();
+        assert_eq!(masked.len(), 6);
+        assert!(masked.valid_runs().eq([0..3, 5..6]));
+    }
+
+    #[test]
+    fn expansions() {
+        let seq = IupacSequence::try_from_ascii(b"NAB").unwrap();
+        assert_eq!(seq.expansion_count(), Some(12));
+        let all: Vec<String> = seq.expansions::<u8>().map(|s| s.to_string()).collect();
+        assert_eq!(all.len(), 12);
+        assert_eq!((all[0].as_str(), all[11].as_str()), ("AAC", "TAT"));
+
+        let gap = IupacSequence::try_from_ascii(b"A-A").unwrap();
+        assert_eq!(gap.expansion_count(), Some(0));
+        assert_eq!(gap.expansions::<u8>().count(), 0);
+
+        let empty = IupacSequence::new();
+        assert_eq!(empty.expansions::<u8>().count(), 1);
+        let huge: IupacSequence = core::iter::repeat_n(Iupac::N, 64).collect();
+        assert_eq!(huge.expansion_count(), None);
+    }
+}
@@ -0,0 +1,501 @@
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use bitvec::field::BitField;
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+use bitvec::vec::BitVec;
+
+use super::{MinimizerIter, Sequence, SmallKmerIter};
+use crate::small::Kmer;
+use crate::{Base, ParseBaseError};
+
+/// A sequence that records which of its positions were ambiguous (such as
+/// `N`) or soft-masked (lowercase) in the input.
+///
+/// Ambiguous positions are stored as `C` in the underlying [`Sequence`], and
+/// iterators over k-mers skip every window that overlaps one. Windows that
+/// overlap a soft-masked base are also skipped if enabled with
+/// [`MaskedKmerIter::skip_soft_masked`] or
+/// [`MaskedMinimizerIter::skip_soft_masked`].
+///
+/// # Examples
+///
+/// ```
+/// use helicase::sequence::MaskedSequence;
+///
+/// let seq = MaskedSequence::<usize>::from_ascii(b"GATNNACAG");
+/// assert_eq!(seq.len(), 9);
+/// assert!(!seq.is_valid(3));
+///
+/// let kmers: Vec<(usize, String)> = seq.kmers::<3>().map(|(i, k)| (i, k.to_string())).collect();
+/// assert_eq!(kmers, [(0, "GAT".to_string()), (5, "ACA".to_string()), (6, "CAG".to_string())]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskedSequence<B: BitStore = usize> {
+    seq: Sequence<B>,
+    /// One bit per base, set if the base is ambiguous.
+    invalid: BitVec<usize, Lsb0>,
+    /// One bit per base, set if the base was lowercase.
+    soft: BitVec<usize, Lsb0>,
+}
+
+impl<B: BitStore> From<Sequence<B>> for MaskedSequence<B> {
+    fn from(seq: Sequence<B>) -> Self {
+        Self {
+            invalid: BitVec::repeat(false, seq.len()),
+            soft: BitVec::repeat(false, seq.len()),
+            seq,
+        }
+    }
+}
+
+impl<B: BitStore> MaskedSequence<B> {
+    /// Creates a sequence from ASCII bases, marking every character that is
+    /// not `A`, `C`, `G` or `T` (in either case) as ambiguous, and every
+    /// lowercase character as soft-masked.
+    pub fn from_ascii(ascii: &[u8]) -> Self {
+        let mut seq = Self::from(Sequence::with_capacity(ascii.len()));
+        for &byte in ascii {
+            seq.push_ascii(byte);
+        }
+        seq
+    }
+
+    /// Appends an ASCII character to the end of the sequence, recording
+    /// whether it is ambiguous or soft-masked.
+    pub(super) fn push_ascii(&mut self, byte: u8) {
+        let base = Base::from_ascii(byte);
+        self.push_position(
+            base.unwrap_or(Base::C),
+            base.is_none(),
+            byte.is_ascii_lowercase(),
+        );
+    }
+
+    fn push_position(&mut self, base: Base, invalid: bool, soft: bool) {
+        self.seq.push(base);
+        self.invalid.push(invalid);
+        self.soft.push(soft);
+    }
+
+    /// Appends a base to the end of the sequence.
+    pub fn push(&mut self, base: Base) {
+        self.push_position(base, false, false);
+    }
+
+    /// Appends a soft-masked base to the end of the sequence.
+    pub fn push_soft_masked(&mut self, base: Base) {
+        self.push_position(base, false, true);
+    }
+
+    /// Appends an ambiguous position to the end of the sequence.
+    pub fn push_ambiguous(&mut self) {
+        self.push_position(Base::C, true, false);
+    }
+
+    /// Returns `true` if the base at index `i` was soft-masked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn is_soft_masked(&self, i: usize) -> bool {
+        self.soft[i]
+    }
+
+    /// Soft-masks the positions in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn soft_mask(&mut self, range: Range<usize>) {
+        self.soft[range].fill(true);
+    }
+
+    /// Returns an iterator over the maximal runs of soft-masked positions.
+    pub fn masked_regions(&self) -> Runs<'_> {
+        Runs::new(&self.soft, true)
+    }
+
+    /// Returns the number of positions in the sequence, including ambiguous
+    /// ones.
+    pub fn len(&self) -> usize {
+        self.invalid.len()
+    }
+
+    /// Returns `true` if the sequence contains no positions.
+    pub fn is_empty(&self) -> bool {
+        self.invalid.is_empty()
+    }
+
+    /// Returns `true` if the base at index `i` is not ambiguous.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn is_valid(&self, i: usize) -> bool {
+        !self.invalid[i]
+    }
+
+    /// Returns the number of ambiguous positions in the sequence.
+    pub fn ambiguous_count(&self) -> usize {
+        self.invalid.count_ones()
+    }
+
+    /// Returns the underlying sequence, with ambiguous positions stored as
+    /// `C`.
+    pub fn sequence(&self) -> &Sequence<B> {
+        &self.seq
+    }
+
+    /// Converts into the underlying sequence, with ambiguous positions
+    /// stored as `C`.
+    pub fn into_sequence(self) -> Sequence<B> {
+        self.seq
+    }
+
+    /// Returns an iterator over the maximal runs of unambiguous bases.
+    pub fn valid_runs(&self) -> Runs<'_> {
+        Runs::new(&self.invalid, false)
+    }
+
+    /// Returns an iterator over the k-mers that do not overlap an ambiguous
+    /// position, along with the index of their first base.
+    pub fn kmers<const K: usize>(&self) -> MaskedKmerIter<'_, K, B> {
+        MaskedKmerIter {
+            bits: &self.seq.store,
+            soft: &self.soft,
+            runs: self.valid_runs(),
+            current: None,
+        }
+    }
+
+    /// Returns an iterator over the minimizers of each run of unambiguous
+    /// bases.
+    ///
+    /// See [`Sequence::minimizers`]. Windows never span an ambiguous
+    /// position, and positions are relative to the whole sequence.
+    pub fn minimizers<const K: usize, const W: usize>(&self) -> MaskedMinimizerIter<'_, K, W, B> {
+        MaskedMinimizerIter {
+            bits: &self.seq.store,
+            soft: &self.soft,
+            runs: self.valid_runs(),
+            current: None,
+        }
+    }
+}
+
+impl<B: BitStore> Sequence<B> {
+    /// Creates a sequence from ASCII bases like
+    /// [`try_from_ascii`](Self::try_from_ascii), recording every lowercase
+    /// base as soft-masked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the byte offset and value of the first character
+    /// that is not a valid base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from_ascii_soft_masked(b"GATtacaGATTACA").unwrap();
+    /// assert_eq!(seq.sequence().to_string(), "GATTACAGATTACA");
+    /// assert!(seq.masked_regions().eq([3..7]));
+    /// ```
+    pub fn try_from_ascii_soft_masked(ascii: &[u8]) -> Result<MaskedSequence<B>, ParseBaseError> {
+        let seq = Self::try_from_ascii(ascii)?;
+        Ok(MaskedSequence {
+            invalid: BitVec::repeat(false, seq.len()),
+            soft: ascii.iter().map(u8::is_ascii_lowercase).collect(),
+            seq,
+        })
+    }
+}
+
+/// An iterator over the maximal runs of positions in a [`MaskedSequence`]
+/// that have, or lack, some property.
+#[derive(Debug, Clone)]
+pub struct Runs<'a> {
+    /// One bit per position, set if it has the property.
+    mask: &'a BitSlice<usize, Lsb0>,
+    /// A second mask of the same length, whose set positions also have the
+    /// property.
+    other: Option<&'a BitSlice<usize, Lsb0>>,
+    /// Whether the runs are of set or unset positions.
+    value: bool,
+    pos: usize,
+}
+
+impl<'a> Runs<'a> {
+    fn new(mask: &'a BitSlice<usize, Lsb0>, value: bool) -> Self {
+        Self {
+            mask,
+            other: None,
+            value,
+            pos: 0,
+        }
+    }
+
+    /// Returns the index of the first position from `from` with `value`.
+    fn first(&self, from: usize, value: bool) -> Option<usize> {
+        let Some(other) = self.other else {
+            let bits = &self.mask[from..];
+            let offset = if value {
+                bits.first_one()
+            } else {
+                bits.first_zero()
+            };
+            return offset.map(|offset| from + offset);
+        };
+        // combine the masks a word at a time, rather than into a new mask
+        let words = self.mask[from..].chunks(usize::BITS as usize);
+        let other_words = other[from..].chunks(usize::BITS as usize);
+        for (i, (word, other_word)) in words.zip(other_words).enumerate() {
+            let mut bits = word.load_le::<usize>() | other_word.load_le::<usize>();
+            if !value {
+                bits = !bits;
+            }
+            // the bits past the end of a short last word are set if inverted
+            let offset = bits.trailing_zeros() as usize;
+            if offset < word.len() {
+                return Some(from + i * usize::BITS as usize + offset);
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for Runs<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.first(self.pos, self.value)?;
+        let end = self.first(start, !self.value).unwrap_or(self.mask.len());
+        self.pos = end;
+        Some(start..end)
+    }
+}
+
+impl FusedIterator for Runs<'_> {}
+
+/// An iterator over the k-mers in a [`MaskedSequence`] that do not overlap
+/// an ambiguous position.
+#[derive(Debug)]
+pub struct MaskedKmerIter<'a, const K: usize, B: BitStore> {
+    bits: &'a BitSlice<B, Lsb0>,
+    /// One bit per base, set if the base was soft-masked.
+    soft: &'a BitSlice<usize, Lsb0>,
+    runs: Runs<'a>,
+    /// The start of the current run and an iterator over its k-mers.
+    current: Option<(usize, SmallKmerIter<'a, K, B>)>,
+}
+
+impl<'a, const K: usize, B: BitStore> MaskedKmerIter<'a, K, B> {
+    /// Sets whether to also skip the k-mers that overlap a soft-masked base.
+    /// Defaults to `false`.
+    ///
+    /// Only affects runs of bases that the iterator has not yet started.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sequence::MaskedSequence;
+    ///
+    /// let seq = MaskedSequence::<usize>::from_ascii(b"GATtacaGATTACA");
+    /// assert!(seq.masked_regions().eq([3..7]));
+    /// assert_eq!(seq.kmers::<5>().count(), 10);
+    ///
+    /// let kmers = seq.kmers::<5>().skip_soft_masked(true);
+    /// assert!(kmers.map(|(i, _)| i).eq([7, 8, 9]));
+    /// ```
+    pub fn skip_soft_masked(mut self, skip: bool) -> Self {
+        self.runs.other = skip.then_some(self.soft);
+        self
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for MaskedKmerIter<'a, K, B> {
+    type Item = (usize, Kmer<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((pos, kmers)) = &mut self.current
+                && let Some(kmer) = kmers.next()
+            {
+                *pos += 1;
+                return Some((*pos - 1, kmer));
+            }
+            let run = self.runs.next()?;
+            if run.len() >= K {
+                let bits = &self.bits[run.start * 2..run.end * 2];
+                self.current = Some((run.start, SmallKmerIter::new(bits)));
+            }
+        }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for MaskedKmerIter<'a, K, B> {}
+
+/// An iterator over the minimizers of the runs of unambiguous bases in a
+/// [`MaskedSequence`].
+#[derive(Debug)]
+pub struct MaskedMinimizerIter<'a, const K: usize, const W: usize, B: BitStore> {
+    bits: &'a BitSlice<B, Lsb0>,
+    /// One bit per base, set if the base was soft-masked.
+    soft: &'a BitSlice<usize, Lsb0>,
+    runs: Runs<'a>,
+    /// The start of the current run and an iterator over its minimizers.
+    current: Option<(usize, MinimizerIter<'a, K, W, B>)>,
+}
+
+impl<'a, const K: usize, const W: usize, B: BitStore> MaskedMinimizerIter<'a, K, W, B> {
+    /// Sets whether windows also never span a soft-masked base. Defaults to
+    /// `false`.
+    ///
+    /// Only affects runs of bases that the iterator has not yet started.
+    pub fn skip_soft_masked(mut self, skip: bool) -> Self {
+        self.runs.other = skip.then_some(self.soft);
+        self
+    }
+}
+
+impl<'a, const K: usize, const W: usize, B: BitStore> Iterator
+    for MaskedMinimizerIter<'a, K, W, B>
+{
+    type Item = (usize, Kmer<K>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((start, minimizers)) = &mut self.current
+                && let Some((pos, kmer, hash)) = minimizers.next()
+            {
+                return Some((*start + pos, kmer, hash));
+            }
+            let run = self.runs.next()?;
+            let bits = &self.bits[run.start * 2..run.end * 2];
+            self.current = Some((run.start, MinimizerIter::new(bits)));
+        }
+    }
+}
+
+impl<'a, const K: usize, const W: usize, B: BitStore> FusedIterator
+    for MaskedMinimizerIter<'a, K, W, B>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn valid_runs() {
+        let seq = MaskedSequence::<u8>::from_ascii(b"NNGATNACANNNTN");
+        assert_eq!(seq.ambiguous_count(), 7);
+        let runs: Vec<_> = seq.valid_runs().collect();
+        assert_eq!(runs, [2..5, 6..9, 12..13]);
+
+        let seq = MaskedSequence::<u8>::from_ascii(b"gat");
+        let mut runs = seq.valid_runs();
+        assert_eq!(runs.next(), Some(0..3));
+        assert_eq!(runs.next(), None);
+        assert_eq!(
+            MaskedSequence::<u8>::from_ascii(b"").valid_runs().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn skipped_runs() {
+        let ascii: Vec<u8> = (0..300).map(|i| b"ACGTNacgt"[i * 7 % 29 % 9]).collect();
+        let seq = MaskedSequence::<u16>::from_ascii(&ascii);
+        let mut expected: Vec<Range<usize>> = Vec::new();
+        for (i, &byte) in ascii.iter().enumerate() {
+            if byte.is_ascii_uppercase() && byte != b'N' {
+                match expected.last_mut() {
+                    Some(run) if run.end == i => *run = run.start..i + 1,
+                    _ => expected.push(i..i + 1),
+                }
+            }
+        }
+        let kmers = seq.kmers::<1>().skip_soft_masked(true);
+        assert!(
+            kmers
+                .map(|(i, _)| i)
+                .eq(expected.iter().flat_map(Clone::clone))
+        );
+        let mut runs = seq.valid_runs();
+        runs.other = Some(&seq.soft);
+        assert!(runs.eq(expected));
+    }
+
+    #[test]
+    fn kmers() {
+        let ascii: Vec<u8> = (0..400).map(|i| b"ACGTN"[i * 7 % 23 % 5]).collect();
+        let seq = MaskedSequence::<u16>::from_ascii(&ascii);
+
+        let expected: Vec<(usize, String)> = ascii
+            .windows(4)
+            .enumerate()
+            .filter(|(_, window)| !window.contains(&b'N'))
+            .map(|(i, window)| (i, String::from_utf8(window.to_vec()).unwrap()))
+            .collect();
+        let kmers: Vec<(usize, String)> = seq
+            .kmers::<4>()
+            .map(|(i, kmer)| (i, kmer.to_string()))
+            .collect();
+        assert!(!kmers.is_empty());
+        assert_eq!(kmers, expected);
+    }
+
+    #[test]
+    fn minimizers() {
+        let mut ascii = random_ascii(400, 0).into_bytes();
+        for c in ascii.iter_mut().step_by(97) {
+            *c = b'N';
+        }
+        let seq = MaskedSequence::<u64>::from_ascii(&ascii);
+
+        let mut expected = Vec::new();
+        for run in seq.valid_runs() {
+            let slice = seq.sequence().slice(run.clone());
+            expected.extend(
+                slice
+                    .minimizers::<7, 5>()
+                    .map(|(pos, kmer, hash)| (run.start + pos, kmer.as_masked(), hash)),
+            );
+        }
+        let minimizers: Vec<_> = seq
+            .minimizers::<7, 5>()
+            .map(|(pos, kmer, hash)| (pos, kmer.as_masked(), hash))
+            .collect();
+        assert_eq!(minimizers, expected);
+        for (pos, _, _) in minimizers {
+            assert!((pos..pos + 7).all(|i| seq.is_valid(i)));
+        }
+    }
+
+    #[test]
+    fn soft_masked() {
+        let mut seq = MaskedSequence::<u8>::from_ascii(b"acGTNnACgtAC");
+        assert!(seq.masked_regions().eq([0..2, 5..6, 8..10]));
+        assert!(seq.is_soft_masked(5) && !seq.is_valid(5));
+        assert!(seq.valid_runs().eq([0..4, 6..12]));
+
+        let kmers = seq.kmers::<2>().skip_soft_masked(true);
+        assert!(kmers.map(|(i, _)| i).eq([2, 6, 10]));
+        let minimizers = seq.minimizers::<2, 1>().skip_soft_masked(true);
+        assert!(minimizers.map(|(i, _, _)| i).eq([2, 6, 10]));
+
+        seq.push_soft_masked(Base::G);
+        assert_eq!(
+            Sequence::<u8>::try_from_ascii_soft_masked(b"acGTACgtAC").unwrap(),
+            MaskedSequence::from_ascii(b"acGTACgtAC")
+        );
+        assert!(Sequence::<u8>::try_from_ascii_soft_masked(b"acGTNn").is_err());
+        assert!(seq.masked_regions().eq([0..2, 5..6, 8..10, 12..13]));
+    }
+}
@@ -0,0 +1,253 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::Bases;
+use crate::Base;
+use crate::hash::nthash::seed;
+use crate::hash::nthash2;
+use crate::utils;
+
+/// An iterator over the canonical ntHash2 values of the k-mers in a
+/// sequence.
+///
+/// Created with [`Sequence::nthash2`](super::Sequence::nthash2).
+#[derive(Debug)]
+pub struct NtHash2Iter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    /// Trails `bases` by `K`, yielding the base leaving the k-mer.
+    out: Bases<'a, B>,
+    forward: u64,
+    reverse: u64,
+    primed: bool,
+}
+
+impl<'a, const K: usize, B: BitStore> NtHash2Iter<'a, K, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        utils::const_eval::assert_less::<0, K>();
+        Self {
+            bases: Bases::new(bits),
+            out: Bases::new(bits),
+            forward: 0,
+            reverse: 0,
+            primed: false,
+        }
+    }
+
+    /// Yields `M` hashes of each k-mer instead of one, as derived by
+    /// [`nthash2::extend`], such as for the hash functions of a Bloom filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::hash::nthash2;
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// for (hashes, hash) in seq.nthash2::<5>().multi::<3>().zip(seq.nthash2::<5>()) {
+    ///     assert_eq!(hashes, [0, 1, 2].map(|i| nthash2::extend(hash, 5, i)));
+    /// }
+    /// ```
+    pub fn multi<const M: usize>(self) -> MultiHashIter<'a, K, M, B> {
+        MultiHashIter { hashes: self }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for NtHash2Iter<'a, K, B> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.primed {
+            self.primed = true;
+            for i in 0..K {
+                // the same folds as `nthash2::hash` and `nthash2::reverse_hash`
+                let base = self.bases.next()?;
+                self.forward = nthash2::split_rotate_left(self.forward, 1) ^ seed(base);
+                self.reverse ^= nthash2::split_rotate_left(seed(base.complement()), i);
+            }
+        } else {
+            let base = self.bases.next()?;
+            // `out` trails `bases` by `K`, so it cannot run out first.
+            let out = self.out.next()?;
+            self.forward = nthash2::roll(self.forward, K, out, base);
+            self.reverse = nthash2::roll_reverse(self.reverse, K, out, base);
+        }
+        Some(nthash2::canonical(self.forward, self.reverse))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.primed {
+            self.bases.len()
+        } else {
+            (self.bases.len() + 1).saturating_sub(K)
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for NtHash2Iter<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for NtHash2Iter<'a, K, B> {}
+
+/// An iterator over several ntHash2 values of each k-mer in a sequence.
+///
+/// Created with [`NtHash2Iter::multi`].
+#[derive(Debug)]
+pub struct MultiHashIter<'a, const K: usize, const M: usize, B: BitStore> {
+    hashes: NtHash2Iter<'a, K, B>,
+}
+
+impl<'a, const K: usize, const M: usize, B: BitStore> Iterator for MultiHashIter<'a, K, M, B> {
+    type Item = [u64; M];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let hash = self.hashes.next()?;
+        Some(core::array::from_fn(|i| nthash2::extend(hash, K, i)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.hashes.size_hint()
+    }
+}
+
+impl<'a, const K: usize, const M: usize, B: BitStore> FusedIterator for MultiHashIter<'a, K, M, B> {}
+
+impl<'a, const K: usize, const M: usize, B: BitStore> ExactSizeIterator
+    for MultiHashIter<'a, K, M, B>
+{
+}
+
+/// An iterator over the canonical ntHash2 values of the spaced seeds of a
+/// sequence.
+///
+/// Created with
+/// [`Sequence::spaced_seed_hashes`](super::Sequence::spaced_seed_hashes).
+#[derive(Debug)]
+pub struct SpacedHashIter<'a, B: BitStore> {
+    bases: Bases<'a, B>,
+    span: usize,
+    /// The bases of the current window, with the first at `start`.
+    window: Vec<Base>,
+    start: usize,
+    /// The don't-care positions within the window.
+    ignored: Vec<usize>,
+    /// The hashes of the whole window, from which the bases at don't-care
+    /// positions are removed.
+    forward: u64,
+    reverse: u64,
+}
+
+impl<'a, B: BitStore> SpacedHashIter<'a, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>, shape: u64) -> Self {
+        assert_ne!(shape, 0, "shape must have at least one care position");
+        let span = 64 - shape.leading_zeros() as usize;
+        Self {
+            bases: Bases::new(bits),
+            span,
+            window: Vec::with_capacity(span),
+            start: 0,
+            ignored: (0..span).filter(|&i| shape >> i & 1 == 0).collect(),
+            forward: 0,
+            reverse: 0,
+        }
+    }
+}
+
+impl<'a, B: BitStore> Iterator for SpacedHashIter<'a, B> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.span;
+        if self.window.len() < span {
+            // the same folds as `nthash2::hash` and `nthash2::reverse_hash`
+            while self.window.len() < span {
+                let base = self.bases.next()?;
+                let i = self.window.len();
+                self.window.push(base);
+                self.forward = nthash2::split_rotate_left(self.forward, 1) ^ seed(base);
+                self.reverse ^= nthash2::split_rotate_left(seed(base.complement()), i);
+            }
+        } else {
+            let base = self.bases.next()?;
+            let out = core::mem::replace(&mut self.window[self.start], base);
+            self.start = (self.start + 1) % span;
+            self.forward = nthash2::roll(self.forward, span, out, base);
+            self.reverse = nthash2::roll_reverse(self.reverse, span, out, base);
+        }
+
+        let (mut forward, mut reverse) = (self.forward, self.reverse);
+        for &i in &self.ignored {
+            let base = self.window[(self.start + i) % span];
+            forward ^= nthash2::split_rotate_left(seed(base), span - 1 - i);
+            reverse ^= nthash2::split_rotate_left(seed(base.complement()), i);
+        }
+        Some(nthash2::canonical(forward, reverse))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.window.len() < self.span {
+            (self.bases.len() + self.window.len() + 1).saturating_sub(self.span)
+        } else {
+            self.bases.len()
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, B: BitStore> FusedIterator for SpacedHashIter<'a, B> {}
+
+impl<'a, B: BitStore> ExactSizeIterator for SpacedHashIter<'a, B> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sequence;
+    use crate::hash::nthash2;
+    use crate::utils::test::random_ascii;
+
+    fn sequence() -> Sequence<u8> {
+        let s = random_ascii(150, 0);
+        Sequence::try_from(s.as_str()).unwrap()
+    }
+
+    #[test]
+    fn canonical() {
+        let seq = sequence();
+        let hashes: Vec<u64> = seq.nthash2::<21>().collect();
+        assert_eq!(hashes.len(), seq.len() - 20);
+        for (i, &hash) in hashes.iter().enumerate() {
+            let kmer = seq.slice(i..i + 21);
+            let forward = nthash2::hash(kmer.bases());
+            let reverse = nthash2::reverse_hash(kmer.bases());
+            assert_eq!(hash, nthash2::canonical(forward, reverse));
+        }
+
+        let mut rc: Vec<u64> = seq.to_reverse_complement().nthash2::<21>().collect();
+        rc.reverse();
+        assert_eq!(hashes, rc);
+        assert_eq!(
+            Sequence::<u8>::try_from("GATT")
+                .unwrap()
+                .nthash2::<5>()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn spaced() {
+        let seq = sequence();
+        let shape = 0b1_0011_1011;
+        let hashes: Vec<u64> = seq.spaced_seed_hashes(shape).collect();
+        assert_eq!(hashes.len(), seq.len() - 8);
+        for (i, &hash) in hashes.iter().enumerate() {
+            let (forward, reverse) = nthash2::spaced_hashes(seq.slice(i..i + 9).bases(), shape);
+            assert_eq!(hash, nthash2::canonical(forward, reverse));
+        }
+
+        // a contiguous shape is the same as k-mers
+        assert!(seq.spaced_seed_hashes(0xff).eq(seq.nthash2::<8>()));
+    }
+}
@@ -0,0 +1,87 @@
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+use rayon::iter::ParallelIterator;
+use rayon::iter::plumbing::{Folder, UnindexedConsumer, UnindexedProducer, bridge_unindexed};
+
+use super::SmallKmerIter;
+use crate::small::Kmer;
+
+/// The fewest k-mers a chunk is split into before iterating sequentially.
+const MIN_CHUNK: usize = 1 << 12;
+
+/// A parallel iterator over the k-mers in a sequence.
+///
+/// Created with [`Sequence::par_kmers`](super::Sequence::par_kmers).
+#[derive(Debug)]
+pub struct ParKmerIter<'a, const K: usize, B: BitStore> {
+    bits: &'a BitSlice<B, Lsb0>,
+}
+
+impl<'a, const K: usize, B: BitStore> ParKmerIter<'a, K, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the number of k-mers in the chunk.
+    fn len(&self) -> usize {
+        (self.bits.len() / 2 + 1).saturating_sub(K)
+    }
+}
+
+impl<'a, const K: usize, B: BitStore + Sync> ParallelIterator for ParKmerIter<'a, K, B> {
+    type Item = Kmer<K>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(self, consumer)
+    }
+}
+
+impl<'a, const K: usize, B: BitStore + Sync> UnindexedProducer for ParKmerIter<'a, K, B> {
+    type Item = Kmer<K>;
+
+    fn split(self) -> (Self, Option<Self>) {
+        let len = self.len();
+        if len < MIN_CHUNK * 2 {
+            return (self, None);
+        }
+
+        // Both halves share the `K - 1` bases around the split point, so no
+        // k-mers are lost at the boundary.
+        let mid = len / 2;
+        let left = &self.bits[..(mid + K - 1) * 2];
+        let right = &self.bits[mid * 2..];
+        (Self::new(left), Some(Self::new(right)))
+    }
+
+    fn fold_with<F>(self, folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        folder.consume_iter(SmallKmerIter::new(self.bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::iter::ParallelIterator;
+
+    use crate::Sequence;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn par_kmers() {
+        let s = random_ascii(100_000, 0);
+        let seq = Sequence::<usize>::try_from(s.as_str()).unwrap();
+
+        let expected: Vec<u64> = seq.kmers::<31>().map(|k| k.as_masked()).collect();
+        let kmers: Vec<u64> = seq.par_kmers::<31>().map(|k| k.as_masked()).collect();
+        assert_eq!(kmers, expected);
+
+        assert_eq!(seq.slice(..30).par_kmers::<31>().count(), 0);
+        assert_eq!(seq.slice(..31).par_kmers::<31>().count(), 1);
+    }
+}
@@ -0,0 +1,267 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use core::iter::FusedIterator;
+use core::ops::RangeInclusive;
+
+use bitvec::field::BitField as _;
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::{Bases, Sequence};
+use crate::small::Kmer;
+use crate::utils::lanes::{self, LOW_BITS};
+
+const LANES: usize = usize::BITS as usize / 2;
+
+impl<B: BitStore> Sequence<B> {
+    /// Returns an iterator over the positions at which `kmer` occurs in the
+    /// sequence, in increasing order.
+    ///
+    /// Candidate positions are tested a word at a time: each base of the
+    /// k-mer is compared against a word of the sequence at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::small::Kmer;
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGATTACA").unwrap();
+    /// let kmer = Kmer::from_bases([Base::T, Base::A, Base::C]);
+    /// assert!(seq.positions_of(kmer).eq([3, 10]));
+    /// ```
+    pub fn positions_of<const K: usize>(&self, kmer: Kmer<K>) -> KmerPositions<'_, K, B> {
+        KmerPositions::new(&self.store, kmer)
+    }
+
+    /// Returns the position of the first occurrence of `kmer` in the
+    /// sequence, or `None` if it does not occur.
+    pub fn find_kmer<const K: usize>(&self, kmer: &Kmer<K>) -> Option<usize> {
+        self.positions_of(*kmer).next()
+    }
+
+    /// Returns an iterator over the positions at which `needle` occurs in
+    /// the sequence, in increasing order. Occurrences may overlap.
+    ///
+    /// Uses the bit-parallel shift-and algorithm directly on the packed
+    /// bases, taking `needle.len() / 64` word operations per base. An empty
+    /// needle matches at every position, including the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACAGATTACA").unwrap();
+    /// let needle = Sequence::<u8>::try_from("ACAGA").unwrap();
+    /// assert!(seq.match_indices(&needle).eq([4]));
+    /// assert_eq!(seq.find(&needle), Some(4));
+    /// ```
+    pub fn match_indices<C: BitStore>(&self, needle: &Sequence<C>) -> MatchIndices<'_, B> {
+        MatchIndices::new(&self.store, needle)
+    }
+
+    /// Returns the position of the first occurrence of `needle` in the
+    /// sequence, or `None` if it does not occur.
+    pub fn find<C: BitStore>(&self, needle: &Sequence<C>) -> Option<usize> {
+        self.match_indices(needle).next()
+    }
+}
+
+/// An iterator over the positions of a k-mer in a sequence.
+///
+/// Created with [`Sequence::positions_of`].
+#[derive(Debug)]
+pub struct KmerPositions<'a, const K: usize, B: BitStore> {
+    bits: &'a BitSlice<B, Lsb0>,
+    codes: [u8; K],
+    /// The index of the next word of candidate positions to test.
+    word: usize,
+    /// The matches in the current word that have not been yielded.
+    matches: usize,
+}
+
+impl<'a, const K: usize, B: BitStore> KmerPositions<'a, K, B> {
+    fn new(bits: &'a BitSlice<B, Lsb0>, kmer: Kmer<K>) -> Self {
+        let mut codes = [0; K];
+        for (code, base) in codes.iter_mut().zip(kmer.bases()) {
+            *code = base as u8;
+        }
+        Self {
+            bits,
+            codes,
+            word: 0,
+            matches: 0,
+        }
+    }
+
+    /// Tests the candidate positions in the next word, returning `false` if
+    /// there are none left.
+    fn advance(&mut self) -> bool {
+        let len = self.bits.len() / 2;
+        let start = self.word * LANES;
+        let Some(candidates) = (len + 1).checked_sub(K + start).filter(|&n| n > 0) else {
+            return false;
+        };
+        self.word += 1;
+
+        let mut matches = if candidates >= LANES {
+            LOW_BITS
+        } else {
+            LOW_BITS & ((1 << (candidates * 2)) - 1)
+        };
+        for (j, &code) in self.codes.iter().enumerate() {
+            // the bases `j` after each candidate position
+            let from = (start + j) * 2;
+            let to = self.bits.len().min(from + usize::BITS as usize);
+            matches &= lanes::matches(self.bits[from..to].load_le(), code);
+            if matches == 0 {
+                break;
+            }
+        }
+        self.matches = matches;
+        true
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for KmerPositions<'a, K, B> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.matches == 0 {
+            if !self.advance() {
+                return None;
+            }
+        }
+        let lane = self.matches.trailing_zeros() as usize / 2;
+        self.matches &= self.matches - 1;
+        Some((self.word - 1) * LANES + lane)
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for KmerPositions<'a, K, B> {}
+
+/// An iterator over the positions of a subsequence in a sequence.
+///
+/// Created with [`Sequence::match_indices`].
+#[derive(Debug)]
+pub struct MatchIndices<'a, B: BitStore> {
+    text: Bases<'a, B>,
+    /// For each base, the positions in the needle that hold it.
+    masks: [Box<[u64]>; 4],
+    /// The needle prefixes that end at the last base read.
+    state: Box<[u64]>,
+    len: usize,
+    /// The number of bases read.
+    pos: usize,
+    /// The remaining positions, if the needle is empty.
+    empty: Option<RangeInclusive<usize>>,
+}
+
+impl<'a, B: BitStore> MatchIndices<'a, B> {
+    fn new<C: BitStore>(bits: &'a BitSlice<B, Lsb0>, needle: &Sequence<C>) -> Self {
+        let words = needle.len().div_ceil(64);
+        let mut masks = [(); 4].map(|_| vec![0; words].into_boxed_slice());
+        for (i, base) in needle.bases().enumerate() {
+            masks[base as usize][i / 64] |= 1 << (i % 64);
+        }
+        Self {
+            text: Bases::new(bits),
+            masks,
+            state: vec![0; words].into_boxed_slice(),
+            len: needle.len(),
+            pos: 0,
+            empty: needle.is_empty().then_some(0..=bits.len() / 2),
+        }
+    }
+}
+
+impl<'a, B: BitStore> Iterator for MatchIndices<'a, B> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(empty) = &mut self.empty {
+            return empty.next();
+        }
+
+        let last = ((self.len - 1) / 64, (self.len - 1) % 64);
+        loop {
+            let base = self.text.next()?;
+            self.pos += 1;
+
+            // extend every prefix by one base, and start a new one
+            let mut carry = 1;
+            for (word, &mask) in self.state.iter_mut().zip(&self.masks[base as usize]) {
+                let next = *word >> 63;
+                *word = (*word << 1 | carry) & mask;
+                carry = next;
+            }
+            if self.state[last.0] >> last.1 & 1 == 1 {
+                return Some(self.pos - self.len);
+            }
+        }
+    }
+}
+
+impl<'a, B: BitStore> FusedIterator for MatchIndices<'a, B> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sequence;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn positions_of() {
+        let s = random_ascii(500, 0);
+        let seq = Sequence::<u16>::try_from(s.as_str()).unwrap();
+
+        for (i, kmer) in seq.kmers::<6>().enumerate().step_by(37) {
+            let needle = &s[i..i + 6];
+            let expected: Vec<usize> = (0..=s.len() - 6)
+                .filter(|&p| &s[p..p + 6] == needle)
+                .collect();
+            let positions: Vec<usize> = seq.positions_of(kmer).collect();
+            assert_eq!(positions, expected);
+            assert_eq!(seq.find_kmer(&kmer), expected.first().copied());
+        }
+
+        let kmer = seq.kmers::<32>().next_back().unwrap();
+        assert_eq!(seq.positions_of(kmer).last(), Some(500 - 32));
+        let short = Sequence::<u16>::try_from(&s[..31]).unwrap();
+        assert_eq!(short.find_kmer(&kmer), None);
+    }
+
+    #[test]
+    fn match_indices() {
+        let s = random_ascii(500, 0);
+        let seq = Sequence::<u32>::try_from(s.as_str()).unwrap();
+
+        for (start, len) in [
+            (0, 1),
+            (3, 5),
+            (17, 63),
+            (40, 64),
+            (100, 65),
+            (0, 200),
+            (0, 500),
+        ] {
+            let needle = &s[start..start + len];
+            let expected: Vec<usize> = (0..=s.len() - len)
+                .filter(|&p| &s[p..p + len] == needle)
+                .collect();
+            let needle = Sequence::<u8>::try_from(needle).unwrap();
+            let positions: Vec<usize> = seq.match_indices(&needle).collect();
+            assert_eq!(positions, expected, "len = {len}");
+            assert_eq!(seq.find(&needle), Some(expected[0]));
+        }
+
+        let needle = Sequence::<u8>::try_from("GGGGG").unwrap();
+        assert_eq!(seq.find(&needle), None);
+        let long = Sequence::<u8>::try_from(s.repeat(2).as_str()).unwrap();
+        assert_eq!(seq.find(&long), None);
+
+        let seq = Sequence::<u8>::try_from("GAT").unwrap();
+        assert!(seq.match_indices(&Sequence::<u8>::new()).eq(0..=3));
+    }
+}
@@ -0,0 +1,281 @@
+//! Sampling k-mers from a sequence.
+
+use alloc::collections::VecDeque;
+use core::iter::FusedIterator;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::{HashedKmerIter, KmerHashIter, SmallKmerIter};
+use crate::small::Kmer;
+use crate::utils;
+
+/// The minimum of a sliding window over a stream of hashes.
+///
+/// A monotonic queue holds the hashes that could still become the minimum:
+/// each is smaller than every hash pushed after it, so the front is the
+/// minimum of the window. Every hash is pushed and popped at most once, so
+/// sliding the window takes amortized constant time for any width.
+#[derive(Debug)]
+struct WindowMin {
+    /// The indices and hashes of the candidates, increasing in both.
+    queue: VecDeque<(usize, u64)>,
+    width: usize,
+    /// The number of hashes pushed so far.
+    pushed: usize,
+}
+
+impl WindowMin {
+    fn new(width: usize) -> Self {
+        Self {
+            queue: VecDeque::with_capacity(width),
+            width,
+            pushed: 0,
+        }
+    }
+
+    /// Pushes a hash into the window, returning the index of the leftmost
+    /// minimum once the window is full.
+    fn push(&mut self, hash: u64) -> Option<usize> {
+        let i = self.pushed;
+        self.pushed += 1;
+
+        // Equal hashes are kept, so the leftmost minimum stays in front.
+        while self.queue.back().is_some_and(|&(_, back)| back > hash) {
+            self.queue.pop_back();
+        }
+        self.queue.push_back((i, hash));
+        // At most one index leaves the window per push.
+        if self.queue[0].0 + self.width <= i {
+            self.queue.pop_front();
+        }
+
+        (i + 1 >= self.width).then(|| self.queue[0].0)
+    }
+}
+
+/// An iterator over the minimizers of a sequence.
+///
+/// Created with [`Sequence::minimizers`](super::Sequence::minimizers).
+#[derive(Debug)]
+pub struct MinimizerIter<'a, const K: usize, const W: usize, B: BitStore> {
+    kmers: HashedKmerIter<'a, K, B>,
+    /// The k-mers and hashes in the current window, indexed by position.
+    window: [(Kmer<K>, u64); W],
+    min: WindowMin,
+    last: Option<usize>,
+}
+
+impl<'a, const K: usize, const W: usize, B: BitStore> MinimizerIter<'a, K, W, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        utils::const_eval::assert_less::<0, W>();
+        Self {
+            kmers: HashedKmerIter::new(bits),
+            window: [(Kmer::new(), 0); W],
+            min: WindowMin::new(W),
+            last: None,
+        }
+    }
+}
+
+impl<'a, const K: usize, const W: usize, B: BitStore> Iterator for MinimizerIter<'a, K, W, B> {
+    type Item = (usize, Kmer<K>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (kmer, hash) = self.kmers.next()?;
+            let position = self.min.pushed;
+            self.window[position % W] = (kmer, hash);
+
+            let Some(min) = self.min.push(hash) else {
+                continue;
+            };
+            if self.last != Some(min) {
+                self.last = Some(min);
+                let (kmer, hash) = self.window[min % W];
+                return Some((min, kmer, hash));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.kmers.size_hint().1)
+    }
+}
+
+impl<'a, const K: usize, const W: usize, B: BitStore> FusedIterator for MinimizerIter<'a, K, W, B> {}
+
+/// Which k-mers are selected as syncmers.
+///
+/// A k-mer is a syncmer if the smallest of its s-mers, by
+/// [ntHash](crate::hash::nthash), is at a particular offset within it. Ties
+/// are broken by taking the leftmost s-mer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncmerKind {
+    /// The smallest s-mer is at the given offset.
+    Open(usize),
+    /// The smallest s-mer is at the start or end of the k-mer.
+    Closed,
+}
+
+/// An iterator over the syncmers of a sequence.
+///
+/// Created with [`Sequence::syncmers`](super::Sequence::syncmers).
+#[derive(Debug)]
+pub struct SyncmerIter<'a, const K: usize, const S: usize, B: BitStore> {
+    kmers: SmallKmerIter<'a, K, B>,
+    smers: KmerHashIter<'a, S, B>,
+    min: WindowMin,
+    kind: SyncmerKind,
+}
+
+impl<'a, const K: usize, const S: usize, B: BitStore> SyncmerIter<'a, K, S, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>, kind: SyncmerKind) -> Self {
+        utils::const_eval::assert_less::<0, S>();
+        utils::const_eval::assert_leq::<S, K>();
+        if let SyncmerKind::Open(offset) = kind {
+            assert!(
+                offset <= K - S,
+                "offset {offset} is out of bounds for {} s-mers",
+                K - S + 1
+            );
+        }
+        Self {
+            kmers: SmallKmerIter::new(bits),
+            smers: KmerHashIter::new(bits, false),
+            min: WindowMin::new(K - S + 1),
+            kind,
+        }
+    }
+}
+
+impl<'a, const K: usize, const S: usize, B: BitStore> Iterator for SyncmerIter<'a, K, S, B> {
+    type Item = (usize, Kmer<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let hash = self.smers.next()?;
+            let Some(min) = self.min.push(hash) else {
+                continue;
+            };
+            // The window is full, so it covers exactly the s-mers of a k-mer.
+            let kmer = self.kmers.next()?;
+            let position = self.min.pushed - (K - S + 1);
+            let offset = min - position;
+            let selected = match self.kind {
+                SyncmerKind::Open(t) => offset == t,
+                SyncmerKind::Closed => offset == 0 || offset == K - S,
+            };
+            if selected {
+                return Some((position, kmer));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.kmers.size_hint().1)
+    }
+}
+
+impl<'a, const K: usize, const S: usize, B: BitStore> FusedIterator for SyncmerIter<'a, K, S, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{SyncmerKind, WindowMin};
+    use crate::Sequence;
+    use crate::hash::nthash;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn window_min() {
+        // few distinct hashes, so there are many ties
+        let hashes: Vec<u64> = (0..1000).map(|i| i * 31 % 97 % 5).collect();
+        for width in [1, 2, 7, 64] {
+            let mut min = WindowMin::new(width);
+            let mins: Vec<usize> = hashes.iter().filter_map(|&h| min.push(h)).collect();
+            let expected: Vec<usize> = hashes
+                .windows(width)
+                .enumerate()
+                .map(|(start, window)| {
+                    let min = window.iter().min().unwrap();
+                    start + window.iter().position(|h| h == min).unwrap()
+                })
+                .collect();
+            assert_eq!(mins, expected);
+            assert!(min.queue.len() <= width);
+        }
+    }
+
+    #[test]
+    fn minimizers() {
+        let s = random_ascii(500, 0);
+        let seq = Sequence::<usize>::try_from(s.as_str()).unwrap();
+        let kmers: Vec<_> = seq.kmers::<11>().collect();
+        let hashes: Vec<u64> = kmers.iter().map(|k| nthash::hash(k.bases())).collect();
+
+        // every window minimum, found by brute force
+        let mut expected: Vec<usize> = hashes
+            .windows(8)
+            .enumerate()
+            .map(|(start, window)| {
+                let min = window.iter().min().unwrap();
+                start + window.iter().position(|h| h == min).unwrap()
+            })
+            .collect();
+        expected.dedup();
+
+        let minimizers: Vec<_> = seq.minimizers::<11, 8>().collect();
+        assert_eq!(minimizers.iter().map(|m| m.0).collect::<Vec<_>>(), expected);
+        for (position, kmer, hash) in minimizers {
+            assert_eq!(kmer.as_masked(), kmers[position].as_masked());
+            assert_eq!(hash, hashes[position]);
+        }
+
+        assert_eq!(seq.slice(..17).minimizers::<11, 8>().count(), 0);
+        assert_eq!(seq.slice(..18).minimizers::<11, 8>().count(), 1);
+    }
+
+    #[test]
+    fn syncmers() {
+        let s = random_ascii(300, 0);
+        let seq = Sequence::<u8>::try_from(s.as_str()).unwrap();
+        let kmers: Vec<_> = seq.kmers::<9>().collect();
+        let smers: Vec<u64> = seq.kmers::<4>().map(|k| nthash::hash(k.bases())).collect();
+
+        // offset of the smallest s-mer in each k-mer, found by brute force
+        let offsets: Vec<usize> = (0..kmers.len())
+            .map(|i| {
+                let window = &smers[i..i + 6];
+                let min = window.iter().min().unwrap();
+                window.iter().position(|h| h == min).unwrap()
+            })
+            .collect();
+
+        for kind in [
+            SyncmerKind::Closed,
+            SyncmerKind::Open(0),
+            SyncmerKind::Open(2),
+        ] {
+            let expected: Vec<usize> = (0..kmers.len())
+                .filter(|&i| match kind {
+                    SyncmerKind::Open(t) => offsets[i] == t,
+                    SyncmerKind::Closed => offsets[i] == 0 || offsets[i] == 5,
+                })
+                .collect();
+            let syncmers: Vec<_> = seq.syncmers::<9, 4>(kind).collect();
+            assert!(!syncmers.is_empty());
+            assert_eq!(syncmers.iter().map(|s| s.0).collect::<Vec<_>>(), expected);
+            for (position, kmer) in syncmers {
+                assert_eq!(kmer.as_masked(), kmers[position].as_masked());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn syncmer_offset_out_of_bounds() {
+        let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        seq.syncmers::<5, 3>(SyncmerKind::Open(3));
+    }
+}
@@ -0,0 +1,327 @@
+use alloc::string::ToString;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::RangeInclusive;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::{
+    Bases, CanonicalKmerIter, HpcKmerIter, KmerHashIter, LargeKmerIter, MinimizerIter, NtHash2Iter,
+    Sequence, SmallKmerIter, SpacedHashIter, SpacedSeedIter, StepKmerIter, StrobeKind,
+    StrobemerIter, SyncmerIter, SyncmerKind,
+};
+
+/// A borrowed view of a contiguous range of bases in a [`Sequence`].
+///
+/// Created with [`Sequence::slice`].
+pub struct SequenceSlice<'a, B: BitStore> {
+    pub(super) bits: &'a BitSlice<B, Lsb0>,
+}
+
+impl<B: BitStore> fmt::Debug for SequenceSlice<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SequenceSlice")
+            .field("len", &self.len())
+            .field("bases", &self.to_string())
+            .finish()
+    }
+}
+
+impl<B: BitStore> fmt::Display for SequenceSlice<'_, B> {
+    /// Formats the bases as uppercase ASCII, or lowercase with `{:#}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        super::write_ascii_chunks(self.bits, f.alternate(), |chunk| {
+            // SAFETY: decoded bases are always ASCII.
+            f.write_str(unsafe { core::str::from_utf8_unchecked(chunk) })
+        })
+    }
+}
+
+impl<B: BitStore> Clone for SequenceSlice<'_, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: BitStore> Copy for SequenceSlice<'_, B> {}
+
+impl<B: BitStore, C: BitStore> PartialEq<SequenceSlice<'_, C>> for SequenceSlice<'_, B> {
+    /// Compares the bases in two views, regardless of their storage types.
+    fn eq(&self, other: &SequenceSlice<'_, C>) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<B: BitStore> Eq for SequenceSlice<'_, B> {}
+
+impl<B: BitStore, C: BitStore> PartialEq<Sequence<C>> for SequenceSlice<'_, B> {
+    fn eq(&self, other: &Sequence<C>) -> bool {
+        self.bits == other.store
+    }
+}
+
+impl<'a, B: BitStore> SequenceSlice<'a, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>) -> Self {
+        Self { bits }
+    }
+
+    /// Returns the number of bases in the view.
+    pub fn len(&self) -> usize {
+        self.bits.len() / 2
+    }
+
+    /// Returns `true` if the view contains no bases.
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Collects the bases in the view into an [`unbounded::Kmer`], with `k`
+    /// equal to the length of the view.
+    ///
+    /// [`unbounded::Kmer`]: crate::unbounded::Kmer
+    pub fn to_kmer(&self) -> crate::unbounded::Kmer {
+        self.bases().collect()
+    }
+
+    /// Returns an iterator over the bases in the view.
+    pub fn bases(&self) -> Bases<'a, B> {
+        Bases::new(self.bits)
+    }
+
+    /// Returns an iterator over the bases in the view from the 3' end.
+    ///
+    /// This is equivalent to `self.bases().rev()`.
+    pub fn bases_rev(&self) -> core::iter::Rev<Bases<'a, B>> {
+        self.bases().rev()
+    }
+
+    /// Returns an iterator over the k-mers in the view.
+    pub fn kmers<const K: usize>(&self) -> SmallKmerIter<'a, K, B> {
+        SmallKmerIter::new(self.bits)
+    }
+
+    /// Returns an iterator over every `step`-th k-mer in the view, along
+    /// with the index of its first base.
+    ///
+    /// See [`Sequence::kmers_step`].
+    pub fn kmers_step<const K: usize>(&self, step: usize) -> StepKmerIter<'a, K, B> {
+        StepKmerIter::new(self.bits, step)
+    }
+
+    /// Returns a parallel iterator over the k-mers in the view.
+    ///
+    /// See [`Sequence::par_kmers`].
+    #[cfg(feature = "rayon")]
+    pub fn par_kmers<const K: usize>(&self) -> super::ParKmerIter<'a, K, B> {
+        super::ParKmerIter::new(self.bits)
+    }
+
+    /// Returns an iterator over the k-mers in the view, for any `k`.
+    ///
+    /// See [`Sequence::large_kmers`].
+    pub fn large_kmers(&self, k: usize) -> LargeKmerIter<'a, B> {
+        LargeKmerIter::new(self.bits, k)
+    }
+
+    /// Returns an iterator over the canonical k-mers in the view.
+    ///
+    /// See [`Sequence::canonical_kmers`].
+    pub fn canonical_kmers<const K: usize>(&self) -> CanonicalKmerIter<'a, K, B> {
+        CanonicalKmerIter::new(self.bits)
+    }
+
+    /// Returns an iterator over the forward ntHash of every k-mer in the view.
+    ///
+    /// See [`Sequence::kmer_hashes`].
+    pub fn kmer_hashes<const K: usize>(&self) -> KmerHashIter<'a, K, B> {
+        KmerHashIter::new(self.bits, false)
+    }
+
+    /// Returns an iterator over the canonical ntHash of every k-mer in the
+    /// view.
+    ///
+    /// See [`Sequence::canonical_kmer_hashes`].
+    pub fn canonical_kmer_hashes<const K: usize>(&self) -> KmerHashIter<'a, K, B> {
+        KmerHashIter::new(self.bits, true)
+    }
+
+    /// Returns an iterator over the canonical ntHash2 of every k-mer in the
+    /// view.
+    ///
+    /// See [`Sequence::nthash2`].
+    pub fn nthash2<const K: usize>(&self) -> NtHash2Iter<'a, K, B> {
+        NtHash2Iter::new(self.bits)
+    }
+
+    /// Returns an iterator over the minimizers of the view.
+    ///
+    /// See [`Sequence::minimizers`].
+    pub fn minimizers<const K: usize, const W: usize>(&self) -> MinimizerIter<'a, K, W, B> {
+        MinimizerIter::new(self.bits)
+    }
+
+    /// Returns an iterator over the syncmers of the view.
+    ///
+    /// See [`Sequence::syncmers`].
+    pub fn syncmers<const K: usize, const S: usize>(
+        &self,
+        kind: SyncmerKind,
+    ) -> SyncmerIter<'a, K, S, B> {
+        SyncmerIter::new(self.bits, kind)
+    }
+
+    /// Returns an iterator over the spaced seeds of the view.
+    ///
+    /// See [`Sequence::spaced_seeds`].
+    pub fn spaced_seeds<const K: usize>(&self, shape: u64) -> SpacedSeedIter<'a, K, B> {
+        SpacedSeedIter::new(self.bits, shape)
+    }
+
+    /// Returns an iterator over the canonical ntHash2 of the spaced seeds of
+    /// the view.
+    ///
+    /// See [`Sequence::spaced_seed_hashes`].
+    pub fn spaced_seed_hashes(&self, shape: u64) -> SpacedHashIter<'a, B> {
+        SpacedHashIter::new(self.bits, shape)
+    }
+
+    /// Returns an iterator over the k-mers of the homopolymer-compressed
+    /// view.
+    ///
+    /// See [`Sequence::hpc_kmers`].
+    pub fn hpc_kmers<const K: usize>(&self) -> HpcKmerIter<'a, K, B> {
+        HpcKmerIter::new(self.bits)
+    }
+
+    /// Returns an iterator over the strobemers of the view.
+    ///
+    /// See [`Sequence::strobemers`].
+    pub fn strobemers<const L: usize, const N: usize>(
+        &self,
+        kind: StrobeKind,
+        window: RangeInclusive<usize>,
+    ) -> StrobemerIter<'a, L, N, B> {
+        StrobemerIter::new(self.bits, kind, window)
+    }
+}
+
+/// An iterator over the overlapping windows of `k` bases in a sequence.
+///
+/// Created with [`Sequence::windows`].
+#[derive(Debug)]
+pub struct Windows<'a, B: BitStore> {
+    bits: &'a BitSlice<B, Lsb0>,
+    k: usize,
+    /// The start of the next window from the front.
+    front: usize,
+    /// The end of the range of window starts not yet yielded.
+    back: usize,
+}
+
+impl<'a, B: BitStore> Windows<'a, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>, k: usize) -> Self {
+        assert!(k > 0, "k must be greater than 0");
+        Self {
+            bits,
+            k,
+            front: 0,
+            back: (bits.len() / 2 + 1).saturating_sub(k),
+        }
+    }
+
+    fn window(&self, start: usize) -> SequenceSlice<'a, B> {
+        SequenceSlice::new(&self.bits[start * 2..(start + self.k) * 2])
+    }
+}
+
+impl<'a, B: BitStore> Iterator for Windows<'a, B> {
+    type Item = SequenceSlice<'a, B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.window(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<'a, B: BitStore> DoubleEndedIterator for Windows<'a, B> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.window(self.back))
+    }
+}
+
+impl<'a, B: BitStore> FusedIterator for Windows<'a, B> {}
+
+impl<'a, B: BitStore> ExactSizeIterator for Windows<'a, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Base;
+
+    #[test]
+    fn slice() {
+        let seq = Sequence::<u8>::try_from("ACGTTGCAACGTTGCA").unwrap();
+        let other = Sequence::<u64>::try_from("TTGCAAC").unwrap();
+
+        let slice = seq.slice(3..10);
+        assert_eq!(slice.len(), 7);
+        assert_eq!(slice, other.as_slice());
+        assert!(slice == other);
+        assert_ne!(seq.slice(2..9), other.as_slice());
+        assert!(seq.slice(4..4).is_empty());
+
+        let bases: Vec<Base> = slice.bases().collect();
+        assert_eq!(
+            bases,
+            [
+                Base::T,
+                Base::T,
+                Base::G,
+                Base::C,
+                Base::A,
+                Base::A,
+                Base::C
+            ]
+        );
+
+        let kmers: Vec<String> = slice.kmers::<5>().map(|kmer| kmer.to_string()).collect();
+        assert_eq!(kmers, ["TTGCA", "TGCAA", "GCAAC"]);
+    }
+
+    #[test]
+    fn windows() {
+        let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        let windows: Vec<String> = seq.windows(3).map(|w| w.to_string()).collect();
+        assert_eq!(windows, ["GAT", "ATT", "TTA", "TAC", "ACA"]);
+
+        let mut windows = seq.windows(4);
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows.next_back().unwrap().to_string(), "TACA");
+        assert_eq!(windows.nth(1).unwrap().to_string(), "ATTA");
+        assert_eq!(windows.next().unwrap().to_kmer().to_string(), "TTAC");
+        assert!(windows.next().is_none());
+
+        assert_eq!(seq.windows(7).count(), 1);
+        assert_eq!(seq.windows(8).count(), 0);
+        assert!(seq.windows(5).map(|w| w.to_kmer()).eq(seq.large_kmers(5)));
+    }
+}
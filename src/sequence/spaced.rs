@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::Bases;
+use crate::small::Kmer;
+
+/// An iterator over the spaced seeds of a sequence.
+///
+/// Created with [`Sequence::spaced_seeds`](super::Sequence::spaced_seeds).
+#[derive(Debug)]
+pub struct SpacedSeedIter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    /// The last `span` bases, with the first in the most significant lanes.
+    window: u64,
+    /// Runs of consecutive care positions, as `(shift, mask, shift)`
+    /// triples moving each run from the window into the seed.
+    runs: Vec<(u32, u64, u32)>,
+}
+
+impl<'a, const K: usize, B: BitStore> SpacedSeedIter<'a, K, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>, shape: u64) -> Self {
+        let span = 64 - shape.leading_zeros() as usize;
+        assert!(span <= 32, "shape spans {span} bases, more than 32");
+        assert_eq!(
+            shape.count_ones() as usize,
+            K,
+            "shape must have exactly K care positions"
+        );
+
+        let mut runs = Vec::new();
+        let mut seen = 0;
+        let mut p = 0;
+        while p < span {
+            let len = (shape >> p).trailing_ones() as usize;
+            if len > 0 {
+                let mask = u64::MAX >> (64 - len * 2);
+                runs.push((
+                    ((span - p - len) * 2) as u32,
+                    mask,
+                    ((K - seen - len) * 2) as u32,
+                ));
+                seen += len;
+            }
+            p += len + (shape >> (p + len)).trailing_zeros() as usize;
+        }
+
+        let mut iter = Self {
+            bases: Bases::new(bits),
+            window: 0,
+            runs,
+        };
+        for _ in 0..span - 1 {
+            let Some(base) = iter.bases.next() else {
+                break;
+            };
+            iter.window = iter.window << 2 | base as u64;
+        }
+        iter
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for SpacedSeedIter<'a, K, B> {
+    type Item = Kmer<K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.window = self.window << 2 | self.bases.next()? as u64;
+        let seed = self.runs.iter().fold(0, |seed, &(from, mask, to)| {
+            seed | (self.window >> from & mask) << to
+        });
+        Some(Kmer::from(seed))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bases.size_hint()
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for SpacedSeedIter<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for SpacedSeedIter<'a, K, B> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sequence;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn spaced_seeds() {
+        let s = random_ascii(120, 0);
+        let seq = Sequence::<u8>::try_from(s.as_str()).unwrap();
+
+        // care positions 0, 1, 3, 4, 5, 8 of a 9 base window
+        let shape = 0b1_0011_1011;
+        let seeds: Vec<String> = seq
+            .spaced_seeds::<6>(shape)
+            .map(|seed| seed.to_string())
+            .collect();
+        assert_eq!(seeds.len(), 112);
+        for (i, seed) in seeds.iter().enumerate() {
+            let expected: String = [0, 1, 3, 4, 5, 8]
+                .iter()
+                .map(|p| &s[i + p..i + p + 1])
+                .collect();
+            assert_eq!(*seed, expected);
+        }
+
+        // a contiguous shape is the same as k-mers
+        let contiguous = seq.spaced_seeds::<32>(u32::MAX as u64);
+        for (seed, kmer) in contiguous.zip(seq.kmers::<32>()) {
+            assert_eq!(seed.as_masked(), kmer.as_masked());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn shape_mismatch() {
+        let seq = Sequence::<u8>::try_from("GATTACA").unwrap();
+        seq.spaced_seeds::<3>(0b1001);
+    }
+}
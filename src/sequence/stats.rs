@@ -0,0 +1,144 @@
+use bitvec::store::BitStore;
+
+use super::Sequence;
+use crate::utils::lanes::{self, LOW_BITS};
+
+const BITS: usize = usize::BITS as usize;
+
+/// Returns a mask with the low bit set in each of the first `n` lanes of a
+/// word.
+const fn first_lanes(n: usize) -> usize {
+    if n >= BITS / 2 {
+        LOW_BITS
+    } else {
+        LOW_BITS & ((1 << (n * 2)) - 1)
+    }
+}
+
+impl<B: BitStore> Sequence<B> {
+    /// Counts the occurrences of each base in the sequence, indexed by
+    /// 2-bit code (`base as usize`).
+    ///
+    /// Bases are counted a word at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let counts = seq.composition();
+    /// assert_eq!(counts[Base::A as usize], 3);
+    /// assert_eq!(counts[Base::T as usize], 2);
+    /// ```
+    pub fn composition(&self) -> [u64; 4] {
+        let mut counts = [0; 4];
+        for (i, word) in self.words().into_iter().enumerate() {
+            let valid = first_lanes(self.len() - i * BITS / 2);
+            for (code, count) in counts.iter_mut().enumerate().take(3) {
+                *count += (lanes::matches(word, code as u8) & valid).count_ones() as u64;
+            }
+        }
+        counts[3] = self.len() as u64 - counts[..3].iter().sum::<u64>();
+        counts
+    }
+
+    /// Counts the `G` and `C` bases in the sequence.
+    pub fn gc_count(&self) -> u64 {
+        let counts = self.composition();
+        counts[crate::Base::G as usize] + counts[crate::Base::C as usize]
+    }
+
+    /// Returns the fraction of bases in the sequence that are `G` or `C`, or
+    /// 0 if the sequence is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACCA").unwrap();
+    /// assert_eq!(seq.gc_content(), 0.375);
+    /// ```
+    pub fn gc_content(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.gc_count() as f64 / self.len() as f64
+    }
+
+    /// Counts each pair of adjacent bases in the sequence, indexed by the
+    /// 2-bit codes of the first and second base.
+    ///
+    /// Pairs are counted a word at a time, including pairs that span two
+    /// words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::{Base, Sequence};
+    ///
+    /// let seq = Sequence::<usize>::try_from("GATTACA").unwrap();
+    /// let counts = seq.dinucleotide_counts();
+    /// assert_eq!(counts[Base::A as usize][Base::T as usize], 1);
+    /// assert_eq!(counts[Base::C as usize][Base::A as usize], 1);
+    /// assert_eq!(counts[Base::T as usize][Base::T as usize], 1);
+    /// ```
+    pub fn dinucleotide_counts(&self) -> [[u64; 4]; 4] {
+        let mut counts = [[0; 4]; 4];
+        let pairs = self.len().saturating_sub(1);
+        let words = self.words();
+        for (i, &word) in words.iter().enumerate() {
+            let Some(remaining) = pairs.checked_sub(i * BITS / 2) else {
+                break;
+            };
+            let valid = first_lanes(remaining);
+            // the second base of each pair, in the lane of the first
+            let next = words.get(i + 1).map_or(0, |next| next << (BITS - 2));
+            let second = word >> 2 | next;
+            for (first, row) in counts.iter_mut().enumerate() {
+                let first = lanes::matches(word, first as u8) & valid;
+                if first == 0 {
+                    continue;
+                }
+                for (code, count) in row.iter_mut().enumerate() {
+                    *count += (first & lanes::matches(second, code as u8)).count_ones() as u64;
+                }
+            }
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test::random_ascii;
+    use crate::{Base, Sequence};
+
+    #[test]
+    fn composition() {
+        let s = random_ascii(300, 0);
+        for n in [0, 1, 2, 31, 32, 33, 64, 65, 300] {
+            let seq = Sequence::<u8>::try_from(&s[..n]).unwrap();
+            let bases: Vec<Base> = seq.bases().collect();
+
+            let mut expected = [0; 4];
+            for &base in &bases {
+                expected[base as usize] += 1;
+            }
+            assert_eq!(seq.composition(), expected, "n = {n}");
+            assert_eq!(
+                seq.gc_count(),
+                expected[Base::C as usize] + expected[Base::G as usize]
+            );
+
+            let mut expected = [[0; 4]; 4];
+            for pair in bases.windows(2) {
+                expected[pair[0] as usize][pair[1] as usize] += 1;
+            }
+            assert_eq!(seq.dinucleotide_counts(), expected, "n = {n}");
+        }
+
+        assert_eq!(Sequence::<u8>::new().gc_content(), 0.0);
+    }
+}
@@ -0,0 +1,118 @@
+use core::iter::FusedIterator;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::Bases;
+use crate::small::Kmer;
+
+/// An iterator over every `step`-th k-mer of a sequence.
+///
+/// Created with [`Sequence::kmers_step`](super::Sequence::kmers_step).
+#[derive(Debug)]
+pub struct StepKmerIter<'a, const K: usize, B: BitStore> {
+    bases: Bases<'a, B>,
+    step: usize,
+    /// The index of the next k-mer to yield.
+    pos: usize,
+    kmer: Kmer<K>,
+}
+
+impl<'a, const K: usize, B: BitStore> StepKmerIter<'a, K, B> {
+    pub(super) fn new(bits: &'a BitSlice<B, Lsb0>, step: usize) -> Self {
+        assert!(step > 0, "step must be non-zero");
+        let mut bases = Bases::new(bits);
+        let mut kmer = Kmer::new();
+        if bases.len() < K {
+            bases.nth(K);
+        }
+        // the first k-mer shares no bases with a previous one, so prime
+        // all but the `min(step, K)` bases that `next` reads
+        for base in bases.by_ref().take(K - step.min(K)) {
+            kmer.push(base);
+        }
+        Self {
+            bases,
+            step,
+            pos: 0,
+            kmer,
+        }
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> Iterator for StepKmerIter<'a, K, B> {
+    type Item = (usize, Kmer<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step > K && self.pos > 0 {
+            // skip the bases that no yielded k-mer covers
+            self.bases.nth(self.step - K - 1)?;
+        }
+        let fresh = self.step.min(K);
+        if self.bases.len() < fresh {
+            self.bases.nth(fresh);
+            return None;
+        }
+        for base in self.bases.by_ref().take(fresh) {
+            self.kmer.push(base);
+        }
+        self.pos += self.step;
+        Some((self.pos - self.step, self.kmer))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, const K: usize, B: BitStore> FusedIterator for StepKmerIter<'a, K, B> {}
+
+impl<'a, const K: usize, B: BitStore> ExactSizeIterator for StepKmerIter<'a, K, B> {
+    fn len(&self) -> usize {
+        let fresh = self.step.min(K);
+        let skip = if self.pos > 0 { self.step - fresh } else { 0 };
+        match self.bases.len().checked_sub(skip + fresh) {
+            Some(rest) => rest / self.step + 1,
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sequence;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn kmers_step() {
+        let s = random_ascii(150, 0);
+        let seq = Sequence::<u32>::try_from(s.as_str()).unwrap();
+
+        for step in [1, 2, 3, 7, 8, 9, 40, 143, 144, 200] {
+            let expected: Vec<(usize, String)> = (0..=s.len() - 8)
+                .step_by(step)
+                .map(|i| (i, s[i..i + 8].to_string()))
+                .collect();
+            let mut iter = seq.kmers_step::<8>(step);
+            assert_eq!(iter.len(), expected.len(), "step {step}");
+            let mut kmers = Vec::new();
+            while let Some((i, kmer)) = iter.next() {
+                kmers.push((i, kmer.to_string()));
+                assert_eq!(iter.len(), expected.len() - kmers.len(), "step {step}");
+            }
+            assert_eq!(kmers, expected, "step {step}");
+        }
+
+        let short = Sequence::<u8>::try_from("GATTACA").unwrap();
+        assert_eq!(short.kmers_step::<8>(1).len(), 0);
+        assert!(short.kmers_step::<8>(1).next().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_step() {
+        Sequence::<u8>::new().kmers_step::<4>(0);
+    }
+}
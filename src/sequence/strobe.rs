@@ -0,0 +1,211 @@
+use alloc::collections::VecDeque;
+use core::iter::FusedIterator;
+use core::ops::RangeInclusive;
+
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::store::BitStore;
+
+use super::HashedKmerIter;
+use crate::small::Kmer;
+
+/// How each strobe after the first is chosen from its window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StrobeKind {
+    /// The strobe with the smallest hash, independent of earlier strobes.
+    Minstrobe,
+    /// The strobe whose hash, XORed with the combined hash of the earlier
+    /// strobes, is smallest.
+    Randstrobe,
+}
+
+/// A seed made of `N` strobes of `L` bases each, spread over a window of
+/// the sequence.
+#[derive(Debug, Clone, Copy)]
+pub struct Strobemer<const L: usize, const N: usize> {
+    positions: [usize; N],
+    strobes: [Kmer<L>; N],
+    hashes: [u64; N],
+}
+
+impl<const L: usize, const N: usize> Strobemer<L, N> {
+    /// Returns the index of the first base of the first strobe, which
+    /// anchors the strobemer.
+    pub fn position(&self) -> usize {
+        self.positions[0]
+    }
+
+    /// Returns the index of the first base of each strobe, in increasing
+    /// order.
+    pub fn positions(&self) -> [usize; N] {
+        self.positions
+    }
+
+    /// Returns the bases of each strobe.
+    pub fn strobes(&self) -> [Kmer<L>; N] {
+        self.strobes
+    }
+
+    /// Returns the ntHash value of each strobe.
+    pub fn strobe_hashes(&self) -> [u64; N] {
+        self.hashes
+    }
+
+    /// Returns a hash of the whole strobemer, which depends on the order of
+    /// the strobes.
+    pub fn hash(&self) -> u64 {
+        combine(&self.hashes)
+    }
+}
+
+/// Combines strobe hashes so that different orders give different hashes.
+fn combine(hashes: &[u64]) -> u64 {
+    hashes
+        .iter()
+        .fold(0, |acc: u64, &hash| acc.rotate_left(21) ^ hash)
+}
+
+/// An iterator over the strobemers of a sequence.
+///
+/// Created with [`Sequence::strobemers`](super::Sequence::strobemers).
+#[derive(Debug)]
+pub struct StrobemerIter<'a, const L: usize, const N: usize, B: BitStore> {
+    strobes: HashedKmerIter<'a, L, B>,
+    /// The strobes from the current anchor onwards.
+    window: VecDeque<(Kmer<L>, u64)>,
+    kind: StrobeKind,
+    /// The offsets from the start of each window of the first and last
+    /// candidate strobe.
+    offsets: RangeInclusive<usize>,
+    /// The index of the current anchor.
+    pos: usize,
+}
+
+impl<'a, const L: usize, const N: usize, B: BitStore> StrobemerIter<'a, L, N, B> {
+    pub(super) fn new(
+        bits: &'a BitSlice<B, Lsb0>,
+        kind: StrobeKind,
+        offsets: RangeInclusive<usize>,
+    ) -> Self {
+        assert!(N >= 2, "a strobemer needs at least two strobes");
+        assert!(
+            *offsets.start() > 0 && offsets.start() <= offsets.end(),
+            "window offsets must be a non-empty range starting after 0"
+        );
+        Self {
+            strobes: HashedKmerIter::new(bits),
+            window: VecDeque::with_capacity((N - 1) * offsets.end() + 1),
+            kind,
+            offsets,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a, const L: usize, const N: usize, B: BitStore> Iterator for StrobemerIter<'a, L, N, B> {
+    type Item = Strobemer<L, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (w_min, w_max) = (*self.offsets.start(), *self.offsets.end());
+        let span = (N - 1) * w_max + 1;
+        if !self.window.is_empty() {
+            self.window.pop_front();
+            self.pos += 1;
+        }
+        while self.window.len() < span {
+            self.window.push_back(self.strobes.next()?);
+        }
+
+        let mut offsets = [0; N];
+        let mut hashes = [self.window[0].1; N];
+        for j in 1..N {
+            // strobe `j` is chosen from its own window, as in Sahlin (2021)
+            let candidates = (j - 1) * w_max + w_min..=j * w_max;
+            let acc = combine(&hashes[..j]);
+            let best = candidates
+                .min_by_key(|&i| match self.kind {
+                    StrobeKind::Minstrobe => self.window[i].1,
+                    StrobeKind::Randstrobe => acc ^ self.window[i].1,
+                })
+                .unwrap();
+            offsets[j] = best;
+            hashes[j] = self.window[best].1;
+        }
+
+        Some(Strobemer {
+            positions: offsets.map(|offset| self.pos + offset),
+            strobes: offsets.map(|offset| self.window[offset].0),
+            hashes,
+        })
+    }
+}
+
+impl<'a, const L: usize, const N: usize, B: BitStore> FusedIterator for StrobemerIter<'a, L, N, B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sequence;
+    use crate::hash::nthash;
+    use crate::utils::test::random_ascii;
+
+    #[test]
+    fn minstrobes() {
+        let ascii = random_ascii(400, 0).into_bytes();
+        let seq = Sequence::<u32>::try_from_ascii(&ascii).unwrap();
+        let hashes: Vec<u64> = seq.kmer_hashes::<5>().collect();
+
+        let strobemers: Vec<_> = seq
+            .strobemers::<5, 3>(StrobeKind::Minstrobe, 3..=10)
+            .collect();
+        assert_eq!(strobemers.len(), hashes.len() - 20);
+        for (i, strobemer) in strobemers.iter().enumerate() {
+            let [a, b, c] = strobemer.positions();
+            assert_eq!(a, i);
+            assert!((i + 3..=i + 10).contains(&b));
+            assert!((i + 13..=i + 20).contains(&c));
+            assert_eq!(hashes[b], *hashes[i + 3..=i + 10].iter().min().unwrap());
+            assert_eq!(hashes[c], *hashes[i + 13..=i + 20].iter().min().unwrap());
+
+            for (&pos, strobe) in strobemer.positions().iter().zip(strobemer.strobes()) {
+                assert_eq!(strobe.to_string().as_bytes(), &ascii[pos..pos + 5]);
+                assert_eq!(nthash::hash(strobe.bases()), hashes[pos]);
+            }
+        }
+    }
+
+    #[test]
+    fn randstrobes() {
+        let seq = Sequence::<u64>::try_from_ascii(random_ascii(400, 0).as_bytes()).unwrap();
+        let hashes: Vec<u64> = seq.kmer_hashes::<8>().collect();
+        for strobemer in seq.strobemers::<8, 2>(StrobeKind::Randstrobe, 5..=12) {
+            let [a, b] = strobemer.positions();
+            let best = (a + 5..=a + 12)
+                .min_by_key(|&i| hashes[a] ^ hashes[i])
+                .unwrap();
+            assert_eq!(b, best);
+            assert_eq!(strobemer.hash(), hashes[a].rotate_left(21) ^ hashes[b]);
+        }
+    }
+
+    #[test]
+    fn short() {
+        let seq = Sequence::<u8>::try_from("GATTACAGATTACA").unwrap();
+        assert_eq!(
+            seq.strobemers::<4, 2>(StrobeKind::Minstrobe, 1..=10)
+                .count(),
+            1
+        );
+        assert_eq!(
+            seq.strobemers::<4, 2>(StrobeKind::Minstrobe, 1..=11)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_window() {
+        Sequence::<u8>::new().strobemers::<4, 2>(StrobeKind::Minstrobe, 0..=3);
+    }
+}
@@ -0,0 +1,329 @@
+//! Sets of k-mers.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::set::KmerSet;
+//! use helicase::small::Kmer;
+//! use helicase::Base::*;
+//!
+//! let a: KmerSet<2> = [[G, A], [A, T]].map(Kmer::from_bases).into_iter().collect();
+//! let b: KmerSet<2> = [[A, T], [T, T]].map(Kmer::from_bases).into_iter().collect();
+//! assert_eq!(a.union(&b).len(), 3);
+//! assert_eq!(a.jaccard(&b), 1.0 / 3.0);
+//! ```
+
+use crate::small::Kmer;
+use crate::utils;
+use alloc::vec::Vec;
+
+/// A set of k-mers, stored as a sorted array.
+///
+/// Set operations merge the arrays of both sets in linear time. A canonical
+/// set stores each k-mer under its canonical form, so that it holds the same
+/// k-mers as the reverse complement of its sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct KmerSet<const K: usize> {
+    /// The masked value of each k-mer, in increasing order.
+    kmers: Vec<u64>,
+    canonical: bool,
+}
+
+impl<const K: usize> KmerSet<K> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty set of canonical k-mers.
+    pub fn canonical() -> Self {
+        Self {
+            kmers: Vec::new(),
+            canonical: true,
+        }
+    }
+
+    /// Creates a set from masked k-mer values in any order.
+    fn from_values(mut kmers: Vec<u64>, canonical: bool) -> Self {
+        kmers.sort_unstable();
+        kmers.dedup();
+        Self { kmers, canonical }
+    }
+
+    /// Creates the set of k-mers in a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::set::KmerSet;
+    /// use helicase::Sequence;
+    ///
+    /// let a: Sequence = "GATTACA".try_into().unwrap();
+    /// let b: Sequence = "TTACAGG".try_into().unwrap();
+    /// let a = KmerSet::<4>::from_sequence(&a);
+    /// let b = KmerSet::<4>::from_sequence(&b);
+    /// assert_eq!(a.intersection(&b).len(), 2);
+    /// assert_eq!(a.jaccard(&b), 2.0 / 6.0);
+    /// ```
+    #[cfg(feature = "bitvec")]
+    pub fn from_sequence<B: bitvec::store::BitStore>(seq: &crate::Sequence<B>) -> Self {
+        Self::from_values(seq.kmers::<K>().map(|k| k.as_masked()).collect(), false)
+    }
+
+    /// Creates the set of canonical k-mers in a sequence.
+    #[cfg(feature = "bitvec")]
+    pub fn canonical_from_sequence<B: bitvec::store::BitStore>(seq: &crate::Sequence<B>) -> Self {
+        Self::from_values(
+            seq.canonical_kmers::<K>().map(|k| k.as_masked()).collect(),
+            true,
+        )
+    }
+
+    /// Returns `true` if the set stores canonical k-mers.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Returns the value under which `kmer` is stored.
+    fn key(&self, kmer: Kmer<K>) -> u64 {
+        if self.canonical {
+            kmer.canonical().as_masked()
+        } else {
+            kmer.as_masked()
+        }
+    }
+
+    /// Returns the number of k-mers in the set.
+    pub fn len(&self) -> usize {
+        self.kmers.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.kmers.is_empty()
+    }
+
+    /// Returns `true` if the set contains `kmer`, or for a canonical set,
+    /// its reverse complement.
+    pub fn contains(&self, kmer: Kmer<K>) -> bool {
+        self.kmers.binary_search(&self.key(kmer)).is_ok()
+    }
+
+    /// Adds a k-mer to the set, and returns `true` if it was not already
+    /// present.
+    ///
+    /// Each insertion takes linear time; build large sets in bulk with
+    /// [`collect`](Iterator::collect) or
+    /// [`from_sequence`](Self::from_sequence) instead.
+    pub fn insert(&mut self, kmer: Kmer<K>) -> bool {
+        let key = self.key(kmer);
+        match self.kmers.binary_search(&key) {
+            Ok(_) => false,
+            Err(i) => {
+                self.kmers.insert(i, key);
+                true
+            }
+        }
+    }
+
+    /// Returns an iterator over the k-mers in the set, in increasing order
+    /// of their 2-bit encoding.
+    pub fn iter(&self) -> impl Iterator<Item = Kmer<K>> + '_ {
+        self.kmers.iter().map(|&kmer| Kmer::from(kmer))
+    }
+
+    /// Merges two sorted sets, keeping the values for which `keep` returns
+    /// `true` given whether they are in `self` and in `other`.
+    fn merge(&self, other: &Self, keep: impl Fn(bool, bool) -> bool) -> Self {
+        assert_eq!(
+            self.canonical, other.canonical,
+            "cannot combine canonical and non-canonical sets"
+        );
+        let kmers = utils::merge_sorted(&self.kmers, &other.kmers)
+            .filter(|&(_, in_self, in_other)| keep(in_self, in_other))
+            .map(|(kmer, _, _)| kmer)
+            .collect();
+        Self {
+            kmers,
+            canonical: self.canonical,
+        }
+    }
+
+    /// Returns the k-mers in either set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if only one of the sets is canonical, as do the other set
+    /// operations.
+    pub fn union(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a || b)
+    }
+
+    /// Returns the k-mers in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a && b)
+    }
+
+    /// Returns the k-mers in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a && !b)
+    }
+
+    /// Returns the k-mers in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a != b)
+    }
+
+    /// Returns the Jaccard index of the two sets: the size of their
+    /// intersection over the size of their union.
+    ///
+    /// Returns 0 if both sets are empty.
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        let shared = self.intersection(other).len();
+        let total = self.len() + other.len() - shared;
+        if total == 0 {
+            0.0
+        } else {
+            shared as f64 / total as f64
+        }
+    }
+}
+
+impl<const K: usize> FromIterator<Kmer<K>> for KmerSet<K> {
+    fn from_iter<T: IntoIterator<Item = Kmer<K>>>(iter: T) -> Self {
+        Self::from_values(iter.into_iter().map(|k| k.as_masked()).collect(), false)
+    }
+}
+
+impl<const K: usize> Extend<Kmer<K>> for KmerSet<K> {
+    fn extend<T: IntoIterator<Item = Kmer<K>>>(&mut self, iter: T) {
+        let canonical = self.canonical;
+        let mut kmers = core::mem::take(&mut self.kmers);
+        kmers.extend(iter.into_iter().map(|kmer| self.key(kmer)));
+        *self = Self::from_values(kmers, canonical);
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const K: usize> ArchivedKmerSet<K> {
+    /// Returns `true` if the set stores canonical k-mers.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Returns the number of k-mers in the set.
+    pub fn len(&self) -> usize {
+        self.kmers.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.kmers.is_empty()
+    }
+
+    /// Returns `true` if the set contains `kmer`, or for a canonical set,
+    /// its reverse complement, without deserializing the set.
+    pub fn contains(&self, kmer: Kmer<K>) -> bool {
+        let key = if self.canonical {
+            kmer.canonical().as_masked()
+        } else {
+            kmer.as_masked()
+        };
+        self.kmers
+            .binary_search_by_key(&key, |kmer| kmer.to_native())
+            .is_ok()
+    }
+
+    /// Returns an iterator over the k-mers in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = Kmer<K>> + '_ {
+        self.kmers.iter().map(|kmer| Kmer::from(kmer.to_native()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Base;
+
+    fn set(kmers: &[&str]) -> KmerSet<3> {
+        kmers
+            .iter()
+            .map(|s| s.parse::<Kmer<3>>().unwrap())
+            .collect()
+    }
+
+    fn strings(set: &KmerSet<3>) -> Vec<String> {
+        set.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn algebra() {
+        let a = set(&["GAT", "ATT", "TTA", "GAT"]);
+        let b = set(&["TTA", "TAC", "GAT"]);
+        assert_eq!(a.len(), 3);
+        assert_eq!(strings(&a.union(&b)), ["ATT", "TAC", "TTA", "GAT"]);
+        assert_eq!(strings(&a.intersection(&b)), ["TTA", "GAT"]);
+        assert_eq!(strings(&a.difference(&b)), ["ATT"]);
+        assert_eq!(strings(&a.symmetric_difference(&b)), ["ATT", "TAC"]);
+        assert_eq!(a.jaccard(&b), 0.5);
+        assert_eq!(KmerSet::<3>::new().jaccard(&KmerSet::new()), 0.0);
+    }
+
+    #[test]
+    fn insert() {
+        let mut set = KmerSet::<3>::canonical();
+        let gat = Kmer::from_bases([Base::G, Base::A, Base::T]);
+        assert!(set.insert(gat));
+        assert!(!set.insert(gat.reverse_complement()));
+        assert!(set.contains(gat.reverse_complement()));
+        set.extend([gat, Kmer::from_bases([Base::C; 3])]);
+        assert_eq!(set.len(), 2);
+        assert!(set.is_canonical());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mixed_canonical() {
+        KmerSet::<3>::new().union(&KmerSet::canonical());
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn archive() {
+        let set = set(&["GAT", "ATT", "TTA"]);
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&set).unwrap();
+        let archived = rkyv::access::<ArchivedKmerSet<3>, rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), 3);
+        assert!(!archived.is_canonical());
+        assert!(archived.contains(Kmer::from_bases([Base::T, Base::T, Base::A])));
+        assert!(!archived.contains(Kmer::from_bases([Base::T, Base::A, Base::A])));
+        assert!(
+            archived
+                .iter()
+                .map(|k| k.as_masked())
+                .eq(set.iter().map(|k| k.as_masked()))
+        );
+
+        let deserialized: KmerSet<3> =
+            rkyv::deserialize::<_, rkyv::rancor::Error>(archived).unwrap();
+        assert_eq!(deserialized, set);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn from_sequence() {
+        let seq = crate::Sequence::<u8>::try_from("GATTACAGATTACA").unwrap();
+        let set = KmerSet::<3>::from_sequence(&seq);
+        assert_eq!(set.len(), 7);
+        let rc = KmerSet::<3>::from_sequence(&seq.to_reverse_complement());
+        assert!(set.jaccard(&rc) < 1.0);
+
+        let set = KmerSet::<3>::canonical_from_sequence(&seq);
+        let rc = KmerSet::<3>::canonical_from_sequence(&seq.to_reverse_complement());
+        assert_eq!(set, rc);
+    }
+}
@@ -0,0 +1,645 @@
+//! Sketches of the k-mer content of sequences.
+//!
+//! A [`MinHash`] sketch keeps a fixed number of the smallest hashes of a set
+//! of k-mers, and a [`FracMinHash`] sketch keeps a fixed fraction of them.
+//! Either way, the similarity of two sets can be estimated without
+//! comparing them in full. A [`MinimizerSketch`] keeps the hashes of the
+//! minimizers of a sequence, so that every window of it is represented.
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::sketch::MinHash;
+//!
+//! let hash = |i: u64| i.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+//! let mut a = MinHash::new(100);
+//! let mut b = MinHash::new(100);
+//! a.extend((0..1000).map(hash));
+//! b.extend((500..1500).map(hash));
+//! assert!(a.jaccard(&b) > 0.0 && a.jaccard(&b) < 1.0);
+//! ```
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::utils;
+
+#[cfg(feature = "bitvec")]
+use crate::hash::murmur3;
+
+/// The seed sourmash hashes k-mers with.
+#[cfg(feature = "bitvec")]
+const SOURMASH_SEED: u32 = 42;
+
+/// A bottom-s MinHash sketch: the `s` smallest distinct hashes of a set.
+///
+/// Hashes are usually of canonical k-mers, so that a sequence and its
+/// reverse complement have the same sketch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct MinHash {
+    /// The smallest hashes seen, in increasing order.
+    hashes: Vec<u64>,
+    size: usize,
+}
+
+impl MinHash {
+    /// Creates an empty sketch that keeps the `size` smallest hashes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "sketch size must be non-zero");
+        Self {
+            hashes: Vec::with_capacity(size),
+            size,
+        }
+    }
+
+    /// Returns the maximum number of hashes kept.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of hashes in the sketch.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if the sketch holds no hashes.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns `true` if the sketch holds as many hashes as it can keep.
+    pub fn is_full(&self) -> bool {
+        self.hashes.len() == self.size
+    }
+
+    /// Returns the hashes in the sketch, in increasing order.
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Adds a hash to the sketch, keeping it if it is among the smallest.
+    pub fn insert(&mut self, hash: u64) {
+        if self.is_full() && hash >= self.hashes[self.size - 1] {
+            return;
+        }
+        if let Err(i) = self.hashes.binary_search(&hash) {
+            if self.is_full() {
+                self.hashes.pop();
+            }
+            self.hashes.insert(i, hash);
+        }
+    }
+
+    /// Adds the canonical hash of every k-mer in a sequence to the sketch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sketch::MinHash;
+    /// use helicase::Sequence;
+    ///
+    /// let seq: Sequence = "GATTACAGATTACA".try_into().unwrap();
+    /// let mut forward = MinHash::new(4);
+    /// forward.add_sequence::<5, _>(&seq);
+    /// let mut reverse = MinHash::new(4);
+    /// reverse.add_sequence::<5, _>(&seq.to_reverse_complement());
+    /// assert_eq!(forward, reverse);
+    /// ```
+    #[cfg(feature = "bitvec")]
+    pub fn add_sequence<const K: usize, B: bitvec::store::BitStore>(
+        &mut self,
+        seq: &crate::Sequence<B>,
+    ) {
+        self.extend(seq.canonical_kmer_hashes::<K>());
+    }
+
+    /// Merges the hashes of `other` into this sketch, so that it sketches
+    /// the union of both sets.
+    ///
+    /// The merged sketch keeps the smaller of the two sizes.
+    pub fn merge(&mut self, other: &Self) {
+        self.size = self.size.min(other.size);
+        self.hashes = utils::merge_sorted(&self.hashes, &other.hashes)
+            .map(|(hash, _, _)| hash)
+            .take(self.size)
+            .collect();
+    }
+
+    /// Estimates the Jaccard index of the two sets: the size of their
+    /// intersection over the size of their union.
+    ///
+    /// Returns 0 if both sketches are empty.
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        let mut union = self.clone();
+        union.merge(other);
+        if union.is_empty() {
+            return 0.0;
+        }
+        let shared = union
+            .hashes
+            .iter()
+            .filter(|hash| {
+                self.hashes.binary_search(hash).is_ok() && other.hashes.binary_search(hash).is_ok()
+            })
+            .count();
+        shared as f64 / union.len() as f64
+    }
+
+    /// Estimates the fraction of this set that is contained in `other`.
+    ///
+    /// Only hashes that `other` would have kept are compared. Returns 0 if
+    /// this sketch is empty.
+    pub fn containment(&self, other: &Self) -> f64 {
+        let limit = match other.hashes.last() {
+            Some(&last) if other.is_full() => last,
+            _ => u64::MAX,
+        };
+        let candidates = self.hashes.iter().take_while(|&&hash| hash <= limit);
+        let (mut total, mut shared) = (0, 0);
+        for hash in candidates {
+            total += 1;
+            shared += usize::from(other.hashes.binary_search(hash).is_ok());
+        }
+        if total == 0 {
+            0.0
+        } else {
+            shared as f64 / total as f64
+        }
+    }
+
+    /// Estimates the [Mash distance] between the sets of `k`-mers of two
+    /// sequences: an estimate of their mutation rate.
+    ///
+    /// [Mash distance]: https://doi.org/10.1186/s13059-016-0997-x
+    #[cfg(feature = "std")]
+    pub fn mash_distance(&self, other: &Self, k: usize) -> f64 {
+        let jaccard = self.jaccard(other);
+        if jaccard == 0.0 {
+            return 1.0;
+        }
+        (-(2.0 * jaccard / (1.0 + jaccard)).ln() / k as f64).min(1.0)
+    }
+}
+
+impl Extend<u64> for MinHash {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for hash in iter {
+            self.insert(hash);
+        }
+    }
+}
+
+/// A FracMinHash (scaled) sketch: every hash of a set that is at most
+/// `u64::MAX / scale`.
+///
+/// The sketch keeps about one in `scale` hashes, so its size grows with the
+/// set, and sketches of sets of very different sizes can still be compared
+/// by containment.
+///
+/// Sketches built with [`add_sequence`](Self::add_sequence) hash k-mers the
+/// way sourmash does, and keep the same hashes as a sourmash DNA sketch with
+/// the same `k` and `scaled`.
+///
+/// # Examples
+///
+/// ```
+/// use helicase::sketch::FracMinHash;
+///
+/// let hash = |i: u64| i.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+/// let mut genome = FracMinHash::new(100);
+/// genome.extend((0..100_000).map(hash));
+/// let mut fragment = FracMinHash::new(100);
+/// fragment.extend((0..10_000).map(hash));
+/// assert_eq!(fragment.containment(&genome), 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct FracMinHash {
+    hashes: BTreeSet<u64>,
+    scale: u64,
+}
+
+impl FracMinHash {
+    /// Creates an empty sketch that keeps about one in `scale` hashes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is zero.
+    pub fn new(scale: u64) -> Self {
+        assert!(scale > 0, "scale must be non-zero");
+        Self {
+            hashes: BTreeSet::new(),
+            scale,
+        }
+    }
+
+    /// Returns the scale of the sketch.
+    pub fn scale(&self) -> u64 {
+        self.scale
+    }
+
+    /// Returns the largest hash the sketch keeps.
+    ///
+    /// This is `u64::MAX / scale`, computed in floating point as sourmash
+    /// does, so that both keep exactly the same hashes.
+    pub fn max_hash(&self) -> u64 {
+        match self.scale {
+            1 => u64::MAX,
+            scale => (u64::MAX as f64 / scale as f64) as u64,
+        }
+    }
+
+    /// Returns the number of hashes in the sketch.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if the sketch holds no hashes.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns an iterator over the hashes in the sketch, in increasing
+    /// order.
+    pub fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.hashes.iter().copied()
+    }
+
+    /// Adds a hash to the sketch if it is at most the
+    /// [maximum hash](Self::max_hash).
+    pub fn insert(&mut self, hash: u64) {
+        if hash <= self.max_hash() {
+            self.hashes.insert(hash);
+        }
+    }
+
+    /// Adds the hash of every canonical k-mer in a sequence to the sketch.
+    ///
+    /// As in sourmash, the canonical k-mer is the lesser of the k-mer and
+    /// its reverse complement as uppercase ASCII, and its hash is the low 64
+    /// bits of its [`murmur3`] hash with seed 42.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::sketch::FracMinHash;
+    /// use helicase::Sequence;
+    ///
+    /// let mut sketch = FracMinHash::new(1);
+    /// sketch.add_sequence::<3, _>(&Sequence::<u8>::try_from("CGT").unwrap());
+    /// // the hash of "ACG", as given by sourmash
+    /// assert!(sketch.hashes().eq([1731421407650554201]));
+    /// ```
+    #[cfg(feature = "bitvec")]
+    pub fn add_sequence<const K: usize, B: bitvec::store::BitStore>(
+        &mut self,
+        seq: &crate::Sequence<B>,
+    ) {
+        let forward = seq.to_ascii();
+        let reverse = seq.to_reverse_complement().to_ascii();
+        let len = forward.len();
+        for (i, kmer) in forward.windows(K).enumerate() {
+            let rc = &reverse[len - i - K..len - i];
+            self.insert(murmur3::hash_x64_128(kmer.min(rc), SOURMASH_SEED).0);
+        }
+    }
+
+    /// Merges the hashes of `other` into this sketch, so that it sketches
+    /// the union of both sets.
+    ///
+    /// The merged sketch keeps the larger of the two scales.
+    pub fn merge(&mut self, other: &Self) {
+        self.scale = self.scale.max(other.scale);
+        let max_hash = self.max_hash();
+        self.hashes.retain(|&hash| hash <= max_hash);
+        self.hashes.extend(other.hashes.range(..=max_hash).copied());
+    }
+
+    /// Returns the number of hashes in both sketches, and in either, up to
+    /// the maximum hash of the coarser one.
+    fn overlap(&self, other: &Self) -> (usize, usize) {
+        let max_hash = self.max_hash().min(other.max_hash());
+        let a = self.hashes.range(..=max_hash);
+        let shared = a.clone().filter(|hash| other.hashes.contains(hash)).count();
+        let total = a.count() + other.hashes.range(..=max_hash).count() - shared;
+        (shared, total)
+    }
+
+    /// Estimates the Jaccard index of the two sets.
+    ///
+    /// Returns 0 if both sketches are empty.
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        match self.overlap(other) {
+            (_, 0) => 0.0,
+            (shared, total) => shared as f64 / total as f64,
+        }
+    }
+
+    /// Estimates the fraction of this set that is contained in `other`.
+    ///
+    /// Returns 0 if this sketch is empty.
+    pub fn containment(&self, other: &Self) -> f64 {
+        let max_hash = self.max_hash().min(other.max_hash());
+        let mut total = 0;
+        let mut shared = 0;
+        for hash in self.hashes.range(..=max_hash) {
+            total += 1;
+            shared += usize::from(other.hashes.contains(hash));
+        }
+        if total == 0 {
+            0.0
+        } else {
+            shared as f64 / total as f64
+        }
+    }
+
+    /// Estimates the average nucleotide identity (ANI) of the part of this
+    /// sequence that is contained in `other`, from the containment of their
+    /// sets of `k`-mers.
+    ///
+    /// This is the point estimate `containment^(1/k)`, which assumes that
+    /// mutations are independent and evenly spread.
+    #[cfg(feature = "std")]
+    pub fn containment_ani(&self, other: &Self, k: usize) -> f64 {
+        self.containment(other).powf(1.0 / k as f64)
+    }
+}
+
+impl Extend<u64> for FracMinHash {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for hash in iter {
+            self.insert(hash);
+        }
+    }
+}
+
+/// A winnowed fingerprint of a sequence: the distinct hashes of its
+/// minimizers, in increasing order.
+///
+/// Created with
+#[cfg_attr(
+    feature = "bitvec",
+    doc = "[`Sequence::sketch_minimizers`](crate::Sequence::sketch_minimizers)"
+)]
+#[cfg_attr(not(feature = "bitvec"), doc = "`Sequence::sketch_minimizers`")]
+/// or collected from any hashes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct MinimizerSketch {
+    hashes: Vec<u64>,
+}
+
+impl MinimizerSketch {
+    /// Returns the number of distinct hashes in the sketch.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if the sketch holds no hashes.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the hashes in the sketch, in increasing order.
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Returns `true` if the sketch holds `hash`.
+    pub fn contains(&self, hash: u64) -> bool {
+        self.hashes.binary_search(&hash).is_ok()
+    }
+
+    /// Returns the number of hashes in both sketches.
+    fn shared(&self, other: &Self) -> usize {
+        utils::merge_sorted(&self.hashes, &other.hashes)
+            .filter(|&(_, in_self, in_other)| in_self && in_other)
+            .count()
+    }
+
+    /// Merges two sketches, keeping the hashes for which `keep` returns
+    /// `true` given whether they are in `self` and in `other`.
+    fn merge(&self, other: &Self, keep: impl Fn(bool, bool) -> bool) -> Self {
+        let hashes = utils::merge_sorted(&self.hashes, &other.hashes)
+            .filter(|&(_, in_self, in_other)| keep(in_self, in_other))
+            .map(|(hash, _, _)| hash)
+            .collect();
+        Self { hashes }
+    }
+
+    /// Returns the hashes in either sketch.
+    pub fn union(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a || b)
+    }
+
+    /// Returns the hashes in both sketches.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a && b)
+    }
+
+    /// Returns the hashes in this sketch but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.merge(other, |a, b| a && !b)
+    }
+
+    /// Returns the Jaccard index of the two sketches.
+    ///
+    /// Returns 0 if both sketches are empty.
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        let shared = self.shared(other);
+        match self.len() + other.len() - shared {
+            0 => 0.0,
+            total => shared as f64 / total as f64,
+        }
+    }
+
+    /// Returns the fraction of this sketch's hashes that are in `other`.
+    ///
+    /// Returns 0 if this sketch is empty.
+    pub fn containment(&self, other: &Self) -> f64 {
+        match self.len() {
+            0 => 0.0,
+            len => self.shared(other) as f64 / len as f64,
+        }
+    }
+}
+
+impl FromIterator<u64> for MinimizerSketch {
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut hashes: Vec<u64> = iter.into_iter().collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        Self { hashes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(i: u64) -> u64 {
+        i.wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(23)
+    }
+
+    fn sketch(range: core::ops::Range<u64>, size: usize) -> MinHash {
+        let mut sketch = MinHash::new(size);
+        sketch.extend(range.map(hash));
+        sketch
+    }
+
+    #[test]
+    fn bottom_s() {
+        let mut sketch = MinHash::new(3);
+        sketch.extend([9, 4, 7, 4, 1, 8]);
+        assert_eq!(sketch.hashes(), [1, 4, 7]);
+        assert!(sketch.is_full());
+        sketch.insert(7);
+        sketch.insert(0);
+        assert_eq!(sketch.hashes(), [0, 1, 4]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = MinHash::new(4);
+        a.extend([1, 3, 5, 7]);
+        let mut b = MinHash::new(3);
+        b.extend([2, 3, 8]);
+        a.merge(&b);
+        assert_eq!((a.size(), a.hashes()), (3, &[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn similarity() {
+        let a = sketch(0..10_000, 1000);
+        let b = sketch(5000..15_000, 1000);
+        // the true Jaccard index is 1/3
+        assert!((a.jaccard(&b) - 1.0 / 3.0).abs() < 0.05);
+        assert_eq!(a.jaccard(&a), 1.0);
+        assert_eq!(a.jaccard(&sketch(20_000..30_000, 1000)), 0.0);
+        assert_eq!(MinHash::new(5).jaccard(&MinHash::new(5)), 0.0);
+
+        // half of `a` is in `b`, and all of `c` is in `a`
+        assert!((a.containment(&b) - 0.5).abs() < 0.05);
+        let c = sketch(0..2000, 1000);
+        assert!(c.containment(&a) > 0.95);
+
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(a.mash_distance(&a, 21), 0.0);
+            assert!(a.mash_distance(&b, 21) > 0.0);
+        }
+    }
+
+    #[test]
+    fn frac_min_hash() {
+        let mut a = FracMinHash::new(10);
+        a.extend((0..20_000).map(hash));
+        assert!((1800..2200).contains(&a.len()));
+        assert!(a.hashes().all(|hash| hash <= a.max_hash()));
+
+        let mut b = FracMinHash::new(10);
+        b.extend((10_000..30_000).map(hash));
+        assert!((a.jaccard(&b) - 1.0 / 3.0).abs() < 0.05);
+        assert!((a.containment(&b) - 0.5).abs() < 0.05);
+        assert_eq!(a.containment(&a), 1.0);
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(a.containment_ani(&a, 21), 1.0);
+            assert!(a.containment_ani(&b, 21) > 0.95);
+        }
+
+        // a coarser sketch is compared on the hashes both keep
+        let mut coarse = FracMinHash::new(100);
+        coarse.extend((0..20_000).map(hash));
+        assert_eq!(coarse.containment(&a), 1.0);
+        assert_eq!(a.containment(&coarse), 1.0);
+
+        a.merge(&coarse);
+        assert_eq!(a, coarse);
+        assert_eq!(FracMinHash::new(1).jaccard(&FracMinHash::new(1)), 0.0);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn frac_min_hash_sequence() {
+        let s = crate::utils::test::random_ascii(5000, 0);
+        let seq = crate::Sequence::<u64>::try_from(s.as_str()).unwrap();
+        let mut forward = FracMinHash::new(4);
+        forward.add_sequence::<21, _>(&seq);
+        let mut reverse = FracMinHash::new(4);
+        reverse.add_sequence::<21, _>(&seq.to_reverse_complement());
+        assert!(!forward.is_empty());
+        assert_eq!(forward, reverse);
+    }
+
+    #[test]
+    fn frac_min_hash_sourmash() {
+        // the max_hash sourmash writes in signatures with scaled=1000
+        assert_eq!(FracMinHash::new(1000).max_hash(), 18_446_744_073_709_552);
+        assert_eq!(FracMinHash::new(1).max_hash(), u64::MAX);
+
+        #[cfg(feature = "bitvec")]
+        {
+            let mut sketch = FracMinHash::new(1);
+            sketch.add_sequence::<3, _>(&crate::Sequence::<u8>::try_from("ACG").unwrap());
+            // sourmash's `hash_murmur("ACG")`
+            assert!(sketch.hashes().eq([1_731_421_407_650_554_201]));
+        }
+    }
+
+    #[test]
+    fn minimizer_sketch() {
+        let a: MinimizerSketch = [5, 1, 3, 5, 9].into_iter().collect();
+        let b: MinimizerSketch = [3, 4, 5].into_iter().collect();
+        assert_eq!(a.hashes(), [1, 3, 5, 9]);
+        assert!(a.contains(9) && !a.contains(4));
+        assert_eq!(a.union(&b).hashes(), [1, 3, 4, 5, 9]);
+        assert_eq!(a.intersection(&b).hashes(), [3, 5]);
+        assert_eq!(a.difference(&b).hashes(), [1, 9]);
+        assert_eq!(a.jaccard(&b), 0.4);
+        assert_eq!(b.containment(&a), 2.0 / 3.0);
+        assert_eq!(MinimizerSketch::default().containment(&a), 0.0);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn archive() {
+        use rkyv::rancor::Error;
+
+        let min_hash = sketch(0..1000, 10);
+        let bytes = rkyv::to_bytes::<Error>(&min_hash).unwrap();
+        assert_eq!(
+            rkyv::from_bytes::<MinHash, Error>(&bytes).unwrap(),
+            min_hash
+        );
+
+        let mut frac = FracMinHash::new(10);
+        frac.extend((0..1000).map(hash));
+        let bytes = rkyv::to_bytes::<Error>(&frac).unwrap();
+        assert_eq!(
+            rkyv::from_bytes::<FracMinHash, Error>(&bytes).unwrap(),
+            frac
+        );
+
+        let minimizers: MinimizerSketch = [5, 1, 3].into_iter().collect();
+        let bytes = rkyv::to_bytes::<Error>(&minimizers).unwrap();
+        assert_eq!(
+            rkyv::from_bytes::<MinimizerSketch, Error>(&bytes).unwrap(),
+            minimizers
+        );
+    }
+}
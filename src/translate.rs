@@ -0,0 +1,377 @@
+//! Translation of DNA into protein.
+//!
+//! Codons are [`small::Kmer<3>`](crate::small::Kmer) values, translated with
+//! one of the [NCBI genetic codes][ncbi] provided as a [`CodonTable`].
+//!
+//! # Example
+//!
+//! ```
+//! use helicase::small::Kmer;
+//! use helicase::translate::{AminoAcid, CodonTable};
+//! use helicase::Base;
+//!
+//! let codon = Kmer::from_bases([Base::T, Base::G, Base::A]);
+//! assert_eq!(CodonTable::STANDARD.translate(codon), AminoAcid::Stop);
+//! assert_eq!(CodonTable::VERTEBRATE_MITOCHONDRIAL.translate(codon), AminoAcid::Trp);
+//! ```
+//!
+//! [ncbi]: https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi
+
+use core::fmt::Display;
+
+use crate::small::Kmer;
+
+/// An amino acid, or the end of translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AminoAcid {
+    /// Alanine (`A`).
+    Ala,
+    /// Arginine (`R`).
+    Arg,
+    /// Asparagine (`N`).
+    Asn,
+    /// Aspartic acid (`D`).
+    Asp,
+    /// Cysteine (`C`).
+    Cys,
+    /// Glutamine (`Q`).
+    Gln,
+    /// Glutamic acid (`E`).
+    Glu,
+    /// Glycine (`G`).
+    Gly,
+    /// Histidine (`H`).
+    His,
+    /// Isoleucine (`I`).
+    Ile,
+    /// Leucine (`L`).
+    Leu,
+    /// Lysine (`K`).
+    Lys,
+    /// Methionine (`M`).
+    Met,
+    /// Phenylalanine (`F`).
+    Phe,
+    /// Proline (`P`).
+    Pro,
+    /// Serine (`S`).
+    Ser,
+    /// Threonine (`T`).
+    Thr,
+    /// Tryptophan (`W`).
+    Trp,
+    /// Tyrosine (`Y`).
+    Tyr,
+    /// Valine (`V`).
+    Val,
+    /// A stop codon (`*`).
+    Stop,
+}
+
+impl Display for AminoAcid {
+    /// Formats the amino acid as its one-letter code.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Write::write_char(f, self.to_ascii() as char)
+    }
+}
+
+impl AminoAcid {
+    /// Converts a one-letter code, or `*` for a stop, to an amino acid.
+    ///
+    /// Returns `None` if the character is not a valid code.
+    pub const fn from_ascii(value: u8) -> Option<Self> {
+        use AminoAcid::*;
+        Some(match value.to_ascii_uppercase() {
+            b'A' => Ala,
+            b'R' => Arg,
+            b'N' => Asn,
+            b'D' => Asp,
+            b'C' => Cys,
+            b'Q' => Gln,
+            b'E' => Glu,
+            b'G' => Gly,
+            b'H' => His,
+            b'I' => Ile,
+            b'L' => Leu,
+            b'K' => Lys,
+            b'M' => Met,
+            b'F' => Phe,
+            b'P' => Pro,
+            b'S' => Ser,
+            b'T' => Thr,
+            b'W' => Trp,
+            b'Y' => Tyr,
+            b'V' => Val,
+            b'*' => Stop,
+            _ => return None,
+        })
+    }
+
+    /// Converts the amino acid to its uppercase one-letter code, or `*` for a
+    /// stop.
+    pub const fn to_ascii(self) -> u8 {
+        use AminoAcid::*;
+        match self {
+            Ala => b'A',
+            Arg => b'R',
+            Asn => b'N',
+            Asp => b'D',
+            Cys => b'C',
+            Gln => b'Q',
+            Glu => b'E',
+            Gly => b'G',
+            His => b'H',
+            Ile => b'I',
+            Leu => b'L',
+            Lys => b'K',
+            Met => b'M',
+            Phe => b'F',
+            Pro => b'P',
+            Ser => b'S',
+            Thr => b'T',
+            Trp => b'W',
+            Tyr => b'Y',
+            Val => b'V',
+            Stop => b'*',
+        }
+    }
+}
+
+/// A genetic code, mapping each codon to an amino acid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodonTable {
+    id: u8,
+    name: &'static str,
+    /// Indexed by the 6-bit value of each codon.
+    amino_acids: [AminoAcid; 64],
+    /// Bit `i` is set if the codon with value `i` can start translation.
+    starts: u64,
+}
+
+/// The position of each base, by 2-bit code, in the `TCAG` order of NCBI
+/// tables.
+const NCBI_ORDER: [usize; 4] = [1, 2, 0, 3];
+
+/// Returns the index into an NCBI table of the codon with 6-bit value `i`.
+const fn ncbi_index(i: usize) -> usize {
+    NCBI_ORDER[i >> 4] * 16 + NCBI_ORDER[i >> 2 & 0b11] * 4 + NCBI_ORDER[i & 0b11]
+}
+
+impl CodonTable {
+    /// Builds a table from its NCBI `AAs` and `Starts` lines.
+    const fn from_ncbi(id: u8, name: &'static str, aas: &[u8; 64], starts: &[u8; 64]) -> Self {
+        let mut amino_acids = [AminoAcid::Stop; 64];
+        let mut start_mask = 0;
+        let mut i = 0;
+        while i < 64 {
+            amino_acids[i] = match AminoAcid::from_ascii(aas[ncbi_index(i)]) {
+                Some(aa) => aa,
+                None => panic!("invalid amino acid in codon table"),
+            };
+            if starts[ncbi_index(i)] == b'M' {
+                start_mask |= 1 << i;
+            }
+            i += 1;
+        }
+        Self {
+            id,
+            name,
+            amino_acids,
+            starts: start_mask,
+        }
+    }
+
+    /// The standard code (NCBI table 1).
+    pub const STANDARD: Self = Self::from_ncbi(
+        1,
+        "Standard",
+        b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        b"---M---------------M---------------M----------------------------",
+    );
+
+    /// The vertebrate mitochondrial code (NCBI table 2).
+    pub const VERTEBRATE_MITOCHONDRIAL: Self = Self::from_ncbi(
+        2,
+        "Vertebrate Mitochondrial",
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        b"--------------------------------MMMM---------------M------------",
+    );
+
+    /// The mold, protozoan and coelenterate mitochondrial code and the
+    /// mycoplasma/spiroplasma code (NCBI table 4).
+    pub const MOLD_MITOCHONDRIAL: Self = Self::from_ncbi(
+        4,
+        "Mold, Protozoan, and Coelenterate Mitochondrial and Mycoplasma/Spiroplasma",
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        b"--MM---------------M------------MMMM---------------M------------",
+    );
+
+    /// The invertebrate mitochondrial code (NCBI table 5).
+    pub const INVERTEBRATE_MITOCHONDRIAL: Self = Self::from_ncbi(
+        5,
+        "Invertebrate Mitochondrial",
+        b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        b"---M----------------------------MMMM---------------M------------",
+    );
+
+    /// The ciliate, dasycladacean and hexamita nuclear code (NCBI table 6).
+    pub const CILIATE_NUCLEAR: Self = Self::from_ncbi(
+        6,
+        "Ciliate, Dasycladacean and Hexamita Nuclear",
+        b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        b"-----------------------------------M----------------------------",
+    );
+
+    /// The bacterial, archaeal and plant plastid code (NCBI table 11).
+    pub const BACTERIAL: Self = Self::from_ncbi(
+        11,
+        "Bacterial, Archaeal and Plant Plastid",
+        b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        b"---M---------------M------------MMMM---------------M------------",
+    );
+
+    /// Returns the table with the given NCBI identifier, if it is provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::translate::CodonTable;
+    ///
+    /// assert_eq!(CodonTable::from_id(11).unwrap().name(), "Bacterial, Archaeal and Plant Plastid");
+    /// assert!(CodonTable::from_id(7).is_none());
+    /// ```
+    pub const fn from_id(id: u8) -> Option<&'static Self> {
+        Some(match id {
+            1 => &Self::STANDARD,
+            2 => &Self::VERTEBRATE_MITOCHONDRIAL,
+            4 => &Self::MOLD_MITOCHONDRIAL,
+            5 => &Self::INVERTEBRATE_MITOCHONDRIAL,
+            6 => &Self::CILIATE_NUCLEAR,
+            11 => &Self::BACTERIAL,
+            _ => return None,
+        })
+    }
+
+    /// Returns the NCBI identifier of the table.
+    pub const fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Returns the NCBI name of the table.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Translates a codon into an amino acid.
+    pub const fn translate(&self, codon: Kmer<3>) -> AminoAcid {
+        self.amino_acids[codon.as_masked() as usize]
+    }
+
+    /// Returns `true` if the codon can start translation.
+    pub const fn is_start(&self, codon: Kmer<3>) -> bool {
+        self.starts >> codon.as_masked() & 1 == 1
+    }
+}
+
+#[cfg(feature = "bitvec")]
+impl<B: bitvec::store::BitStore> crate::Sequence<B> {
+    /// Translates the sequence into protein, starting `frame` bases from the
+    /// start.
+    ///
+    /// Every complete codon is translated, including stops. For the frames
+    /// on the reverse strand, translate the
+    /// [reverse complement](Self::to_reverse_complement).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` is not 0, 1 or 2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use helicase::translate::CodonTable;
+    /// use helicase::Sequence;
+    ///
+    /// let seq = Sequence::<usize>::try_from("ATGGATTACATAGA").unwrap();
+    /// let protein: String = seq
+    ///     .translate(0, &CodonTable::STANDARD)
+    ///     .iter()
+    ///     .map(|aa| aa.to_ascii() as char)
+    ///     .collect();
+    /// assert_eq!(protein, "MDYI");
+    /// ```
+    pub fn translate(&self, frame: usize, table: &CodonTable) -> alloc::vec::Vec<AminoAcid> {
+        assert!(frame < 3, "frame must be 0, 1 or 2");
+        let mut bases = self.bases().skip(frame);
+        let mut protein = alloc::vec::Vec::with_capacity(self.len().saturating_sub(frame) / 3);
+        while let (Some(a), Some(b), Some(c)) = (bases.next(), bases.next(), bases.next()) {
+            protein.push(table.translate(Kmer::from_bases([a, b, c])));
+        }
+        protein
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Base;
+
+    fn codon(s: &[u8; 3]) -> Kmer<3> {
+        Kmer::from_bases(s.map(|b| Base::from_ascii(b).unwrap()))
+    }
+
+    #[test]
+    fn standard() {
+        let table = &CodonTable::STANDARD;
+        assert_eq!(table.translate(codon(b"ATG")), AminoAcid::Met);
+        assert_eq!(table.translate(codon(b"TTT")), AminoAcid::Phe);
+        assert_eq!(table.translate(codon(b"GGG")), AminoAcid::Gly);
+        assert_eq!(table.translate(codon(b"TGG")), AminoAcid::Trp);
+        assert_eq!(table.translate(codon(b"CAT")), AminoAcid::His);
+        for stop in [b"TAA", b"TAG", b"TGA"] {
+            assert_eq!(table.translate(codon(stop)), AminoAcid::Stop);
+        }
+        for start in [b"ATG", b"CTG", b"TTG"] {
+            assert!(table.is_start(codon(start)));
+        }
+        assert!(!table.is_start(codon(b"GTG")));
+    }
+
+    #[test]
+    fn alternative() {
+        let table = &CodonTable::VERTEBRATE_MITOCHONDRIAL;
+        assert_eq!(table.translate(codon(b"AGA")), AminoAcid::Stop);
+        assert_eq!(table.translate(codon(b"ATA")), AminoAcid::Met);
+        assert!(table.is_start(codon(b"ATT")));
+
+        let table = CodonTable::from_id(6).unwrap();
+        assert_eq!(table.translate(codon(b"TAA")), AminoAcid::Gln);
+        assert_eq!(table.translate(codon(b"TGA")), AminoAcid::Stop);
+    }
+
+    #[test]
+    fn amino_acid_ascii() {
+        for c in b"ARNDCQEGHILKMFPSTWYV*" {
+            let aa = AminoAcid::from_ascii(*c).unwrap();
+            assert_eq!(aa.to_ascii(), *c);
+            assert_eq!(aa.to_string(), (*c as char).to_string());
+        }
+        assert_eq!(AminoAcid::from_ascii(b'm'), Some(AminoAcid::Met));
+        assert_eq!(AminoAcid::from_ascii(b'X'), None);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn translate_sequence() {
+        let seq = crate::Sequence::<u8>::try_from("CATGGCCTAAG").unwrap();
+        let frames: Vec<String> = (0..3)
+            .map(|frame| {
+                seq.translate(frame, &CodonTable::STANDARD)
+                    .iter()
+                    .map(|aa| aa.to_string())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(frames, ["HGL", "MA*", "WPK"]);
+    }
+}
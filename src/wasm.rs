@@ -0,0 +1,252 @@
+//! JavaScript bindings with [wasm-bindgen](https://docs.rs/wasm-bindgen).
+//!
+//! The exported types wrap the rest of the crate without const generics:
+//! the k-mer length is an argument checked at runtime, and must be between
+//! 1 and 32. K-mers cross the boundary as their packed `u64` values (a
+//! `BigInt` in JavaScript), with the first base in the most significant
+//! bits, as returned by [`Kmer::as_masked`](crate::small::Kmer::as_masked).
+//!
+//! ```js
+//! import { KmerCounter, MinHash, Sequence } from "helicase";
+//!
+//! const seq = new Sequence("GATTACAGATTACA");
+//! const counter = new KmerCounter(3, true);
+//! counter.countSequence(seq);
+//! counter.get("GAT"); // 2n
+//!
+//! const sketch = new MinHash(1000, 21);
+//! sketch.addSequence(seq);
+//! sketch.jaccard(sketch); // 1
+//! ```
+//!
+//! The crate is only built as a library by default, so build the module as
+//! a `cdylib` and generate the JavaScript glue with
+//! [wasm-bindgen](https://rustwasm.github.io/docs/wasm-bindgen/):
+//!
+//! ```sh
+//! cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --out-dir pkg target/wasm32-unknown-unknown/release/helicase.wasm
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::bindings::{self, DynCounter, with_k};
+use crate::sketch;
+
+/// A DNA sequence, packed two bits per base.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    inner: crate::Sequence,
+}
+
+#[wasm_bindgen]
+impl Sequence {
+    /// Packs a sequence from ASCII, case-insensitively.
+    ///
+    /// Throws if the sequence contains a character other than `A`, `C`,
+    /// `G` or `T`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(ascii: &str) -> Result<Sequence, JsError> {
+        Ok(Self {
+            inner: crate::Sequence::try_from_ascii(ascii.as_bytes())?,
+        })
+    }
+
+    /// The number of bases in the sequence.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns the fraction of bases that are `G` or `C`.
+    #[wasm_bindgen(js_name = gcContent)]
+    pub fn gc_content(&self) -> f64 {
+        self.inner.gc_content()
+    }
+
+    /// Returns the reverse complement of the sequence.
+    #[wasm_bindgen(js_name = reverseComplement)]
+    pub fn reverse_complement(&self) -> Sequence {
+        Self {
+            inner: self.inner.to_reverse_complement(),
+        }
+    }
+
+    /// Returns the packed values of the `k`-mers of the sequence, in order.
+    ///
+    /// If `canonical` is `true`, each k-mer is replaced by the smaller of it
+    /// and its reverse complement.
+    pub fn kmers(&self, k: usize, canonical: bool) -> Result<Vec<u64>, JsError> {
+        Ok(with_k!(k, K => if canonical {
+            self.inner.canonical_kmers::<K>().map(|kmer| kmer.as_masked()).collect()
+        } else {
+            self.inner.kmers::<K>().map(|kmer| kmer.as_masked()).collect()
+        })?)
+    }
+
+    /// Returns the sequence as uppercase ASCII.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// Counts of the k-mers of one or more sequences.
+#[wasm_bindgen]
+pub struct KmerCounter {
+    inner: Box<dyn DynCounter>,
+    k: usize,
+}
+
+#[wasm_bindgen]
+impl KmerCounter {
+    /// Creates an empty counter of `k`-mers.
+    ///
+    /// If `canonical` is `true`, each k-mer is counted together with its
+    /// reverse complement. Throws if `k` is not between 1 and 32.
+    #[wasm_bindgen(constructor)]
+    pub fn new(k: usize, canonical: bool) -> Result<KmerCounter, JsError> {
+        let inner = bindings::new_counter(k, canonical)?;
+        Ok(Self { inner, k })
+    }
+
+    /// The length of the counted k-mers.
+    #[wasm_bindgen(getter)]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Counts every k-mer in a sequence.
+    #[wasm_bindgen(js_name = countSequence)]
+    pub fn count_sequence(&mut self, seq: &Sequence) {
+        self.inner.count_sequence(&seq.inner);
+    }
+
+    /// Returns the number of times a k-mer, given as ASCII, was counted.
+    ///
+    /// Throws if `kmer` is not `k` bases long.
+    pub fn get(&self, kmer: &str) -> Result<u64, JsError> {
+        self.inner
+            .get(kmer.as_bytes())
+            .ok_or_else(|| JsError::new(&format!("{kmer:?} is not a {}-mer", self.k)))
+    }
+
+    /// The number of distinct k-mers counted.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns the total number of occurrences counted.
+    pub fn total(&self) -> u64 {
+        self.inner.total()
+    }
+
+    /// Returns the packed values of the `n` most abundant k-mers, with ties
+    /// sorted by k-mer.
+    #[wasm_bindgen(js_name = mostCommon)]
+    pub fn most_common(&self, n: usize) -> Vec<u64> {
+        let counts = self.inner.sorted_by_count();
+        counts.into_iter().take(n).map(|(kmer, _)| kmer).collect()
+    }
+}
+
+/// A bottom-s MinHash sketch of the canonical k-mers of sequences.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct MinHash {
+    inner: sketch::MinHash,
+    k: usize,
+}
+
+#[wasm_bindgen]
+impl MinHash {
+    /// Creates an empty sketch of `k`-mers that keeps the `size` smallest
+    /// hashes.
+    ///
+    /// Throws if `size` is zero or `k` is not between 1 and 32.
+    #[wasm_bindgen(constructor)]
+    pub fn new(size: usize, k: usize) -> Result<MinHash, JsError> {
+        if size == 0 {
+            return Err(JsError::new("sketch size must be greater than 0"));
+        }
+        Ok(with_k!(k, K => Self {
+            inner: sketch::MinHash::new(size),
+            k: K,
+        })?)
+    }
+
+    /// Adds the canonical k-mers of a sequence to the sketch.
+    #[wasm_bindgen(js_name = addSequence)]
+    pub fn add_sequence(&mut self, seq: &Sequence) {
+        // `k` was checked when the sketch was created
+        let _ = with_k!(self.k, K => self.inner.add_sequence::<K, _>(&seq.inner));
+    }
+
+    /// Estimates the Jaccard index of the sets of k-mers of two sketches.
+    pub fn jaccard(&self, other: &MinHash) -> f64 {
+        self.inner.jaccard(&other.inner)
+    }
+
+    /// Estimates the fraction of the k-mers of this sketch that are in
+    /// `other`.
+    pub fn containment(&self, other: &MinHash) -> f64 {
+        self.inner.containment(&other.inner)
+    }
+
+    /// Estimates the Mash distance between the sequences of two sketches.
+    #[wasm_bindgen(js_name = mashDistance)]
+    pub fn mash_distance(&self, other: &MinHash) -> f64 {
+        self.inner.mash_distance(&other.inner, self.k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence() {
+        let seq = Sequence::new("gattaca").unwrap();
+        assert_eq!(seq.length(), 7);
+        assert_eq!(seq.to_js_string(), "GATTACA");
+        assert_eq!(seq.reverse_complement().to_js_string(), "TGTAATC");
+
+        let kmers = seq.kmers(5, false).unwrap();
+        let expected: Vec<u64> = seq
+            .inner
+            .kmers::<5>()
+            .map(|kmer| kmer.as_masked())
+            .collect();
+        assert_eq!(kmers, expected);
+        let canonical = seq.kmers(5, true).unwrap();
+        let mut rc = seq.reverse_complement().kmers(5, true).unwrap();
+        rc.reverse();
+        assert_eq!(canonical, rc);
+    }
+
+    #[test]
+    fn counter() {
+        let mut counter = KmerCounter::new(3, true).unwrap();
+        counter.count_sequence(&Sequence::new("GATTACAGATTACA").unwrap());
+        assert_eq!(counter.k(), 3);
+        assert_eq!(counter.total(), 12);
+        assert_eq!(counter.get("GAT").unwrap(), 2);
+        assert_eq!(counter.get("atc").unwrap(), 2);
+        assert_eq!(counter.most_common(1).len(), 1);
+        assert!(counter.size() <= 12);
+    }
+
+    #[test]
+    fn min_hash() {
+        let a = Sequence::new(&"GATTACA".repeat(20)).unwrap();
+        let mut forward = MinHash::new(10, 5).unwrap();
+        forward.add_sequence(&a);
+        let mut reverse = MinHash::new(10, 5).unwrap();
+        reverse.add_sequence(&a.reverse_complement());
+        assert_eq!(forward.jaccard(&reverse), 1.0);
+        assert_eq!(forward.containment(&reverse), 1.0);
+        assert_eq!(forward.mash_distance(&reverse), 0.0);
+    }
+}